@@ -0,0 +1,170 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+*/
+
+#![allow(dead_code)]
+
+use super::AeadError;
+use crate::crypto::{AES, Blocksize};
+
+pub const TAG_LENGTH: usize = 16;
+
+// x^128 + x^7 + x^2 + x + 1 in the reflected bit order of GCM
+const R: u128 = 0xe1 << 120;
+
+pub struct AesGcm {
+    key: Vec<u8>,
+    blocksize: Blocksize,
+}
+
+impl AesGcm {
+    pub fn new(key: &[u8]) -> Self {
+        // the key length selects AES-128, AES-192 or AES-256, AES::new rejects anything else
+        let blocksize: Blocksize = match key.len() {
+            24 => Blocksize::B192,
+            32 => Blocksize::B256,
+            _ => Blocksize::B128,
+        };
+
+        Self { key: key.to_vec(), blocksize }
+    }
+
+    pub fn encrypt(&self, nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        // ciphertext || tag, the counter of the key stream starts at 2
+        let mut cipher: AES = AES::new(&self.key, self.blocksize);
+        let mut output: Vec<u8> = gctr(&mut cipher, nonce, 2, plaintext);
+        let tag: [u8; 16] = tag(&mut cipher, nonce, aad, &output);
+        output.extend_from_slice(&tag);
+
+        output
+    }
+
+    pub fn decrypt(&self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::CiphertextTooShort);
+        }
+
+        let (ciphertext, received) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let mut cipher: AES = AES::new(&self.key, self.blocksize);
+        let expected: [u8; 16] = tag(&mut cipher, nonce, aad, ciphertext);
+
+        let difference: u8 = expected.iter().zip(received.iter()).fold(0, |acc, (x, y)| acc | (x ^ y));
+        if difference != 0 {
+            return Err(AeadError::InvalidTag);
+        }
+
+        Ok(gctr(&mut cipher, nonce, 2, ciphertext))
+    }
+}
+
+fn counter_block(nonce: &[u8; 12], counter: u32) -> [u8; 16] {
+    // J0 = IV || 0^31 || 1 for 96-bit IVs, inc32 only touches the last word
+    let mut block: [u8; 16] = [0; 16];
+    block[..12].copy_from_slice(nonce);
+    block[12..].copy_from_slice(&counter.to_be_bytes());
+
+    block
+}
+
+fn gctr(cipher: &mut AES, nonce: &[u8; 12], counter: u32, input: &[u8]) -> Vec<u8> {
+    let mut output: Vec<u8> = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(16).enumerate() {
+        let key_stream: [u8; 16] = cipher.cipher(counter_block(nonce, counter.wrapping_add(i as u32)));
+        output.extend(chunk.iter().zip(key_stream.iter()).map(|(x, y)| x ^ y));
+    }
+
+    output
+}
+
+fn gf_mul(x: u128, y: u128) -> u128 {
+    // bit 0 of the GCM spec is the most significant bit, every step multiplies v by x
+    let mut z: u128 = 0;
+    let mut v: u128 = y;
+    for i in 0..128 {
+        let bit: u128 = ((x >> (127 - i)) & 1).wrapping_neg();
+        z ^= v & bit;
+        let carry: u128 = (v & 1).wrapping_neg();
+        v = (v >> 1) ^ (R & carry);
+    }
+
+    z
+}
+
+fn ghash(h: u128, aad: &[u8], ciphertext: &[u8]) -> u128 {
+    // both inputs zero padded to full blocks, followed by their lengths in bits
+    let mut y: u128 = 0;
+    for data in [aad, ciphertext] {
+        for chunk in data.chunks(16) {
+            let mut block: [u8; 16] = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            y = gf_mul(y ^ u128::from_be_bytes(block), h);
+        }
+    }
+    let lengths: u128 = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+
+    gf_mul(y ^ lengths, h)
+}
+
+fn tag(cipher: &mut AES, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    // T = E(K, J0) xor GHASH_H(A, C) with H = E(K, 0^128)
+    let h: u128 = u128::from_be_bytes(cipher.cipher([0; 16]));
+    let s: u128 = ghash(h, aad, ciphertext);
+    let j0: u128 = u128::from_be_bytes(cipher.cipher(counter_block(nonce, 1)));
+
+    (s ^ j0).to_be_bytes()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::AesGcm;
+    use super::super::AeadError;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf (test cases 2, 3 and 4)
+    #[test]
+    fn aes_128_gcm() {
+        let aead: AesGcm = AesGcm::new(&[0; 16]);
+        let ciphertext: Vec<u8> = hex("0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf");
+        assert_eq!(aead.encrypt(&[0; 12], &[], &[0; 16]), ciphertext);
+        assert_eq!(aead.decrypt(&[0; 12], &[], &ciphertext).unwrap(), vec![0; 16]);
+
+        let aead: AesGcm = AesGcm::new(&hex("feffe9928665731c6d6a8f9467308308"));
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let plaintext: Vec<u8> = hex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255");
+        let ciphertext: Vec<u8> = hex("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f59854d5c2af327cd64a62cf35abd2ba6fab4");
+        assert_eq!(aead.encrypt(&nonce, &[], &plaintext), ciphertext);
+
+        let aad: Vec<u8> = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let ciphertext: Vec<u8> = hex("42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e0915bc94fbc3221a5db94fae95ae7121a47");
+        assert_eq!(aead.encrypt(&nonce, &aad, &plaintext[..60]), ciphertext);
+        assert_eq!(aead.decrypt(&nonce, &aad, &ciphertext).unwrap(), &plaintext[..60]);
+    }
+
+    // Test-Vectors from: https://csrc.nist.rip/groups/ST/toolkit/BCM/documents/proposedmodes/gcm/gcm-spec.pdf (test case 16)
+    #[test]
+    fn aes_256_gcm() {
+        let aead: AesGcm = AesGcm::new(&hex("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308"));
+        let nonce: [u8; 12] = hex("cafebabefacedbaddecaf888").try_into().unwrap();
+        let aad: Vec<u8> = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext: Vec<u8> = hex("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
+        let ciphertext: Vec<u8> = hex("522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f66276fc6ece0f4e1768cddf8853bb2d551b");
+
+        assert_eq!(aead.encrypt(&nonce, &aad, &plaintext), ciphertext);
+        assert_eq!(aead.decrypt(&nonce, &aad, &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn reject_tampering() {
+        let aead: AesGcm = AesGcm::new(&[0x42; 16]);
+        let nonce: [u8; 12] = [0; 12];
+        let ciphertext: Vec<u8> = aead.encrypt(&nonce, b"header", b"message");
+
+        let mut modified: Vec<u8> = ciphertext.clone();
+        modified[ciphertext.len() - 1] ^= 0x80;
+        assert_eq!(aead.decrypt(&nonce, b"header", &modified), Err(AeadError::InvalidTag));
+        assert_eq!(aead.decrypt(&nonce, b"other", &ciphertext), Err(AeadError::InvalidTag));
+        assert_eq!(aead.decrypt(&nonce, b"header", &ciphertext[..15]), Err(AeadError::CiphertextTooShort));
+    }
+}
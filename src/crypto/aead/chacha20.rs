@@ -0,0 +1,91 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8439#section-2.3
+*/
+
+#![allow(dead_code)]
+
+// "expand 32-byte k" as little-endian words
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+pub fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12]) -> [u8; 64] {
+    let mut state: [u32; 16] = [0; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    for i in 0..8 {
+        state[4 + i] = u32::from_le_bytes(key[4 * i..4 * i + 4].try_into().unwrap());
+    }
+    state[12] = counter;
+    for i in 0..3 {
+        state[13 + i] = u32::from_le_bytes(nonce[4 * i..4 * i + 4].try_into().unwrap());
+    }
+
+    // 10 double rounds, column rounds followed by diagonal rounds
+    let mut working: [u32; 16] = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+
+    let mut output: [u8; 64] = [0; 64];
+    for i in 0..16 {
+        output[4 * i..4 * i + 4].copy_from_slice(&working[i].wrapping_add(state[i]).to_le_bytes());
+    }
+
+    output
+}
+
+pub fn chacha20_xor(key: &[u8; 32], counter: u32, nonce: &[u8; 12], input: &[u8]) -> Vec<u8> {
+    // encryption and decryption are the same, the key stream is xored block by block
+    let mut output: Vec<u8> = Vec::with_capacity(input.len());
+    for (i, chunk) in input.chunks(64).enumerate() {
+        let key_stream: [u8; 64] = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        output.extend(chunk.iter().zip(key_stream.iter()).map(|(x, y)| x ^ y));
+    }
+
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{chacha20_block, chacha20_xor};
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8439#section-2.3.2
+    #[test]
+    fn block_function() {
+        let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let nonce: [u8; 12] = hex("000000090000004a00000000").try_into().unwrap();
+
+        assert_eq!(chacha20_block(&key, 1, &nonce).to_vec(), hex("10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"));
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8439#section-2.4.2
+    #[test]
+    fn encryption() {
+        let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let nonce: [u8; 12] = hex("000000000000004a00000000").try_into().unwrap();
+        let plaintext: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let ciphertext: Vec<u8> = hex("6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d807ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab77937365af90bbf74a35be6b40b8eedf2785e42874d");
+
+        assert_eq!(chacha20_xor(&key, 1, &nonce, plaintext), ciphertext);
+        assert_eq!(chacha20_xor(&key, 1, &nonce, &ciphertext), plaintext);
+    }
+}
@@ -0,0 +1,99 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8439#section-2.8
+*/
+
+#![allow(dead_code)]
+
+use super::{chacha20_block, chacha20_xor, poly1305, AeadError};
+
+pub const TAG_LENGTH: usize = 16;
+
+pub struct ChaCha20Poly1305 {
+    key: [u8; 32],
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self { key: *key }
+    }
+
+    pub fn encrypt(&self, nonce: &[u8; 12], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        // ciphertext || tag, the key stream starts at counter 1
+        let mut output: Vec<u8> = chacha20_xor(&self.key, 1, nonce, plaintext);
+        let tag: [u8; 16] = self.tag(nonce, aad, &output);
+        output.extend_from_slice(&tag);
+
+        output
+    }
+
+    pub fn decrypt(&self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, AeadError> {
+        if ciphertext.len() < TAG_LENGTH {
+            return Err(AeadError::CiphertextTooShort);
+        }
+
+        let (ciphertext, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+        let expected: [u8; 16] = self.tag(nonce, aad, ciphertext);
+
+        // compare all bytes, an early exit would tell how many were right
+        let difference: u8 = expected.iter().zip(tag.iter()).fold(0, |acc, (x, y)| acc | (x ^ y));
+        if difference != 0 {
+            return Err(AeadError::InvalidTag);
+        }
+
+        Ok(chacha20_xor(&self.key, 1, nonce, ciphertext))
+    }
+
+    fn tag(&self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        // one-time key from block 0, MAC over aad || pad16 || ciphertext || pad16 || lengths
+        let one_time_key: [u8; 32] = chacha20_block(&self.key, 0, nonce)[..32].try_into().unwrap();
+
+        let mut mac_data: Vec<u8> = aad.to_vec();
+        mac_data.resize(aad.len().next_multiple_of(16), 0);
+        mac_data.extend_from_slice(ciphertext);
+        mac_data.resize(mac_data.len().next_multiple_of(16), 0);
+        mac_data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+        mac_data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+        poly1305(&one_time_key, &mac_data)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::ChaCha20Poly1305;
+    use super::super::AeadError;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8439#section-2.8.2
+    #[test]
+    fn aead_encryption() {
+        let key: [u8; 32] = core::array::from_fn(|i| 0x80 + i as u8);
+        let nonce: [u8; 12] = hex("070000004041424344454647").try_into().unwrap();
+        let aad: Vec<u8> = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let ciphertext: Vec<u8> = hex("d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b61161ae10b594f09e26a7e902ecbd0600691");
+
+        let aead: ChaCha20Poly1305 = ChaCha20Poly1305::new(&key);
+        assert_eq!(aead.encrypt(&nonce, &aad, plaintext), ciphertext);
+        assert_eq!(aead.decrypt(&nonce, &aad, &ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn reject_tampering() {
+        let aead: ChaCha20Poly1305 = ChaCha20Poly1305::new(&[0x42; 32]);
+        let nonce: [u8; 12] = [0; 12];
+        let ciphertext: Vec<u8> = aead.encrypt(&nonce, b"header", b"message");
+
+        let mut modified: Vec<u8> = ciphertext.clone();
+        modified[0] ^= 0x01;
+        assert_eq!(aead.decrypt(&nonce, b"header", &modified), Err(AeadError::InvalidTag));
+        assert_eq!(aead.decrypt(&nonce, b"other", &ciphertext), Err(AeadError::InvalidTag));
+        assert_eq!(aead.decrypt(&[1; 12], b"header", &ciphertext), Err(AeadError::InvalidTag));
+        assert_eq!(aead.decrypt(&nonce, b"header", &ciphertext[..15]), Err(AeadError::CiphertextTooShort));
+
+        // empty plaintext is just the tag
+        assert_eq!(aead.decrypt(&nonce, &[], &aead.encrypt(&nonce, &[], &[])).unwrap(), Vec::<u8>::new());
+    }
+}
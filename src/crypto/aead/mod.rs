@@ -0,0 +1,16 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod chacha20;
+mod poly1305;
+mod chacha20poly1305;
+mod aes_gcm;
+mod types;
+
+pub use chacha20::{chacha20_block, chacha20_xor};
+pub use poly1305::poly1305;
+pub use chacha20poly1305::ChaCha20Poly1305;
+pub use aes_gcm::AesGcm;
+
+pub use types::AeadError;
@@ -0,0 +1,125 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8439#section-2.5
+*/
+
+#![allow(dead_code)]
+
+fn load32(input: &[u8]) -> u32 {
+    u32::from_le_bytes(input[..4].try_into().unwrap())
+}
+
+pub fn poly1305(key: &[u8; 32], message: &[u8]) -> [u8; 16] {
+    // accumulator and clamped r in five 26-bit limbs, 2^130 = 5 mod p folds the high products
+    let r: [u32; 5] = [
+        load32(&key[0..]) & 0x3ffffff,
+        (load32(&key[3..]) >> 2) & 0x3ffff03,
+        (load32(&key[6..]) >> 4) & 0x3ffc0ff,
+        (load32(&key[9..]) >> 6) & 0x3f03fff,
+        (load32(&key[12..]) >> 8) & 0x00fffff,
+    ];
+    let s: [u64; 5] = [0, r[1] as u64 * 5, r[2] as u64 * 5, r[3] as u64 * 5, r[4] as u64 * 5];
+    let r: [u64; 5] = r.map(|limb| limb as u64);
+    let mut h: [u64; 5] = [0; 5];
+
+    for chunk in message.chunks(16) {
+        // every block gets a 0x01 byte appended, full blocks at bit 128
+        let mut block: [u8; 17] = [0; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+
+        h[0] += (load32(&block[0..]) & 0x3ffffff) as u64;
+        h[1] += ((load32(&block[3..]) >> 2) & 0x3ffffff) as u64;
+        h[2] += ((load32(&block[6..]) >> 4) & 0x3ffffff) as u64;
+        h[3] += ((load32(&block[9..]) >> 6) & 0x3ffffff) as u64;
+        h[4] += ((load32(&block[12..]) >> 8) | ((block[16] as u32) << 24)) as u64;
+
+        let d: [u64; 5] = [
+            h[0] * r[0] + h[1] * s[4] + h[2] * s[3] + h[3] * s[2] + h[4] * s[1],
+            h[0] * r[1] + h[1] * r[0] + h[2] * s[4] + h[3] * s[3] + h[4] * s[2],
+            h[0] * r[2] + h[1] * r[1] + h[2] * r[0] + h[3] * s[4] + h[4] * s[3],
+            h[0] * r[3] + h[1] * r[2] + h[2] * r[1] + h[3] * r[0] + h[4] * s[4],
+            h[0] * r[4] + h[1] * r[3] + h[2] * r[2] + h[3] * r[1] + h[4] * r[0],
+        ];
+
+        let mut carry: u64 = 0;
+        for i in 0..5 {
+            let value: u64 = d[i] + carry;
+            h[i] = value & 0x3ffffff;
+            carry = value >> 26;
+        }
+        h[0] += carry * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= 0x3ffffff;
+    }
+
+    // full carry, then h - p is kept if it does not borrow
+    let mut carry: u64 = h[1] >> 26;
+    h[1] &= 0x3ffffff;
+    for limb in h.iter_mut().skip(2) {
+        *limb += carry;
+        carry = *limb >> 26;
+        *limb &= 0x3ffffff;
+    }
+    h[0] += carry * 5;
+    h[1] += h[0] >> 26;
+    h[0] &= 0x3ffffff;
+
+    let mut g: [u64; 5] = [0; 5];
+    let mut carry: u64 = 5;
+    for i in 0..5 {
+        let value: u64 = h[i] + carry;
+        g[i] = value & 0x3ffffff;
+        carry = value >> 26;
+    }
+    // carry is 1 if h + 5 reached 2^130, i.e. h >= p
+    let mask: u64 = carry.wrapping_neg();
+    for i in 0..5 {
+        h[i] = (h[i] & !mask) | (g[i] & mask);
+    }
+
+    // tag = (h + s) mod 2^128
+    let words: [u64; 4] = [
+        h[0] | (h[1] << 26),
+        (h[1] >> 6) | (h[2] << 20),
+        (h[2] >> 12) | (h[3] << 14),
+        (h[3] >> 18) | (h[4] << 8),
+    ];
+
+    let mut output: [u8; 16] = [0; 16];
+    let mut carry: u64 = 0;
+    for i in 0..4 {
+        let value: u64 = (words[i] & 0xffffffff) + load32(&key[16 + 4 * i..]) as u64 + carry;
+        output[4 * i..4 * i + 4].copy_from_slice(&(value as u32).to_le_bytes());
+        carry = value >> 32;
+    }
+
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::poly1305;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8439#section-2.5.2
+    #[test]
+    fn message_authentication() {
+        let key: [u8; 32] = hex("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b").try_into().unwrap();
+
+        assert_eq!(poly1305(&key, b"Cryptographic Forum Research Group").to_vec(), hex("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8439#appendix-A.3 (#5),
+    // the second one generated with pyca/cryptography
+    #[test]
+    fn reduction_edge_cases() {
+        let mut key: [u8; 32] = [0; 32];
+        key[0] = 0x02;
+        assert_eq!(poly1305(&key, &[0xff; 16]).to_vec(), hex("03000000000000000000000000000000"));
+
+        assert_eq!(poly1305(&[0xff; 32], &[0xff; 100]).to_vec(), hex("b99c030d7ce939bb6607393e68656f22"));
+        assert_eq!(poly1305(&key, &[]).to_vec(), hex("00000000000000000000000000000000"));
+    }
+}
@@ -0,0 +1,11 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum AeadError {
+    // Authentication tag that does not match the ciphertext, nonce and associated data.
+    InvalidTag,
+    // Ciphertext shorter than the authentication tag.
+    CiphertextTooShort,
+}
@@ -0,0 +1,96 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://ed25519.cr.yp.to/ed25519-20110926.pdf (fixed-base scalar multiplication)
+*/
+
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use super::edwards25519::{EdwardsPoint, base_point, identity, point_add, to_montgomery_u};
+use super::msm::{radix_16, multiples_table, select};
+
+// 16^i * B, ..., 8 * 16^i * B for each of the 65 signed radix-16 digits of a scalar,
+// computed once on first use.
+static BASEPOINT_TABLE: OnceLock<Vec<[EdwardsPoint; 8]>> = OnceLock::new();
+
+fn basepoint_table() -> &'static [[EdwardsPoint; 8]] {
+    BASEPOINT_TABLE.get_or_init(|| {
+        let mut point: EdwardsPoint = base_point();
+        let mut tables: Vec<[EdwardsPoint; 8]> = Vec::with_capacity(65);
+
+        for _ in 0..65 {
+            tables.push(multiples_table(&point));
+            for _ in 0..4 {
+                point = point_add(&point, &point);
+            }
+        }
+
+        tables
+    })
+}
+
+pub fn scalarmult_base(scalar: &[u8; 32]) -> EdwardsPoint {
+    // sum of e_i * 16^i * B, 65 additions and no doublings, constant time in the scalar
+    let digits: [i8; 65] = radix_16(scalar);
+    let tables: &[[EdwardsPoint; 8]] = basepoint_table();
+
+    let mut output: EdwardsPoint = identity();
+    for (digit, table) in digits.iter().zip(tables.iter()) {
+        output = point_add(&output, &select(table, *digit));
+    }
+
+    output
+}
+
+pub fn x25519_base(scalar: &[u8; 32]) -> [u8; 32] {
+    // same result as scalarmult with u = 9, through the Edwards base point and the birational map
+    let mut clamped: [u8; 32] = *scalar;
+    clamped[0] &= 0xf8;
+    clamped[31] = (clamped[31] & 0x7f) | 0x40;
+
+    to_montgomery_u(&scalarmult_base(&clamped))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{scalarmult_base, x25519_base};
+    use crate::crypto::ec::edwards25519::{base_point, encode, scalarmult_point};
+    use crate::crypto::ec::{Curve, scalarmult};
+    use crate::utils::rng;
+
+    #[test]
+    fn matches_ladder() {
+        let mut u: [u8; 32] = [0; 32];
+        u[0] = 9;
+
+        for _ in 0..20 {
+            let mut scalar: [u8; 32] = [0; 32];
+            rng::fill_bytes(&mut scalar);
+
+            assert_eq!(encode(&scalarmult_base(&scalar)), encode(&scalarmult_point(&base_point(), &scalar)));
+            assert_eq!(x25519_base(&scalar), scalarmult(&u, &scalar));
+        }
+
+        let order: [u8; 32] = Curve::twisted_edwards().n;
+        let mut order_minus_1: [u8; 32] = order;
+        order_minus_1[0] -= 1;
+
+        for scalar in [[0; 32], [0xff; 32], order, order_minus_1] {
+            assert_eq!(encode(&scalarmult_base(&scalar)), encode(&scalarmult_point(&base_point(), &scalar)));
+        }
+    }
+
+    #[test]
+    fn rfc7748_key_pairs() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7748#section-6.1
+        let alice_private_key: [u8; 32] = [0x77, 0x07, 0x6d, 0x0a, 0x73, 0x18, 0xa5, 0x7d, 0x3c, 0x16, 0xc1, 0x72, 0x51, 0xb2, 0x66, 0x45, 0xdf, 0x4c, 0x2f, 0x87, 0xeb, 0xc0, 0x99, 0x2a, 0xb1, 0x77, 0xfb, 0xa5, 0x1d, 0xb9, 0x2c, 0x2a];
+        let alice_public_key: [u8; 32] = [0x85, 0x20, 0xf0, 0x09, 0x89, 0x30, 0xa7, 0x54, 0x74, 0x8b, 0x7d, 0xdc, 0xb4, 0x3e, 0xf7, 0x5a, 0x0d, 0xbf, 0x3a, 0x0d, 0x26, 0x38, 0x1a, 0xf4, 0xeb, 0xa4, 0xa9, 0x8e, 0xaa, 0x9b, 0x4e, 0x6a];
+        let bob_private_key: [u8; 32] = [0x5d, 0xab, 0x08, 0x7e, 0x62, 0x4a, 0x8a, 0x4b, 0x79, 0xe1, 0x7f, 0x8b, 0x83, 0x80, 0x0e, 0xe6, 0x6f, 0x3b, 0xb1, 0x29, 0x26, 0x18, 0xb6, 0xfd, 0x1c, 0x2f, 0x8b, 0x27, 0xff, 0x88, 0xe0, 0xeb];
+        let bob_public_key: [u8; 32] = [0xde, 0x9e, 0xdb, 0x7d, 0x7b, 0x7d, 0xc1, 0xb4, 0xd3, 0x5b, 0x61, 0xc2, 0xec, 0xe4, 0x35, 0x37, 0x3f, 0x83, 0x43, 0xc8, 0x5b, 0x78, 0x67, 0x4d, 0xad, 0xfc, 0x7e, 0x14, 0x6f, 0x88, 0x2b, 0x4f];
+
+        assert_eq!(x25519_base(&alice_private_key), alice_public_key);
+        assert_eq!(x25519_base(&bob_private_key), bob_public_key);
+    }
+}
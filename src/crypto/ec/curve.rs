@@ -7,22 +7,27 @@ Implementation according to
 #![allow(unused_variables)]
 
 use super::Point;
-use super::x25519::{FieldElement, pack25519, unpack25519, fmul, fadd};
+use super::x25519::{FieldElement, unpack25519, fmul, fadd, fequal};
+use super::x448::{FieldElement448, pack448, unpack448, fmul448, fadd448};
+use super::field256::{FieldElement256, unpack256};
 
 
 pub enum CurveType {
     Montgomery,
-    TwistedEdwards
+    TwistedEdwards,
+    ShortWeierstrass,
 }
 
-pub struct Curve {
+// F is the field element type of the coordinates and N the byte length
+// of the parameters, Curve25519 is used if nothing else is given.
+pub struct Curve<F = FieldElement, const N: usize = 32> {
     pub curve: CurveType,
-    pub p: [u8; 32],
-    pub a: [u8; 32],
-    pub b: [u8; 32],
-    pub g: Point,
-    pub n: [u8; 32],
-    pub h: [u8; 32],
+    pub p: [u8; N],
+    pub a: [u8; N],
+    pub b: [u8; N],
+    pub g: Point<F>,
+    pub n: [u8; N],
+    pub h: [u8; N],
 }
 
 impl Curve {
@@ -43,41 +48,160 @@ impl Curve {
     }
 
     pub fn twisted_edwards() -> Self {
-        // https://neuromancer.sk/std/other/Ed25519
+        // https://neuromancer.sk/std/other/Ed25519, all values little-endian, b holds d = -121665 / 121666
         Self {
             curve: CurveType::TwistedEdwards,
-            p: [0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xed],
-            a: [0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xec],
-            b: [0x52, 0x03, 0x6c, 0xee, 0x2b, 0x6f, 0xfe, 0x73, 0x8c, 0xc7, 0x40, 0x79, 0x77, 0x79, 0xe8, 0x98, 0x00, 0x70, 0x0a, 0x4d, 0x41, 0x41, 0xd8, 0xab, 0x75, 0xeb, 0x4d, 0xca, 0x13, 0x59, 0x78, 0xa3],
+            p: [0xed, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+            a: [0xec, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+            b: [0xa3, 0x78, 0x59, 0x13, 0xca, 0x4d, 0xeb, 0x75, 0xab, 0xd8, 0x41, 0x41, 0x4d, 0x0a, 0x70, 0x00, 0x98, 0xe8, 0x79, 0x77, 0x79, 0x40, 0xc7, 0x8c, 0x73, 0xfe, 0x6f, 0x2b, 0xee, 0x6c, 0x03, 0x52],
             g: Point::new(
-                [0x21, 0x69, 0x36, 0xD3, 0xCD, 0x6E, 0x53, 0xFE, 0xC0, 0xA4, 0xE2, 0x31, 0xFD, 0xD6, 0xDC, 0x5C, 0x69, 0x2C, 0xC7, 0x60, 0x95, 0x25, 0xA7, 0xB2, 0xC9, 0x56, 0x2D, 0x60, 0x8F, 0x25, 0xD5, 0x1A],
-                [0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x58],
+                [0x1a, 0xd5, 0x25, 0x8f, 0x60, 0x2d, 0x56, 0xc9, 0xb2, 0xa7, 0x25, 0x95, 0x60, 0xc7, 0x2c, 0x69, 0x5c, 0xdc, 0xd6, 0xfd, 0x31, 0xe2, 0xa4, 0xc0, 0xfe, 0x53, 0x6e, 0xcd, 0xd3, 0x36, 0x69, 0x21],
+                [0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66],
             ),
-            n: [0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0xde, 0xf9, 0xde, 0xa2, 0xf7, 0x9c, 0xd6, 0x58, 0x12, 0x63, 0x1a, 0x5c, 0xf5, 0xd3, 0xed],
+            n: [0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10],
             h: [0x8, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0],
         }
     }
 
-    pub fn point_on_curve(&mut self, point: &mut Point) -> bool {
-        let left: FieldElement;
-        let right: FieldElement;
-
+    pub fn point_on_curve(&self, point: &Point) -> bool {
         match self.curve {
             CurveType::Montgomery => {
+                // v^2 = u^3 + A * u^2 + u
+                let u_power_2: FieldElement = fmul(&point.x, &point.x);
+                let u_power_3: FieldElement = fmul(&point.x, &u_power_2);
+                let a_mul_u_power_2: FieldElement = fmul(&unpack25519(&self.a), &u_power_2);
+
+                fequal(&fmul(&point.y, &point.y), &fadd(&u_power_3, &fadd(&a_mul_u_power_2, &point.x)))
+            },
+            CurveType::TwistedEdwards => {
+                // a * x^2 + y^2 = 1 + d * x^2 * y^2
                 let x_power_2: FieldElement = fmul(&point.x, &point.x);
-                let x_power_3: FieldElement = fmul(&point.x, &fmul(&point.x, &point.x));
-                let a_mul_x_power_2: FieldElement = fmul(&unpack25519(&self.a) , &x_power_2);
+                let y_power_2: FieldElement = fmul(&point.y, &point.y);
+                let mut one: FieldElement = [0; 16];
+                one[0] = 1;
+
+                let left: FieldElement = fadd(&fmul(&unpack25519(&self.a), &x_power_2), &y_power_2);
+                let right: FieldElement = fadd(&one, &fmul(&unpack25519(&self.b), &fmul(&x_power_2, &y_power_2)));
+
+                fequal(&left, &right)
+            },
+            // Curve25519 has no Weierstrass parameters in this crate
+            CurveType::ShortWeierstrass => false,
+        }
+    }
+}
+
+impl Curve<FieldElement448, 56> {
+    pub fn curve448() -> Self {
+        // https://neuromancer.sk/std/other/Curve448, all values little-endian
+        let mut h: [u8; 56] = [0; 56];
+        h[0] = 0x4;
+
+        Self {
+            curve: CurveType::Montgomery,
+            p: [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            a: [0xa6, 0x62, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            b: [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            g: Point {
+                x: unpack448(&[0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+                y: unpack448(&[0x1a, 0x5b, 0x7b, 0x45, 0x3d, 0x22, 0xd7, 0x6f, 0xf7, 0x7a, 0x67, 0x50, 0xb1, 0xc4, 0x12, 0x13, 0x21, 0x0d, 0x43, 0x46, 0x23, 0x7e, 0x02, 0xb8, 0xed, 0xf6, 0xf3, 0x8d, 0xc2, 0x5d, 0xf7, 0x60, 0xd0, 0x45, 0x55, 0xf5, 0x34, 0x5d, 0xae, 0xcb, 0xce, 0x6f, 0x32, 0x58, 0x6e, 0xab, 0x98, 0x6c, 0xf6, 0xb1, 0xf5, 0x95, 0x12, 0x5d, 0x23, 0x7d]),
+            },
+            n: [0xf3, 0x44, 0x58, 0xab, 0x92, 0xc2, 0x78, 0x23, 0x55, 0x8f, 0xc5, 0x8d, 0x72, 0xc2, 0x6c, 0x21, 0x90, 0x36, 0xd6, 0xae, 0x49, 0xdb, 0x4e, 0xc4, 0xe9, 0x23, 0xca, 0x7c, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x3f],
+            h,
+        }
+    }
+
+    pub fn edwards448() -> Self {
+        // https://neuromancer.sk/std/other/Ed448, all values little-endian, b holds d = -39081
+        let curve448: Self = Curve::curve448();
 
-                // packing and unpacking removes overflowing bits.
-                left = unpack25519(&pack25519(&mut fmul(&point.y, &point.y)));
-                right = fadd(&x_power_3, &fadd(&a_mul_x_power_2, &point.x));
+        let mut a: [u8; 56] = [0; 56];
+        a[0] = 0x1;
+
+        Self {
+            curve: CurveType::TwistedEdwards,
+            p: curve448.p,
+            a,
+            b: [0x56, 0x67, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            g: Point {
+                x: unpack448(&[0x5e, 0xc0, 0x0c, 0xc7, 0x2b, 0xa8, 0x26, 0x26, 0x8e, 0x93, 0x00, 0x8b, 0xe1, 0x80, 0x3b, 0x43, 0x11, 0x65, 0xb6, 0x2a, 0xf7, 0x1a, 0xae, 0x12, 0x64, 0xa4, 0xd3, 0xa3, 0x24, 0xe3, 0x6d, 0xea, 0x67, 0x17, 0x0f, 0x47, 0x70, 0x65, 0x14, 0x9e, 0xda, 0x36, 0xbf, 0x22, 0xa6, 0x15, 0x1d, 0x22, 0xed, 0x0d, 0xed, 0x6b, 0xc6, 0x70, 0x19, 0x4f]),
+                y: unpack448(&[0x14, 0xfa, 0x30, 0xf2, 0x5b, 0x79, 0x08, 0x98, 0xad, 0xc8, 0xd7, 0x4e, 0x2c, 0x13, 0xbd, 0xfd, 0xc4, 0x39, 0x7c, 0xe6, 0x1c, 0xff, 0xd3, 0x3a, 0xd7, 0xc2, 0xa0, 0x05, 0x1e, 0x9c, 0x78, 0x87, 0x40, 0x98, 0xa3, 0x6c, 0x73, 0x73, 0xea, 0x4b, 0x62, 0xc7, 0xc9, 0x56, 0x37, 0x20, 0x76, 0x88, 0x24, 0xbc, 0xb6, 0x6e, 0x71, 0x46, 0x3f, 0x69]),
+            },
+            n: curve448.n,
+            h: curve448.h,
+        }
+    }
+
+    pub fn point_on_curve(&self, point: &Point<FieldElement448>) -> bool {
+        match self.curve {
+            CurveType::Montgomery => {
+                // v^2 = u^3 + A * u^2 + u, compared after full reduction
+                let u_power_2: FieldElement448 = fmul448(&point.x, &point.x);
+                let u_power_3: FieldElement448 = fmul448(&point.x, &u_power_2);
+                let a_mul_u_power_2: FieldElement448 = fmul448(&unpack448(&self.a), &u_power_2);
+
+                let left: [u8; 56] = pack448(&fmul448(&point.y, &point.y));
+                let right: [u8; 56] = pack448(&fadd448(&u_power_3, &fadd448(&a_mul_u_power_2, &point.x)));
+
+                left == right
             },
             CurveType::TwistedEdwards => {
-                todo!();
-            }
+                // a * x^2 + y^2 = 1 + d * x^2 * y^2, compared after full reduction
+                let x_power_2: FieldElement448 = fmul448(&point.x, &point.x);
+                let y_power_2: FieldElement448 = fmul448(&point.y, &point.y);
+                let mut one: FieldElement448 = [0; 16];
+                one[0] = 1;
+
+                let left: [u8; 56] = pack448(&fadd448(&fmul448(&unpack448(&self.a), &x_power_2), &y_power_2));
+                let right: [u8; 56] = pack448(&fadd448(&one, &fmul448(&unpack448(&self.b), &fmul448(&x_power_2, &y_power_2))));
+
+                left == right
+            },
+            // Curve448 has no Weierstrass parameters in this crate
+            CurveType::ShortWeierstrass => false,
+        }
+    }
+}
+
+impl Curve<FieldElement256, 32> {
+    pub fn secp256k1() -> Self {
+        // https://neuromancer.sk/std/secg/secp256k1, all values big-endian as in SEC 2
+        let mut b: [u8; 32] = [0; 32];
+        b[31] = 0x07;
+        let mut h: [u8; 32] = [0; 32];
+        h[31] = 0x01;
+
+        Self {
+            curve: CurveType::ShortWeierstrass,
+            p: [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xfc, 0x2f],
+            a: [0; 32],
+            b,
+            g: Point {
+                x: unpack256(&[0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17, 0x98]),
+                y: unpack256(&[0x48, 0x3a, 0xda, 0x77, 0x26, 0xa3, 0xc4, 0x65, 0x5d, 0xa4, 0xfb, 0xfc, 0x0e, 0x11, 0x08, 0xa8, 0xfd, 0x17, 0xb4, 0x48, 0xa6, 0x85, 0x54, 0x19, 0x9c, 0x47, 0xd0, 0x8f, 0xfb, 0x10, 0xd4, 0xb8]),
+            },
+            n: [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41],
+            h,
         }
+    }
+
+    pub fn p256() -> Self {
+        // https://neuromancer.sk/std/nist/P-256, all values big-endian as in FIPS 186-4
+        let mut h: [u8; 32] = [0; 32];
+        h[31] = 0x01;
 
-        left == right
+        Self {
+            curve: CurveType::ShortWeierstrass,
+            p: [0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            a: [0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfc],
+            b: [0x5a, 0xc6, 0x35, 0xd8, 0xaa, 0x3a, 0x93, 0xe7, 0xb3, 0xeb, 0xbd, 0x55, 0x76, 0x98, 0x86, 0xbc, 0x65, 0x1d, 0x06, 0xb0, 0xcc, 0x53, 0xb0, 0xf6, 0x3b, 0xce, 0x3c, 0x3e, 0x27, 0xd2, 0x60, 0x4b],
+            g: Point {
+                x: unpack256(&[0x6b, 0x17, 0xd1, 0xf2, 0xe1, 0x2c, 0x42, 0x47, 0xf8, 0xbc, 0xe6, 0xe5, 0x63, 0xa4, 0x40, 0xf2, 0x77, 0x03, 0x7d, 0x81, 0x2d, 0xeb, 0x33, 0xa0, 0xf4, 0xa1, 0x39, 0x45, 0xd8, 0x98, 0xc2, 0x96]),
+                y: unpack256(&[0x4f, 0xe3, 0x42, 0xe2, 0xfe, 0x1a, 0x7f, 0x9b, 0x8e, 0xe7, 0xeb, 0x4a, 0x7c, 0x0f, 0x9e, 0x16, 0x2b, 0xce, 0x33, 0x57, 0x6b, 0x31, 0x5e, 0xce, 0xcb, 0xb6, 0x40, 0x68, 0x37, 0xbf, 0x51, 0xf5]),
+            },
+            n: [0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63, 0x25, 0x51],
+            h,
+        }
     }
 }
 
@@ -85,11 +209,102 @@ impl Curve {
 #[cfg(test)]
 mod tests {
     use super::Curve;
+    use crate::utils::bigint::U256;
 
     #[test] 
     fn test_if_point_on_curve() {
-        let mut curve = Curve::montgomery();
+        let curve = Curve::montgomery();
+
+        assert!(curve.point_on_curve(&curve.g));
+
+        // the same point with non-canonical limbs, x + p
+        let mut unreduced = curve.g;
+        unreduced.x[0] += 0xffed;
+        unreduced.x[15] += 0x7fff;
+        for limb in unreduced.x[1..15].iter_mut() {
+            *limb += 0xffff;
+        }
+
+        assert!(curve.point_on_curve(&unreduced));
+
+        let mut not_on_curve = curve.g;
+        not_on_curve.y[0] += 1;
+
+        assert!(!curve.point_on_curve(&not_on_curve));
+    }
+
+    #[test]
+    fn test_if_point_on_edwards25519() {
+        let curve = Curve::twisted_edwards();
+
+        assert!(curve.point_on_curve(&curve.g));
+
+        let mut not_on_curve = curve.g;
+        not_on_curve.x[3] += 1;
+
+        assert!(!curve.point_on_curve(&not_on_curve));
+    }
+
+    #[test]
+    fn test_if_point_on_curve448() {
+        let curve = Curve::curve448();
+
+        assert!(curve.point_on_curve(&curve.g));
+
+        let mut not_on_curve = curve.g;
+        not_on_curve.y[0] += 1;
+
+        assert!(!curve.point_on_curve(&not_on_curve));
+    }
+
+    #[test]
+    fn test_if_point_on_edwards448() {
+        let curve = Curve::edwards448();
+
+        assert!(curve.point_on_curve(&curve.g));
 
-        assert_eq!(true, curve.point_on_curve(&mut curve.g.clone().to_owned()))
+        let mut not_on_curve = curve.g;
+        not_on_curve.x[3] += 1;
+
+        assert!(!curve.point_on_curve(&not_on_curve));
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        // decimal values from https://neuromancer.sk, Curve25519 is stored little-endian, the others big-endian
+        let curve25519 = Curve::montgomery();
+        let secp256k1 = Curve::secp256k1();
+        let p256 = Curve::p256();
+
+        let parameters: [(U256, U256, &str, &str); 3] = [
+            (
+                U256::from_le_bytes(&curve25519.p).unwrap(),
+                U256::from_le_bytes(&curve25519.n).unwrap(),
+                "57896044618658097711785492504343953926634992332820282019728792003956564819949",
+                "7237005577332262213973186563042994240857116359379907606001950938285454250989",
+            ),
+            (
+                U256::from_be_bytes(&secp256k1.p).unwrap(),
+                U256::from_be_bytes(&secp256k1.n).unwrap(),
+                "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+                "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+            ),
+            (
+                U256::from_be_bytes(&p256.p).unwrap(),
+                U256::from_be_bytes(&p256.n).unwrap(),
+                "115792089210356248762697446949407573530086143415290314195533631308867097853951",
+                "115792089210356248762697446949407573529996955224135760342422259061068512044369",
+            ),
+        ];
+
+        for (p, n, p_decimal, n_decimal) in parameters.iter() {
+            assert_eq!(p.to_decimal(), *p_decimal);
+            assert_eq!(n.to_decimal(), *n_decimal);
+
+            // Fermat test, 2^(m - 1) = 1 for the prime field and group order
+            for m in [p, n] {
+                assert_eq!(U256::from_u64(2).pow_mod(&m.overflowing_sub(&U256::one()).0, m), U256::one());
+            }
+        }
     }
 }
@@ -6,7 +6,9 @@ Warranty Disclaimer: the software is provided "as is" without any warranties or
 #![allow(unused_variables)]
 
 use super::Curve;
-use super::{pack25519, scalarmult};
+use super::{scalarmult, scalarmult_checked, EcError};
+use super::basepoint::x25519_base;
+use super::{FieldElement448, pack448, scalarmult448, scalarmult448_checked};
 
 use crate::utils::rng;
 
@@ -29,6 +31,12 @@ impl ECDH {
         let mut d: [u8; 32] = [0; 32]; 
         rng::fill_array(&mut d);
 
+        self.set_secret_key(&d);
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) {
+        let mut d: [u8; 32] = *secret_key;
+
         /* According to following article: https://cr.yp.to/ecdh.html#use,
         you have to operate on the first and last byte of the privat random
         to create a valid key and to strengthen the key pair */
@@ -38,18 +46,65 @@ impl ECDH {
 
         self.sec_key = d;
 
-        self.pub_key = scalarmult(&pack25519(&mut self.curve.g.x), &d);
+        self.pub_key = x25519_base(&d);
     }
 
     pub fn symmetric_key(&self, public: &[u8; 32]) -> [u8; 32] {
         scalarmult(&public, &self.sec_key)
     }
+
+    pub fn checked_symmetric_key(&self, public: &[u8; 32]) -> Result<[u8; 32], EcError> {
+        scalarmult_checked(public, &self.sec_key)
+    }
+}
+
+pub struct ECDH448 {
+    curve: Curve<FieldElement448, 56>,
+    pub pub_key: [u8; 56],
+    pub sec_key: [u8; 56],
+}
+
+impl ECDH448 {
+    pub fn new() -> Self {
+        Self {
+            curve: Curve::curve448(),
+            pub_key: [0; 56],
+            sec_key: [0; 56],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut d: [u8; 56] = [0; 56];
+        rng::fill_bytes(&mut d);
+
+        // Clamping according to https://datatracker.ietf.org/doc/html/rfc7748#section-5
+        d[0] &= 252;
+        d[55] |= 128;
+
+        self.sec_key = d;
+
+        self.pub_key = scalarmult448(&pack448(&self.curve.g.x), &d);
+    }
+
+    pub fn symmetric_key(&self, public: &[u8; 56]) -> [u8; 56] {
+        scalarmult448(public, &self.sec_key)
+    }
+
+    pub fn checked_symmetric_key(&self, public: &[u8; 56]) -> Result<[u8; 56], EcError> {
+        scalarmult448_checked(public, &self.sec_key)
+    }
+}
+
+impl Default for ECDH448 {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ECDH;
-    use super::scalarmult;
+    use super::{ECDH, ECDH448};
+    use super::{scalarmult, scalarmult448, EcError};
 
     #[test] 
     fn own_keys() {
@@ -101,4 +156,56 @@ mod tests {
 
         assert_eq!(key1, key2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn checked_keys() {
+        let mut ecdh_1: ECDH = ECDH::new();
+        ecdh_1.gen_key_pair();
+
+        let mut ecdh_2: ECDH = ECDH::new();
+        ecdh_2.gen_key_pair();
+
+        let key1: [u8; 32] = ecdh_1.checked_symmetric_key(&ecdh_2.pub_key).unwrap();
+        let key2: [u8; 32] = ecdh_2.symmetric_key(&ecdh_1.pub_key);
+
+        assert_eq!(key1, key2);
+
+        // Point of order 8 from https://datatracker.ietf.org/doc/html/rfc7748#section-6.1
+        let low_order_point: [u8; 32] = [0xe0, 0xeb, 0x7a, 0x7c, 0x3b, 0x41, 0xb8, 0xae, 0x16, 0x56, 0xe3, 0xfa, 0xf1, 0x9f, 0xc4, 0x6a, 0xda, 0x09, 0x8d, 0xeb, 0x9c, 0x32, 0xb1, 0xfd, 0x86, 0x62, 0x05, 0x16, 0x5f, 0x49, 0xb8, 0x00];
+
+        assert_eq!(ecdh_1.checked_symmetric_key(&low_order_point), Err(EcError::LowOrderPoint));
+    }
+
+    #[test]
+    fn own_keys_448() {
+        let mut ecdh_1: ECDH448 = ECDH448::new();
+        ecdh_1.gen_key_pair();
+
+        let mut ecdh_2: ECDH448 = ECDH448::new();
+        ecdh_2.gen_key_pair();
+
+        let key1: [u8; 56] = ecdh_1.symmetric_key(&ecdh_2.pub_key);
+        let key2: [u8; 56] = ecdh_2.checked_symmetric_key(&ecdh_1.pub_key).unwrap();
+
+        assert_eq!(key1, key2);
+        assert_eq!(ecdh_1.checked_symmetric_key(&[0; 56]), Err(EcError::LowOrderPoint));
+    }
+
+    #[test]
+    fn foreign_keys_448() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7748#section-6.2
+        let alice_private_key: [u8; 56] = [0x9a, 0x8f, 0x49, 0x25, 0xd1, 0x51, 0x9f, 0x57, 0x75, 0xcf, 0x46, 0xb0, 0x4b, 0x58, 0x00, 0xd4, 0xee, 0x9e, 0xe8, 0xba, 0xe8, 0xbc, 0x55, 0x65, 0xd4, 0x98, 0xc2, 0x8d, 0xd9, 0xc9, 0xba, 0xf5, 0x74, 0xa9, 0x41, 0x97, 0x44, 0x89, 0x73, 0x91, 0x00, 0x63, 0x82, 0xa6, 0xf1, 0x27, 0xab, 0x1d, 0x9a, 0xc2, 0xd8, 0xc0, 0xa5, 0x98, 0x72, 0x6b];
+        let alice_public_key: [u8; 56] = [0x9b, 0x08, 0xf7, 0xcc, 0x31, 0xb7, 0xe3, 0xe6, 0x7d, 0x22, 0xd5, 0xae, 0xa1, 0x21, 0x07, 0x4a, 0x27, 0x3b, 0xd2, 0xb8, 0x3d, 0xe0, 0x9c, 0x63, 0xfa, 0xa7, 0x3d, 0x2c, 0x22, 0xc5, 0xd9, 0xbb, 0xc8, 0x36, 0x64, 0x72, 0x41, 0xd9, 0x53, 0xd4, 0x0c, 0x5b, 0x12, 0xda, 0x88, 0x12, 0x0d, 0x53, 0x17, 0x7f, 0x80, 0xe5, 0x32, 0xc4, 0x1f, 0xa0];
+        let bob_private_key: [u8; 56] = [0x1c, 0x30, 0x6a, 0x7a, 0xc2, 0xa0, 0xe2, 0xe0, 0x99, 0x0b, 0x29, 0x44, 0x70, 0xcb, 0xa3, 0x39, 0xe6, 0x45, 0x37, 0x72, 0xb0, 0x75, 0x81, 0x1d, 0x8f, 0xad, 0x0d, 0x1d, 0x69, 0x27, 0xc1, 0x20, 0xbb, 0x5e, 0xe8, 0x97, 0x2b, 0x0d, 0x3e, 0x21, 0x37, 0x4c, 0x9c, 0x92, 0x1b, 0x09, 0xd1, 0xb0, 0x36, 0x6f, 0x10, 0xb6, 0x51, 0x73, 0x99, 0x2d];
+        let bob_public_key: [u8; 56] = [0x3e, 0xb7, 0xa8, 0x29, 0xb0, 0xcd, 0x20, 0xf5, 0xbc, 0xfc, 0x0b, 0x59, 0x9b, 0x6f, 0xec, 0xcf, 0x6d, 0xa4, 0x62, 0x71, 0x07, 0xbd, 0xb0, 0xd4, 0xf3, 0x45, 0xb4, 0x30, 0x27, 0xd8, 0xb9, 0x72, 0xfc, 0x3e, 0x34, 0xfb, 0x42, 0x32, 0xa1, 0x3c, 0xa7, 0x06, 0xdc, 0xb5, 0x7a, 0xec, 0x3d, 0xae, 0x07, 0xbd, 0xc1, 0xc6, 0x7b, 0xf3, 0x36, 0x09];
+        let shared_key: [u8; 56] = [0x07, 0xff, 0xf4, 0x18, 0x1a, 0xc6, 0xcc, 0x95, 0xec, 0x1c, 0x16, 0xa9, 0x4a, 0x0f, 0x74, 0xd1, 0x2d, 0xa2, 0x32, 0xce, 0x40, 0xa7, 0x75, 0x52, 0x28, 0x1d, 0x28, 0x2b, 0xb6, 0x0c, 0x0b, 0x56, 0xfd, 0x24, 0x64, 0xc3, 0x35, 0x54, 0x39, 0x36, 0x52, 0x1c, 0x24, 0x40, 0x30, 0x85, 0xd5, 0x9a, 0x44, 0x9a, 0x50, 0x37, 0x51, 0x4a, 0x87, 0x9d];
+
+        let mut base_point: [u8; 56] = [0; 56];
+        base_point[0] = 5;
+
+        assert_eq!(scalarmult448(&base_point, &alice_private_key), alice_public_key);
+        assert_eq!(scalarmult448(&base_point, &bob_private_key), bob_public_key);
+        assert_eq!(scalarmult448(&alice_public_key, &bob_private_key), shared_key);
+        assert_eq!(scalarmult448(&bob_public_key, &alice_private_key), shared_key);
+    }
+}
@@ -0,0 +1,251 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9381#section-5 (ECVRF-EDWARDS25519-SHA512-TAI)
+*/
+
+#![allow(dead_code)]
+
+use super::EcError;
+use super::msm::multiscalar_mult_vartime;
+use super::basepoint::scalarmult_base;
+use super::edwards25519::{EdwardsPoint, base_point, decode, encode, is_identity, multiply_by_cofactor, scalarmult_point, reduce_mod_l, mul_add_mod_l, is_canonical_scalar, L_MINUS_1};
+
+use crate::hash::Sha512;
+use crate::utils::rng;
+
+const SUITE_STRING: u8 = 0x03;
+
+// Keys are Ed25519 keys, the secret scalar and prefix are derived as in RFC 8032 section 5.1.5.
+pub struct ECVRF {
+    pub sec_key: [u8; 32],
+    pub pub_key: [u8; 32],
+    scalar: [u8; 32],
+    prefix: [u8; 32],
+}
+
+impl ECVRF {
+    pub fn new() -> Self {
+        Self {
+            sec_key: [0; 32],
+            pub_key: [0; 32],
+            scalar: [0; 32],
+            prefix: [0; 32],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut secret_key: [u8; 32] = [0; 32];
+        rng::fill_array(&mut secret_key);
+
+        self.set_secret_key(&secret_key);
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) {
+        let h: [u8; 64] = Sha512::new().digest(secret_key);
+
+        self.scalar.copy_from_slice(&h[..32]);
+        self.prefix.copy_from_slice(&h[32..]);
+
+        self.scalar[0] &= 248;
+        self.scalar[31] &= 127;
+        self.scalar[31] |= 64;
+
+        self.sec_key = *secret_key;
+        self.pub_key = encode(&scalarmult_base(&self.scalar));
+    }
+
+    pub fn prove(&self, alpha: &[u8]) -> Result<[u8; 80], EcError> {
+        // RFC 9381 section 5.1
+        let h: EdwardsPoint = encode_to_curve(&self.pub_key, alpha)?;
+        let h_string: [u8; 32] = encode(&h);
+        let gamma: EdwardsPoint = scalarmult_point(&h, &self.scalar);
+
+        // RFC 9381 section 5.4.2.2, nonce generation as for Ed25519 signatures
+        let k: [u8; 32] = reduce_mod_l(&Sha512::new().digest(&[self.prefix.as_slice(), &h_string].concat()));
+
+        let c: [u8; 32] = challenge(&[
+            &decode(&self.pub_key)?,
+            &h,
+            &gamma,
+            &scalarmult_base(&k),
+            &scalarmult_point(&h, &k),
+        ]);
+        let s: [u8; 32] = mul_add_mod_l(&c, &self.scalar, &k);
+
+        let mut pi: [u8; 80] = [0; 80];
+        pi[..32].copy_from_slice(&encode(&gamma));
+        pi[32..48].copy_from_slice(&c[..16]);
+        pi[48..].copy_from_slice(&s);
+
+        Ok(pi)
+    }
+
+    // Returns the VRF output beta of a valid proof.
+    pub fn verify(public_key: &[u8; 32], alpha: &[u8], pi: &[u8; 80]) -> Result<[u8; 64], EcError> {
+        // RFC 9381 section 5.3, including the key validation of section 5.4.5
+        let y: EdwardsPoint = decode(public_key)?;
+        if is_identity(&multiply_by_cofactor(&y)) {
+            return Err(EcError::LowOrderPoint);
+        }
+
+        let (gamma, c, s) = decode_proof(pi)?;
+        let h: EdwardsPoint = encode_to_curve(public_key, alpha)?;
+
+        // U = s * B - c * Y and V = s * H - c * Gamma
+        let c_negated: [u8; 32] = mul_add_mod_l(&c, &L_MINUS_1, &[0; 32]);
+        let u: EdwardsPoint = multiscalar_mult_vartime(&[(s, base_point()), (c_negated, y)]);
+        let v: EdwardsPoint = multiscalar_mult_vartime(&[(s, h), (c_negated, gamma)]);
+
+        if challenge(&[&y, &h, &gamma, &u, &v]) != c {
+            return Err(EcError::InvalidProof);
+        }
+
+        ECVRF::proof_to_hash(pi)
+    }
+
+    pub fn proof_to_hash(pi: &[u8; 80]) -> Result<[u8; 64], EcError> {
+        // RFC 9381 section 5.2
+        let (gamma, _, _) = decode_proof(pi)?;
+
+        let mut input: Vec<u8> = vec![SUITE_STRING, 0x03];
+        input.extend_from_slice(&encode(&multiply_by_cofactor(&gamma)));
+        input.push(0x00);
+
+        Ok(Sha512::new().digest(&input))
+    }
+}
+
+impl Default for ECVRF {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode_to_curve(public_key: &[u8; 32], alpha: &[u8]) -> Result<EdwardsPoint, EcError> {
+    // RFC 9381 section 5.4.1.1, try and increment with the public key as salt. Each attempt
+    // succeeds with probability about 1/2, so 256 failed attempts (InvalidPoint) do not happen in practice.
+    for counter in 0..=255u8 {
+        let mut input: Vec<u8> = vec![SUITE_STRING, 0x01];
+        input.extend_from_slice(public_key);
+        input.extend_from_slice(alpha);
+        input.push(counter);
+        input.push(0x00);
+
+        let hash: [u8; 64] = Sha512::new().digest(&input);
+        if let Ok(point) = decode(hash[..32].try_into().unwrap()) {
+            return Ok(multiply_by_cofactor(&point));
+        }
+    }
+
+    Err(EcError::InvalidPoint)
+}
+
+fn challenge(points: &[&EdwardsPoint]) -> [u8; 32] {
+    // RFC 9381 section 5.4.3, the first 16 bytes of the hash as scalar
+    let mut input: Vec<u8> = vec![SUITE_STRING, 0x02];
+    for point in points {
+        input.extend_from_slice(&encode(point));
+    }
+    input.push(0x00);
+
+    let mut c: [u8; 32] = [0; 32];
+    c[..16].copy_from_slice(&Sha512::new().digest(&input)[..16]);
+    c
+}
+
+fn decode_proof(pi: &[u8; 80]) -> Result<(EdwardsPoint, [u8; 32], [u8; 32]), EcError> {
+    // RFC 9381 section 5.4.4, Gamma, c and s of a proof
+    let gamma: EdwardsPoint = decode(pi[..32].try_into().unwrap())?;
+
+    let mut c: [u8; 32] = [0; 32];
+    c[..16].copy_from_slice(&pi[32..48]);
+
+    let s: [u8; 32] = pi[48..].try_into().unwrap();
+    if !is_canonical_scalar(&s) {
+        return Err(EcError::InvalidProof);
+    }
+
+    Ok((gamma, c, s))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::ECVRF;
+    use crate::crypto::ec::EcError;
+    use crate::utils::testing::hex;
+
+    fn check_ecvrf(secret_key: &str, public_key: &str, alpha: &str, pi: &str, beta: &str) {
+        let mut ecvrf: ECVRF = ECVRF::new();
+        ecvrf.set_secret_key(&hex(secret_key).try_into().unwrap());
+
+        let expected_pi: [u8; 80] = hex(pi).try_into().unwrap();
+
+        assert_eq!(ecvrf.pub_key.to_vec(), hex(public_key));
+        assert_eq!(ecvrf.prove(&hex(alpha)).unwrap(), expected_pi);
+        assert_eq!(ECVRF::proof_to_hash(&expected_pi).unwrap().to_vec(), hex(beta));
+        assert_eq!(ECVRF::verify(&ecvrf.pub_key, &hex(alpha), &expected_pi).unwrap().to_vec(), hex(beta));
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9381#appendix-B.3 (Example 16 - 18)
+        check_ecvrf(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805",
+            "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae",
+        );
+        check_ecvrf(
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed5933bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926da3ef39226bbc355bdc9850112c8f4b02",
+            "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031",
+        );
+        check_ecvrf(
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf8096bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a2d41b00b05081ed0f58ee5e31b3a970e",
+            "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c452118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f",
+        );
+    }
+
+    #[test]
+    fn reject_invalid_proofs() {
+        let mut ecvrf: ECVRF = ECVRF::new();
+        ecvrf.gen_key_pair();
+
+        let pi: [u8; 80] = ecvrf.prove("input".as_bytes()).unwrap();
+        let beta: [u8; 64] = ECVRF::verify(&ecvrf.pub_key, "input".as_bytes(), &pi).unwrap();
+        assert_eq!(beta, ECVRF::proof_to_hash(&pi).unwrap());
+
+        // the output is unique per key and input
+        assert_eq!(ecvrf.prove("input".as_bytes()).unwrap(), pi);
+        assert_ne!(ECVRF::proof_to_hash(&ecvrf.prove("other".as_bytes()).unwrap()).unwrap(), beta);
+
+        assert_eq!(ECVRF::verify(&ecvrf.pub_key, "other".as_bytes(), &pi), Err(EcError::InvalidProof));
+
+        let mut other: ECVRF = ECVRF::new();
+        other.gen_key_pair();
+        assert_eq!(ECVRF::verify(&other.pub_key, "input".as_bytes(), &pi), Err(EcError::InvalidProof));
+
+        for i in [0, 40, 60] {
+            let mut modified: [u8; 80] = pi;
+            modified[i] ^= 0x01;
+            assert!(ECVRF::verify(&ecvrf.pub_key, "input".as_bytes(), &modified).is_err());
+        }
+
+        // s not reduced modulo L
+        let mut unreduced: [u8; 80] = pi;
+        unreduced[79] = 0xff;
+        assert_eq!(ECVRF::verify(&ecvrf.pub_key, "input".as_bytes(), &unreduced), Err(EcError::InvalidProof));
+
+        // the identity as public key has low order
+        let mut identity: [u8; 32] = [0; 32];
+        identity[0] = 1;
+        assert_eq!(ECVRF::verify(&identity, "input".as_bytes(), &pi), Err(EcError::LowOrderPoint));
+    }
+}
@@ -0,0 +1,323 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8032#section-5.1 and https://eprint.iacr.org/2020/1244.pdf (batch verification)
+*/
+
+#![allow(dead_code)]
+
+use super::{EcError, BatchError};
+use super::msm::multiscalar_mult_vartime;
+use super::basepoint::scalarmult_base;
+use super::edwards25519::{EdwardsPoint, base_point, decode, encode, is_identity, multiply_by_cofactor, point_add, scalarmult_point, to_montgomery_u, reduce_mod_l, mul_add_mod_l, is_canonical_scalar, L_MINUS_1};
+
+use crate::hash::Sha512;
+use crate::utils::rng;
+
+pub struct Ed25519 {
+    pub sec_key: [u8; 32],
+    pub pub_key: [u8; 32],
+    scalar: [u8; 32],
+    prefix: [u8; 32],
+}
+
+impl Ed25519 {
+    pub fn new() -> Self {
+        Self {
+            sec_key: [0; 32],
+            pub_key: [0; 32],
+            scalar: [0; 32],
+            prefix: [0; 32],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut secret_key: [u8; 32] = [0; 32];
+        rng::fill_array(&mut secret_key);
+
+        self.set_secret_key(&secret_key);
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) {
+        // RFC 8032 section 5.1.5
+        let h: [u8; 64] = Sha512::new().digest(secret_key);
+
+        self.scalar.copy_from_slice(&h[..32]);
+        self.prefix.copy_from_slice(&h[32..]);
+
+        self.scalar[0] &= 248;
+        self.scalar[31] &= 127;
+        self.scalar[31] |= 64;
+
+        self.sec_key = *secret_key;
+        self.pub_key = encode(&scalarmult_base(&self.scalar));
+    }
+
+    // The clamped scalar derived from the seed is a valid X25519 secret key for the same identity.
+    pub fn to_x25519_secret_key(&self) -> [u8; 32] {
+        self.scalar
+    }
+
+    pub fn to_x25519_public_key(public_key: &[u8; 32]) -> Result<[u8; 32], EcError> {
+        Ok(to_montgomery_u(&decode(public_key)?))
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        // RFC 8032 section 5.1.6
+        let r: [u8; 32] = reduce_mod_l(&Sha512::new().digest(&[self.prefix.as_slice(), message].concat()));
+        let big_r: [u8; 32] = encode(&scalarmult_base(&r));
+
+        let k: [u8; 32] = challenge(&big_r, &self.pub_key, message);
+        let s: [u8; 32] = mul_add_mod_l(&k, &self.scalar, &r);
+
+        let mut signature: [u8; 64] = [0; 64];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&s);
+
+        signature
+    }
+
+    pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> Result<(), EcError> {
+        // RFC 8032 section 5.1.7
+        let (big_r, big_a, s, k) = parse(public_key, message, signature)?;
+
+        // [8][S]B = [8]R + [8][k]A
+        let left: EdwardsPoint = scalarmult_base(&s);
+        let right: EdwardsPoint = point_add(&big_r, &scalarmult_point(&big_a, &k));
+
+        if encode(&multiply_by_cofactor(&left)) != encode(&multiply_by_cofactor(&right)) {
+            return Err(EcError::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    // Verifies all signatures at once. With random 128-bit z_i the batch equation
+    // [8](-(sum z_i * S_i) B + sum z_i * R_i + sum (z_i * k_i) A_i) = 0
+    // holds for an invalid signature with probability 2^-128 at most. A failing
+    // batch is verified signature by signature, the error lists the bad indices.
+    pub fn verify_batch(public_keys: &[[u8; 32]], messages: &[&[u8]], signatures: &[[u8; 64]]) -> Result<(), BatchError> {
+        if public_keys.len() != messages.len() || public_keys.len() != signatures.len() {
+            return Err(BatchError::LengthMismatch);
+        }
+
+        let mut terms: Vec<([u8; 32], EdwardsPoint)> = vec![([0; 32], base_point())];
+        let mut s_sum: [u8; 32] = [0; 32];
+        let mut parsed: bool = true;
+
+        for i in 0..signatures.len() {
+            let (big_r, big_a, s, k) = match parse(&public_keys[i], messages[i], &signatures[i]) {
+                Ok(values) => values,
+                Err(_) => {
+                    parsed = false;
+                    break;
+                }
+            };
+
+            let mut z: [u8; 32] = [0; 32];
+            rng::fill_bytes(&mut z[..16]);
+
+            s_sum = mul_add_mod_l(&z, &s, &s_sum);
+
+            terms.push((z, big_r));
+            terms.push((mul_add_mod_l(&z, &k, &[0; 32]), big_a));
+        }
+
+        if parsed {
+            terms[0].0 = mul_add_mod_l(&s_sum, &L_MINUS_1, &[0; 32]);
+
+            if is_identity(&multiply_by_cofactor(&multiscalar_mult_vartime(&terms))) {
+                return Ok(());
+            }
+        }
+
+        let invalid: Vec<usize> = (0..signatures.len())
+            .filter(|&i| Ed25519::verify(&public_keys[i], messages[i], &signatures[i]).is_err())
+            .collect();
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(BatchError::InvalidSignatures(invalid))
+        }
+    }
+}
+
+impl Default for Ed25519 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn challenge(big_r: &[u8; 32], public_key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    reduce_mod_l(&Sha512::new().digest(&[big_r.as_slice(), public_key, message].concat()))
+}
+
+fn parse(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> Result<(EdwardsPoint, EdwardsPoint, [u8; 32], [u8; 32]), EcError> {
+    // R, A, S and k = H(R || A || M) mod L of a signature
+    let big_r_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+    let s: [u8; 32] = signature[32..].try_into().unwrap();
+
+    let big_r: EdwardsPoint = decode(&big_r_bytes)?;
+    let big_a: EdwardsPoint = decode(public_key)?;
+
+    // S has to be fully reduced modulo L to prevent malleability.
+    if !is_canonical_scalar(&s) {
+        return Err(EcError::InvalidSignature);
+    }
+
+    Ok((big_r, big_a, s, challenge(&big_r_bytes, public_key, message)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Ed25519;
+    use crate::crypto::ec::{ECDH, EcError, BatchError};
+    use crate::utils::testing::hex;
+
+    fn check_ed25519(secret_key: &str, public_key: &str, message: &str, signature: &str) {
+        let mut ed25519: Ed25519 = Ed25519::new();
+        ed25519.set_secret_key(&hex(secret_key).try_into().unwrap());
+
+        let expected_signature: [u8; 64] = hex(signature).try_into().unwrap();
+
+        assert_eq!(ed25519.pub_key.to_vec(), hex(public_key));
+        assert_eq!(ed25519.sign(&hex(message)), expected_signature);
+        assert_eq!(Ed25519::verify(&ed25519.pub_key, &hex(message), &expected_signature), Ok(()));
+    }
+
+    #[test]
+    fn x25519_conversion() {
+        // RFC 8032 TEST 1 key, the expected values computed with pyca/cryptography's X25519
+        let mut ed25519: Ed25519 = Ed25519::new();
+        ed25519.set_secret_key(&hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").try_into().unwrap());
+
+        assert_eq!(ed25519.to_x25519_secret_key().to_vec(), hex("307c83864f2833cb427a2ef1c00a013cfdff2768d980c0a3a520f006904de94f"));
+        assert_eq!(Ed25519::to_x25519_public_key(&ed25519.pub_key).unwrap().to_vec(), hex("d85e07ec22b0ad881537c2f44d662d1a143cf830c57aca4305d85c7a90f6b62e"));
+
+        // converted keys agree with the ECDH API
+        let mut alice: Ed25519 = Ed25519::new();
+        alice.gen_key_pair();
+        let mut bob: Ed25519 = Ed25519::new();
+        bob.gen_key_pair();
+
+        let mut alice_ecdh: ECDH = ECDH::new();
+        alice_ecdh.set_secret_key(&alice.to_x25519_secret_key());
+        let mut bob_ecdh: ECDH = ECDH::new();
+        bob_ecdh.set_secret_key(&bob.to_x25519_secret_key());
+
+        assert_eq!(alice_ecdh.pub_key, Ed25519::to_x25519_public_key(&alice.pub_key).unwrap());
+
+        let key1: [u8; 32] = alice_ecdh.symmetric_key(&Ed25519::to_x25519_public_key(&bob.pub_key).unwrap());
+        let key2: [u8; 32] = bob_ecdh.symmetric_key(&Ed25519::to_x25519_public_key(&alice.pub_key).unwrap());
+        assert_eq!(key1, key2);
+
+        let mut not_on_curve: [u8; 32] = [0; 32];
+        not_on_curve[0] = 0x02;
+        assert_eq!(Ed25519::to_x25519_public_key(&not_on_curve), Err(EcError::InvalidPoint));
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc8032#section-7.1 (TEST 1 - 3)
+        check_ed25519(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        );
+        check_ed25519(
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        );
+        check_ed25519(
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        );
+    }
+
+    #[test]
+    fn reject_modified_signatures() {
+        let mut ed25519: Ed25519 = Ed25519::new();
+        ed25519.gen_key_pair();
+
+        let message: &[u8] = "lightweight blockchain".as_bytes();
+        let signature: [u8; 64] = ed25519.sign(message);
+
+        assert_eq!(Ed25519::verify(&ed25519.pub_key, message, &signature), Ok(()));
+        assert_eq!(Ed25519::verify(&ed25519.pub_key, "other message".as_bytes(), &signature), Err(EcError::InvalidSignature));
+
+        let mut modified: [u8; 64] = signature;
+        modified[40] ^= 0x01;
+        assert_eq!(Ed25519::verify(&ed25519.pub_key, message, &modified), Err(EcError::InvalidSignature));
+
+        // S = L is out of range
+        let mut out_of_range: [u8; 64] = signature;
+        out_of_range[32..].copy_from_slice(&hex("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010"));
+        assert_eq!(Ed25519::verify(&ed25519.pub_key, message, &out_of_range), Err(EcError::InvalidSignature));
+    }
+
+    // public keys, messages and signatures
+    type Batch = (Vec<[u8; 32]>, Vec<Vec<u8>>, Vec<[u8; 64]>);
+
+    fn signed_batch(size: usize) -> Batch {
+        let mut public_keys: Vec<[u8; 32]> = Vec::new();
+        let mut messages: Vec<Vec<u8>> = Vec::new();
+        let mut signatures: Vec<[u8; 64]> = Vec::new();
+
+        for i in 0..size {
+            let mut ed25519: Ed25519 = Ed25519::new();
+            ed25519.gen_key_pair();
+
+            let message: Vec<u8> = format!("transaction {}", i).into_bytes();
+            signatures.push(ed25519.sign(&message));
+            public_keys.push(ed25519.pub_key);
+            messages.push(message);
+        }
+
+        (public_keys, messages, signatures)
+    }
+
+    #[test]
+    fn batch_verification() {
+        let (public_keys, messages, mut signatures) = signed_batch(8);
+        let message_slices: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+
+        assert_eq!(Ed25519::verify_batch(&public_keys, &message_slices, &signatures), Ok(()));
+        assert_eq!(Ed25519::verify_batch(&[], &[], &[]), Ok(()));
+
+        // signatures swapped between two transactions
+        signatures.swap(2, 5);
+        assert_eq!(Ed25519::verify_batch(&public_keys, &message_slices, &signatures), Err(BatchError::InvalidSignatures(vec![2, 5])));
+        signatures.swap(2, 5);
+
+        signatures[7][50] ^= 0x01;
+        assert_eq!(Ed25519::verify_batch(&public_keys, &message_slices, &signatures), Err(BatchError::InvalidSignatures(vec![7])));
+    }
+
+    #[test]
+    fn batch_with_invalid_encodings() {
+        let (mut public_keys, messages, signatures) = signed_batch(3);
+        let message_slices: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+
+        // y = 2 is not the y-coordinate of a point
+        public_keys[1] = [0; 32];
+        public_keys[1][0] = 0x02;
+
+        assert_eq!(Ed25519::verify_batch(&public_keys, &message_slices, &signatures), Err(BatchError::InvalidSignatures(vec![1])));
+    }
+
+    #[test]
+    fn batch_with_mismatched_lengths() {
+        let (public_keys, messages, signatures) = signed_batch(3);
+        let message_slices: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+
+        assert_eq!(Ed25519::verify_batch(&public_keys[..2], &message_slices, &signatures), Err(BatchError::LengthMismatch));
+        assert_eq!(Ed25519::verify_batch(&public_keys, &message_slices, &signatures[..1]), Err(BatchError::LengthMismatch));
+        assert_eq!(Ed25519::verify_batch(&[], &[], &signatures), Err(BatchError::LengthMismatch));
+    }
+}
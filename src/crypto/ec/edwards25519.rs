@@ -0,0 +1,242 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8032#section-5.1 and https://tweetnacl.cr.yp.to/20140427/tweetnacl.c
+*/
+
+#![allow(dead_code)]
+
+use super::{Curve, EcError};
+use super::utils::{reduce_mod, mul_add_mod};
+use super::x25519::{FieldElement, SQRT_M1, pack25519, unpack25519, fadd, fsub, fmul, fneg, finverse, swap25519, is_canonical, fequal, is_zero, is_negative};
+
+// d = -121665 / 121666 and 2 * d in 16-bit limbs
+pub const D: FieldElement = [0x78a3, 0x1359, 0x4dca, 0x75eb, 0xd8ab, 0x4141, 0x0a4d, 0x0070, 0xe898, 0x7779, 0x4079, 0x8cc7, 0xfe73, 0x2b6f, 0x6cee, 0x5203];
+pub const D2: FieldElement = [0xf159, 0x26b2, 0x9b94, 0xebd6, 0xb156, 0x8283, 0x149a, 0x00e0, 0xd130, 0xeef3, 0x80f2, 0x198e, 0xfce7, 0x56df, 0xd9dc, 0x2406];
+
+// L = 2^252 + 27742317777372353535851937790883648493 in little-endian 32-bit limbs
+const L: [u64; 9] = [0x5cf5d3ed, 0x5812631a, 0xa2f79cd6, 0x14def9de, 0x0, 0x0, 0x0, 0x10000000, 0x0];
+
+// L - 1, multiplying by it negates modulo L
+pub const L_MINUS_1: [u8; 32] = [0xec, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10];
+
+// Extended coordinates (X : Y : Z : T) with x = X / Z, y = Y / Z and x * y = T / Z
+#[derive(Copy, Clone, Debug)]
+pub struct EdwardsPoint {
+    pub x: FieldElement,
+    pub y: FieldElement,
+    pub z: FieldElement,
+    pub t: FieldElement,
+}
+
+pub fn one() -> FieldElement {
+    let mut one: FieldElement = [0; 16];
+    one[0] = 1;
+    one
+}
+
+pub fn identity() -> EdwardsPoint {
+    EdwardsPoint { x: [0; 16], y: one(), z: one(), t: [0; 16] }
+}
+
+pub fn base_point() -> EdwardsPoint {
+    let curve: Curve = Curve::twisted_edwards();
+
+    EdwardsPoint {
+        x: curve.g.x,
+        y: curve.g.y,
+        z: one(),
+        t: fmul(&curve.g.x, &curve.g.y),
+    }
+}
+
+pub fn point_add(p: &EdwardsPoint, q: &EdwardsPoint) -> EdwardsPoint {
+    // add-2008-hwcd-3 for a = -1, complete for all inputs including p = q
+    let a: FieldElement = fmul(&fsub(&p.y, &p.x), &fsub(&q.y, &q.x));
+    let b: FieldElement = fmul(&fadd(&p.y, &p.x), &fadd(&q.y, &q.x));
+    let c: FieldElement = fmul(&fmul(&p.t, &q.t), &D2);
+    let d: FieldElement = fmul(&p.z, &fadd(&q.z, &q.z));
+    let e: FieldElement = fsub(&b, &a);
+    let f: FieldElement = fsub(&d, &c);
+    let g: FieldElement = fadd(&d, &c);
+    let h: FieldElement = fadd(&b, &a);
+
+    EdwardsPoint {
+        x: fmul(&e, &f),
+        y: fmul(&h, &g),
+        z: fmul(&g, &f),
+        t: fmul(&e, &h),
+    }
+}
+
+pub fn point_negate(point: &EdwardsPoint) -> EdwardsPoint {
+    EdwardsPoint {
+        x: fsub(&[0; 16], &point.x),
+        y: point.y,
+        z: point.z,
+        t: fsub(&[0; 16], &point.t),
+    }
+}
+
+pub fn point_swap(p: &mut EdwardsPoint, q: &mut EdwardsPoint, bit: i64) {
+    swap25519(&mut p.x, &mut q.x, bit);
+    swap25519(&mut p.y, &mut q.y, bit);
+    swap25519(&mut p.z, &mut q.z, bit);
+    swap25519(&mut p.t, &mut q.t, bit);
+}
+
+pub fn scalarmult_point(point: &EdwardsPoint, scalar: &[u8; 32]) -> EdwardsPoint {
+    // constant-time ladder over all 256 bits of the scalar
+    let mut p: EdwardsPoint = identity();
+    let mut q: EdwardsPoint = *point;
+
+    for i in (0..256).rev() {
+        let bit = ((scalar[i >> 3] >> (i & 7)) & 1) as i64;
+        point_swap(&mut p, &mut q, bit);
+        q = point_add(&q, &p);
+        p = point_add(&p, &p);
+        point_swap(&mut p, &mut q, bit);
+    }
+
+    p
+}
+
+pub fn multiply_by_cofactor(point: &EdwardsPoint) -> EdwardsPoint {
+    let mut output: EdwardsPoint = *point;
+    for _ in 0..3 {
+        output = point_add(&output, &output);
+    }
+
+    output
+}
+
+pub fn is_identity(point: &EdwardsPoint) -> bool {
+    // x = 0 and y = 1, i.e. X = 0 and Y = Z
+    is_zero(&point.x) && fequal(&point.y, &point.z)
+}
+
+pub fn encode(point: &EdwardsPoint) -> [u8; 32] {
+    let z_inverse: FieldElement = finverse(&point.z);
+    let x: [u8; 32] = pack25519(&mut fmul(&point.x, &z_inverse));
+    let mut output: [u8; 32] = pack25519(&mut fmul(&point.y, &z_inverse));
+
+    output[31] ^= (x[0] & 1) << 7;
+    output
+}
+
+pub fn to_montgomery_u(point: &EdwardsPoint) -> [u8; 32] {
+    // birational map u = (1 + y) / (1 - y) from https://datatracker.ietf.org/doc/html/rfc7748#section-4.1
+    let numerator: FieldElement = fadd(&point.z, &point.y);
+    let denominator: FieldElement = fsub(&point.z, &point.y);
+
+    pack25519(&mut fmul(&numerator, &finverse(&denominator)))
+}
+
+pub fn pow_p_minus_5_div_8(input: &FieldElement) -> FieldElement {
+    // input^((p - 5) / 8), where (p - 5) / 8 = 2^252 - 3 has all of its 252 bits set except bit 1.
+    let mut c: FieldElement = *input;
+
+    for i in (0..=250).rev() {
+        c = fmul(&c, &c);
+
+        if i != 1 {
+            c = fmul(&c, input);
+        }
+    }
+
+    c
+}
+
+pub fn decode(input: &[u8; 32]) -> Result<EdwardsPoint, EcError> {
+    // RFC 8032 section 5.1.3
+    let x_0: u8 = input[31] >> 7;
+
+    let mut y_bytes: [u8; 32] = *input;
+    y_bytes[31] &= 0x7f;
+    if !is_canonical(&y_bytes) {
+        return Err(EcError::InvalidPoint);
+    }
+    let y: FieldElement = unpack25519(&y_bytes);
+
+    // x^2 = (y^2 - 1) / (d * y^2 + 1) = u / v
+    let y_power_2: FieldElement = fmul(&y, &y);
+    let u: FieldElement = fsub(&y_power_2, &one());
+    let v: FieldElement = fadd(&fmul(&D, &y_power_2), &one());
+
+    // x = u * v^3 * (u * v^7)^((p - 5) / 8)
+    let v_power_3: FieldElement = fmul(&fmul(&v, &v), &v);
+    let v_power_7: FieldElement = fmul(&fmul(&v_power_3, &v_power_3), &v);
+    let mut x: FieldElement = fmul(
+        &fmul(&u, &v_power_3),
+        &pow_p_minus_5_div_8(&fmul(&u, &v_power_7)),
+    );
+
+    let v_x_power_2: FieldElement = fmul(&v, &fmul(&x, &x));
+    if !fequal(&v_x_power_2, &u) {
+        if !fequal(&v_x_power_2, &fneg(&u)) {
+            return Err(EcError::InvalidPoint);
+        }
+        x = fmul(&x, &SQRT_M1);
+    }
+
+    if is_zero(&x) && x_0 == 1 {
+        return Err(EcError::InvalidPoint);
+    }
+    if is_negative(&x) as u8 != x_0 {
+        x = fneg(&x);
+    }
+
+    Ok(EdwardsPoint { x, y, z: one(), t: fmul(&x, &y) })
+}
+
+pub fn reduce_mod_l(input: &[u8]) -> [u8; 32] {
+    reduce_mod(input, &L)
+}
+
+pub fn mul_add_mod_l(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    mul_add_mod(a, b, c, &L)
+}
+
+pub fn is_canonical_scalar(scalar: &[u8; 32]) -> bool {
+    reduce_mod_l(scalar) == *scalar
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{base_point, decode, encode, identity, is_identity, multiply_by_cofactor, point_add, point_negate, reduce_mod_l, mul_add_mod_l, scalarmult_point};
+    use crate::crypto::ec::{Curve, EcError};
+
+    #[test]
+    fn base_point_encoding() {
+        // y = 4 / 5 with an even x-coordinate
+        let mut expected: [u8; 32] = [0x66; 32];
+        expected[0] = 0x58;
+
+        assert_eq!(encode(&base_point()), expected);
+        assert_eq!(encode(&decode(&expected).unwrap()), expected);
+
+        // the encoding of y = 2 has no x-coordinate
+        let mut not_on_curve: [u8; 32] = [0; 32];
+        not_on_curve[0] = 0x02;
+        assert_eq!(decode(&not_on_curve).unwrap_err(), EcError::InvalidPoint);
+    }
+
+    #[test]
+    fn group_order() {
+        let order: [u8; 32] = Curve::twisted_edwards().n;
+
+        assert!(is_identity(&scalarmult_point(&base_point(), &order)));
+        assert!(!is_identity(&base_point()));
+        assert!(is_identity(&point_add(&base_point(), &point_negate(&base_point()))));
+        assert!(is_identity(&multiply_by_cofactor(&identity())));
+
+        assert_eq!(reduce_mod_l(&order), [0; 32]);
+
+        // (L - 1) * (L - 1) + 0 = 1 mod L
+        let mut l_minus_1: [u8; 32] = order;
+        l_minus_1[0] -= 1;
+        let mut one: [u8; 32] = [0; 32];
+        one[0] = 1;
+        assert_eq!(mul_add_mod_l(&l_minus_1, &l_minus_1, &[0; 32]), one);
+    }
+}
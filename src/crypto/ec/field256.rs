@@ -0,0 +1,290 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://cacr.uwaterloo.ca/hac/about/chap14.pdf (14.36, Montgomery multiplication)
+*/
+
+#![allow(dead_code)]
+
+// Four 64-bit limbs, least significant limb first, 4 * 64 = 256 bits.
+pub type FieldElement256 = [u64; 4];
+
+// Arithmetic modulo an odd modulus below 2^256. Elements handed to add, sub
+// and mul are in Montgomery form (a * 2^256 mod m) and always fully reduced.
+#[derive(Copy, Clone)]
+pub struct PrimeField256 {
+    pub modulus: FieldElement256,
+    m_prime: u64,
+    r2: FieldElement256,
+}
+
+impl PrimeField256 {
+    pub fn new(modulus: &FieldElement256) -> Self {
+        // m' = -m^-1 mod 2^64 by Newton iteration, every step doubles the correct bits
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inverse)));
+        }
+
+        let mut field: PrimeField256 = Self {
+            modulus: *modulus,
+            m_prime: inverse.wrapping_neg(),
+            r2: [0; 4],
+        };
+
+        // R^2 = 2^512 mod m by doubling 1 for 512 times
+        let mut r2: FieldElement256 = [1, 0, 0, 0];
+        for _ in 0..512 {
+            r2 = field.add(&r2, &r2);
+        }
+        field.r2 = r2;
+
+        field
+    }
+
+    pub fn zero(&self) -> FieldElement256 {
+        [0; 4]
+    }
+
+    pub fn one(&self) -> FieldElement256 {
+        self.montgomery_form(&[1, 0, 0, 0])
+    }
+
+    pub fn montgomery_form(&self, input: &FieldElement256) -> FieldElement256 {
+        // also reduces inputs that are not below m
+        self.mul(input, &self.r2)
+    }
+
+    pub fn canonical_form(&self, input: &FieldElement256) -> FieldElement256 {
+        self.mul(input, &[1, 0, 0, 0])
+    }
+
+    pub fn unpack(&self, input: &[u8; 32]) -> FieldElement256 {
+        self.montgomery_form(&unpack256(input))
+    }
+
+    pub fn pack(&self, input: &FieldElement256) -> [u8; 32] {
+        pack256(&self.canonical_form(input))
+    }
+
+    pub fn add(&self, x: &FieldElement256, y: &FieldElement256) -> FieldElement256 {
+        let mut sum: FieldElement256 = [0; 4];
+        let mut carry: u128 = 0;
+        for i in 0..4 {
+            let value: u128 = x[i] as u128 + y[i] as u128 + carry;
+            sum[i] = value as u64;
+            carry = value >> 64;
+        }
+
+        self.subtract_modulus_if_needed(&sum, carry as u64)
+    }
+
+    pub fn sub(&self, x: &FieldElement256, y: &FieldElement256) -> FieldElement256 {
+        let (difference, borrow) = sub256(x, y);
+
+        // add m back if the subtraction borrowed
+        let mask: u64 = borrow.wrapping_neg();
+        let mut output: FieldElement256 = [0; 4];
+        let mut carry: u128 = 0;
+        for i in 0..4 {
+            let value: u128 = difference[i] as u128 + (self.modulus[i] & mask) as u128 + carry;
+            output[i] = value as u64;
+            carry = value >> 64;
+        }
+
+        output
+    }
+
+    pub fn neg(&self, x: &FieldElement256) -> FieldElement256 {
+        self.sub(&[0; 4], x)
+    }
+
+    pub fn mul(&self, x: &FieldElement256, y: &FieldElement256) -> FieldElement256 {
+        // CIOS Montgomery multiplication, returns x * y / 2^256 mod m
+        let mut t: [u64; 6] = [0; 6];
+
+        for x_i in x.iter() {
+            let mut carry: u128 = 0;
+            for j in 0..4 {
+                let value: u128 = t[j] as u128 + (*x_i as u128) * (y[j] as u128) + carry;
+                t[j] = value as u64;
+                carry = value >> 64;
+            }
+            let value: u128 = t[4] as u128 + carry;
+            t[4] = value as u64;
+            t[5] = (value >> 64) as u64;
+
+            let m: u64 = t[0].wrapping_mul(self.m_prime);
+            let mut carry: u128 = (t[0] as u128 + (m as u128) * (self.modulus[0] as u128)) >> 64;
+            for j in 1..4 {
+                let value: u128 = t[j] as u128 + (m as u128) * (self.modulus[j] as u128) + carry;
+                t[j - 1] = value as u64;
+                carry = value >> 64;
+            }
+            let value: u128 = t[4] as u128 + carry;
+            t[3] = value as u64;
+            t[4] = t[5] + (value >> 64) as u64;
+            t[5] = 0;
+        }
+
+        self.subtract_modulus_if_needed(&[t[0], t[1], t[2], t[3]], t[4])
+    }
+
+    pub fn square(&self, x: &FieldElement256) -> FieldElement256 {
+        self.mul(x, x)
+    }
+
+    pub fn pow(&self, x: &FieldElement256, exponent: &FieldElement256) -> FieldElement256 {
+        // square and multiply, the exponent is public
+        let mut output: FieldElement256 = self.one();
+
+        for i in (0..256).rev() {
+            output = self.square(&output);
+
+            if (exponent[i >> 6] >> (i & 63)) & 1 == 1 {
+                output = self.mul(&output, x);
+            }
+        }
+
+        output
+    }
+
+    pub fn inverse(&self, x: &FieldElement256) -> FieldElement256 {
+        // Fermat: x^(m - 2), m has to be prime
+        let (exponent, _) = sub256(&self.modulus, &[2, 0, 0, 0]);
+        self.pow(x, &exponent)
+    }
+
+    pub fn sqrt(&self, x: &FieldElement256) -> Option<FieldElement256> {
+        // only for m = 3 mod 4: x^((m + 1) / 4) is a root if x is a square,
+        // where (m + 1) / 4 = (m >> 2) + 1
+        let m: FieldElement256 = self.modulus;
+        let mut exponent: FieldElement256 = [
+            (m[0] >> 2) | (m[1] << 62),
+            (m[1] >> 2) | (m[2] << 62),
+            (m[2] >> 2) | (m[3] << 62),
+            m[3] >> 2,
+        ];
+        for limb in exponent.iter_mut() {
+            *limb = limb.wrapping_add(1);
+            if *limb != 0 {
+                break;
+            }
+        }
+
+        let root: FieldElement256 = self.pow(x, &exponent);
+
+        if self.square(&root) == *x {
+            Some(root)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_zero(&self, x: &FieldElement256) -> bool {
+        (x[0] | x[1] | x[2] | x[3]) == 0
+    }
+
+    pub fn is_canonical(&self, input: &[u8; 32]) -> bool {
+        less_than256(&unpack256(input), &self.modulus)
+    }
+
+    fn subtract_modulus_if_needed(&self, x: &FieldElement256, high: u64) -> FieldElement256 {
+        // x + high * 2^256 is below 2m, keep x - m unless it borrows from high
+        let (difference, borrow) = sub256(x, &self.modulus);
+        let mask: u64 = (borrow & !high & 1).wrapping_neg();
+
+        let mut output: FieldElement256 = [0; 4];
+        for i in 0..4 {
+            output[i] = (x[i] & mask) | (difference[i] & !mask);
+        }
+
+        output
+    }
+}
+
+pub fn unpack256(input: &[u8; 32]) -> FieldElement256 {
+    // big-endian bytes to little-endian limbs
+    let mut output: FieldElement256 = [0; 4];
+    for (i, limb) in output.iter_mut().enumerate() {
+        *limb = u64::from_be_bytes(input[24 - 8 * i..32 - 8 * i].try_into().unwrap());
+    }
+
+    output
+}
+
+pub fn pack256(input: &FieldElement256) -> [u8; 32] {
+    let mut output: [u8; 32] = [0; 32];
+    for (i, limb) in input.iter().enumerate() {
+        output[24 - 8 * i..32 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+    }
+
+    output
+}
+
+pub fn sub256(x: &FieldElement256, y: &FieldElement256) -> (FieldElement256, u64) {
+    let mut output: FieldElement256 = [0; 4];
+    let mut borrow: u64 = 0;
+    for i in 0..4 {
+        let (difference, borrow_1) = x[i].overflowing_sub(y[i]);
+        let (difference, borrow_2) = difference.overflowing_sub(borrow);
+        output[i] = difference;
+        borrow = (borrow_1 | borrow_2) as u64;
+    }
+
+    (output, borrow)
+}
+
+pub fn less_than256(x: &FieldElement256, y: &FieldElement256) -> bool {
+    sub256(x, y).1 == 1
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{PrimeField256, FieldElement256, unpack256, pack256};
+
+    // p = 2^256 - 2^32 - 977 of secp256k1
+    const P: FieldElement256 = [0xfffffffefffffc2f, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff];
+
+    #[test]
+    fn montgomery_roundtrip() {
+        let field: PrimeField256 = PrimeField256::new(&P);
+        let value: FieldElement256 = [0x0123456789abcdef, 0xfedcba9876543210, 0x1111111111111111, 0x7777777777777777];
+
+        assert_eq!(field.canonical_form(&field.montgomery_form(&value)), value);
+        assert_eq!(field.canonical_form(&field.one()), [1, 0, 0, 0]);
+
+        // p + 1 is reduced to 1
+        let p_plus_1: FieldElement256 = [P[0] + 1, P[1], P[2], P[3]];
+        assert_eq!(field.canonical_form(&field.montgomery_form(&p_plus_1)), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn field_arithmetic() {
+        let field: PrimeField256 = PrimeField256::new(&P);
+        let x: FieldElement256 = field.montgomery_form(&[7, 0, 0, 0]);
+        let y: FieldElement256 = field.montgomery_form(&[P[0] - 3, P[1], P[2], P[3]]);
+
+        // 7 + (-3) = 4, 7 - (-3) = 10, 7 * (-3) = -21
+        assert_eq!(field.canonical_form(&field.add(&x, &y)), [4, 0, 0, 0]);
+        assert_eq!(field.canonical_form(&field.sub(&x, &y)), [10, 0, 0, 0]);
+        assert_eq!(field.canonical_form(&field.mul(&x, &y)), [P[0] - 21, P[1], P[2], P[3]]);
+
+        assert_eq!(field.mul(&x, &field.inverse(&x)), field.one());
+        assert_eq!(field.add(&y, &field.neg(&y)), field.zero());
+
+        let root: FieldElement256 = field.sqrt(&field.square(&y)).unwrap();
+        assert!(root == y || root == field.neg(&y));
+        assert!(field.sqrt(&field.neg(&field.one())).is_none());
+    }
+
+    #[test]
+    fn byte_conversion() {
+        let mut bytes: [u8; 32] = [0; 32];
+        bytes[0] = 0x80;
+        bytes[31] = 0x01;
+
+        assert_eq!(unpack256(&bytes), [1, 0, 0, 0x8000000000000000]);
+        assert_eq!(pack256(&unpack256(&bytes)), bytes);
+    }
+}
@@ -0,0 +1,279 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://cr.yp.to/ecdh/curve25519-20060209.pdf (section 4, radix 2^51) and https://github.com/floodyberry/curve25519-donna (curve25519-donna-c64.c)
+*/
+
+#![allow(dead_code)]
+
+// Five 51-bit limbs, least significant limb first, x = x0 + x1 * 2^51 + ... + x4 * 2^204 mod 2^255 - 19.
+// Limbs may exceed 51 bits between operations, products of two limbs are computed in u128.
+pub type FieldElement51 = [u64; 5];
+
+const MASK_51: u64 = (1 << 51) - 1;
+
+// 16 * p in limbs, added before subtracting so that no limb underflows
+const SIXTEEN_P: FieldElement51 = [0x7ffffffffffed0, 0x7ffffffffffff0, 0x7ffffffffffff0, 0x7ffffffffffff0, 0x7ffffffffffff0];
+
+fn load_8_bytes(input: &[u8]) -> u64 {
+    u64::from_le_bytes(input[..8].try_into().unwrap())
+}
+
+fn carry51(input: &FieldElement51) -> FieldElement51 {
+    // limbs below 2^52 afterwards, 2^255 = 19 mod p
+    let carry: [u64; 5] = [input[0] >> 51, input[1] >> 51, input[2] >> 51, input[3] >> 51, input[4] >> 51];
+
+    [
+        (input[0] & MASK_51) + carry[4] * 19,
+        (input[1] & MASK_51) + carry[0],
+        (input[2] & MASK_51) + carry[1],
+        (input[3] & MASK_51) + carry[2],
+        (input[4] & MASK_51) + carry[3],
+    ]
+}
+
+fn carry_products(c: &[u128; 5]) -> FieldElement51 {
+    let mut output: FieldElement51 = [0; 5];
+    let mut carry: u128 = 0;
+
+    for i in 0..5 {
+        let value: u128 = c[i] + carry;
+        output[i] = (value as u64) & MASK_51;
+        carry = value >> 51;
+    }
+
+    output[0] += (carry as u64) * 19;
+    output[1] += output[0] >> 51;
+    output[0] &= MASK_51;
+
+    output
+}
+
+pub fn unpack51(input: &[u8; 32]) -> FieldElement51 {
+    // the top bit is ignored like in unpack25519
+    [
+        load_8_bytes(&input[0..]) & MASK_51,
+        (load_8_bytes(&input[6..]) >> 3) & MASK_51,
+        (load_8_bytes(&input[12..]) >> 6) & MASK_51,
+        (load_8_bytes(&input[19..]) >> 1) & MASK_51,
+        (load_8_bytes(&input[24..]) >> 12) & MASK_51,
+    ]
+}
+
+pub fn pack51(input: &FieldElement51) -> [u8; 32] {
+    let mut t: FieldElement51 = carry51(&carry51(input));
+
+    // q = 1 if t >= p, computed from the carry of t + 19, then t + 19 * q - 2^255 * q
+    let mut q: u64 = (t[0] + 19) >> 51;
+    for limb in t.iter().skip(1) {
+        q = (limb + q) >> 51;
+    }
+
+    t[0] += 19 * q;
+    for i in 0..4 {
+        t[i + 1] += t[i] >> 51;
+        t[i] &= MASK_51;
+    }
+    t[4] &= MASK_51;
+
+    let mut output: [u8; 32] = [0; 32];
+    let mut buffer: u128 = 0;
+    let mut bits: u32 = 0;
+    let mut position: usize = 0;
+
+    for limb in t.iter() {
+        buffer |= (*limb as u128) << bits;
+        bits += 51;
+        while bits >= 8 && position < 32 {
+            output[position] = buffer as u8;
+            buffer >>= 8;
+            bits -= 8;
+            position += 1;
+        }
+    }
+    if position < 32 {
+        output[position] = buffer as u8;
+    }
+
+    output
+}
+
+pub fn fadd51(x: &FieldElement51, y: &FieldElement51) -> FieldElement51 {
+    carry51(&[x[0] + y[0], x[1] + y[1], x[2] + y[2], x[3] + y[3], x[4] + y[4]])
+}
+
+pub fn fsub51(x: &FieldElement51, y: &FieldElement51) -> FieldElement51 {
+    // x + 16 * p - y, valid for limbs of y below 2^55
+    let mut output: FieldElement51 = [0; 5];
+    for i in 0..5 {
+        output[i] = x[i] + SIXTEEN_P[i] - y[i];
+    }
+
+    carry51(&output)
+}
+
+pub fn fmul51(x: &FieldElement51, y: &FieldElement51) -> FieldElement51 {
+    // 25 products, the ones above 2^255 are folded back with factor 19
+    let m = |a: u64, b: u64| -> u128 { (a as u128) * (b as u128) };
+
+    let y1_19: u64 = y[1] * 19;
+    let y2_19: u64 = y[2] * 19;
+    let y3_19: u64 = y[3] * 19;
+    let y4_19: u64 = y[4] * 19;
+
+    carry_products(&[
+        m(x[0], y[0]) + m(x[4], y1_19) + m(x[3], y2_19) + m(x[2], y3_19) + m(x[1], y4_19),
+        m(x[1], y[0]) + m(x[0], y[1]) + m(x[4], y2_19) + m(x[3], y3_19) + m(x[2], y4_19),
+        m(x[2], y[0]) + m(x[1], y[1]) + m(x[0], y[2]) + m(x[4], y3_19) + m(x[3], y4_19),
+        m(x[3], y[0]) + m(x[2], y[1]) + m(x[1], y[2]) + m(x[0], y[3]) + m(x[4], y4_19),
+        m(x[4], y[0]) + m(x[3], y[1]) + m(x[2], y[2]) + m(x[1], y[3]) + m(x[0], y[4]),
+    ])
+}
+
+pub fn fsquare51(x: &FieldElement51) -> FieldElement51 {
+    // 15 products instead of 25, the mixed terms appear twice
+    let m = |a: u64, b: u64| -> u128 { (a as u128) * (b as u128) };
+
+    let x0_2: u64 = x[0] * 2;
+    let x1_2: u64 = x[1] * 2;
+    let x3_19: u64 = x[3] * 19;
+    let x4_19: u64 = x[4] * 19;
+
+    carry_products(&[
+        m(x[0], x[0]) + m(x1_2, x4_19) + 2 * m(x[2], x3_19),
+        m(x[3], x3_19) + m(x0_2, x[1]) + 2 * m(x[2], x4_19),
+        m(x[1], x[1]) + m(x0_2, x[2]) + 2 * m(x[4], x3_19),
+        m(x[4], x4_19) + m(x0_2, x[3]) + m(x1_2, x[2]),
+        m(x[2], x[2]) + m(x0_2, x[4]) + m(x1_2, x[3]),
+    ])
+}
+
+pub fn fsquare51_times(x: &FieldElement51, k: u32) -> FieldElement51 {
+    // x^(2^k)
+    let mut output: FieldElement51 = *x;
+    for _ in 0..k {
+        output = fsquare51(&output);
+    }
+
+    output
+}
+
+pub fn fmul51_small(x: &FieldElement51, y: u32) -> FieldElement51 {
+    let products: [u128; 5] = [0, 1, 2, 3, 4].map(|i| (x[i] as u128) * (y as u128));
+
+    carry_products(&products)
+}
+
+pub fn finverse51(input: &FieldElement51) -> FieldElement51 {
+    // input^(p - 2) with p - 2 = 2^255 - 21, 254 squarings and 11 multiplications
+    let z_2: FieldElement51 = fsquare51(input);
+    let z_9: FieldElement51 = fmul51(&fsquare51_times(&z_2, 2), input);
+    let z_11: FieldElement51 = fmul51(&z_9, &z_2);
+    let z_2_5_0: FieldElement51 = fmul51(&fsquare51(&z_11), &z_9);
+    let z_2_10_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_5_0, 5), &z_2_5_0);
+    let z_2_20_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_10_0, 10), &z_2_10_0);
+    let z_2_40_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_20_0, 20), &z_2_20_0);
+    let z_2_50_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_40_0, 10), &z_2_10_0);
+    let z_2_100_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_50_0, 50), &z_2_50_0);
+    let z_2_200_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_100_0, 100), &z_2_100_0);
+    let z_2_250_0: FieldElement51 = fmul51(&fsquare51_times(&z_2_200_0, 50), &z_2_50_0);
+
+    // (2^250 - 1) * 2^5 + 11 = 2^255 - 21
+    fmul51(&fsquare51_times(&z_2_250_0, 5), &z_11)
+}
+
+pub fn swap51(p: &mut FieldElement51, q: &mut FieldElement51, bit: u64) {
+    let mask: u64 = bit.wrapping_neg();
+    for i in 0..5 {
+        let t: u64 = mask & (p[i] ^ q[i]);
+        p[i] ^= t;
+        q[i] ^= t;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldElement51, unpack51, pack51, fadd51, fsub51, fmul51, fsquare51, fmul51_small, finverse51, swap51};
+    use crate::crypto::ec::x25519::{FieldElement, pack25519, unpack25519, fadd, fsub, fmul, finverse, is_canonical};
+    use crate::utils::rng;
+
+    fn random_bytes() -> [u8; 32] {
+        let mut bytes: [u8; 32] = [0; 32];
+        rng::fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn byte_conversion() {
+        // p, p + 1 and 2^255 - 1 reduce to 0, 1 and 18 like with pack25519
+        for first_byte in [0xed, 0xee, 0xff] {
+            let mut bytes: [u8; 32] = [0xff; 32];
+            bytes[0] = first_byte;
+            bytes[31] = 0x7f;
+
+            assert_eq!(pack51(&unpack51(&bytes)), pack25519(&mut unpack25519(&bytes)));
+        }
+
+        for _ in 0..100 {
+            let bytes: [u8; 32] = random_bytes();
+            let mut expected: [u8; 32] = bytes;
+            expected[31] &= 0x7f;
+
+            assert_eq!(pack51(&unpack51(&bytes)), pack25519(&mut unpack25519(&bytes)));
+            if is_canonical(&expected) {
+                assert_eq!(pack51(&unpack51(&bytes)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_radix_16_arithmetic() {
+        for _ in 0..100 {
+            let (a, b) = (random_bytes(), random_bytes());
+            let (x, y): (FieldElement51, FieldElement51) = (unpack51(&a), unpack51(&b));
+            let (u, v): (FieldElement, FieldElement) = (unpack25519(&a), unpack25519(&b));
+
+            assert_eq!(pack51(&fadd51(&x, &y)), pack25519(&mut fadd(&u, &v)));
+            assert_eq!(pack51(&fsub51(&x, &y)), pack25519(&mut fsub(&u, &v)));
+            assert_eq!(pack51(&fmul51(&x, &y)), pack25519(&mut fmul(&u, &v)));
+            assert_eq!(pack51(&fsquare51(&x)), pack25519(&mut fmul(&u, &u)));
+            assert_eq!(pack51(&finverse51(&x)), pack25519(&mut finverse(&u)));
+
+            let mut constant: FieldElement = [0; 16];
+            constant[0] = 121665;
+            assert_eq!(pack51(&fmul51_small(&x, 121665)), pack25519(&mut fmul(&u, &constant)));
+        }
+    }
+
+    #[test]
+    fn field_properties() {
+        let mut one: [u8; 32] = [0; 32];
+        one[0] = 1;
+
+        for _ in 0..20 {
+            let x: FieldElement51 = unpack51(&random_bytes());
+            let y: FieldElement51 = unpack51(&random_bytes());
+
+            assert_eq!(pack51(&fmul51(&x, &finverse51(&x))), one);
+            assert_eq!(pack51(&fsub51(&fadd51(&x, &y), &y)), pack51(&x));
+
+            // x - y - y - ... stays in range since every subtraction carries
+            let mut z: FieldElement51 = x;
+            for _ in 0..10 {
+                z = fsub51(&z, &fmul51(&x, &y));
+            }
+            for _ in 0..10 {
+                z = fadd51(&z, &fmul51(&x, &y));
+            }
+            assert_eq!(pack51(&z), pack51(&x));
+
+            let (mut p, mut q): (FieldElement51, FieldElement51) = (x, y);
+            swap51(&mut p, &mut q, 0);
+            assert_eq!((p, q), (x, y));
+            swap51(&mut p, &mut q, 1);
+            assert_eq!((p, q), (y, x));
+        }
+
+        assert_eq!(pack51(&finverse51(&[0; 5])), [0; 32]);
+    }
+}
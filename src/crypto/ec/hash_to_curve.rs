@@ -0,0 +1,276 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9380 (curve25519_XMD:SHA-512_ELL2 and edwards25519_XMD:SHA-512_ELL2)
+*/
+
+#![allow(dead_code)]
+
+use super::{Point, EcError};
+use super::x25519::{FieldElement, fadd, fsub, fmul, fsquare, fneg, finverse, fselect, fcneg, fsqrt, is_square, is_zero};
+use super::edwards25519::{EdwardsPoint, one, point_add, multiply_by_cofactor};
+
+use crate::hash::Sha512;
+
+// J = 486662 of Curve25519 and c1 = sqrt(-486664) with sgn0(c1) = 0 (RFC 9380 appendix D.1), in 16-bit limbs
+const J: FieldElement = [0x6d06, 0x0007, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000];
+const SQRT_MINUS_486664: FieldElement = [0x7e06, 0xff45, 0x04aa, 0xcc6e, 0x1a82, 0x4b7d, 0xd3d1, 0xc5a1, 0x4f7e, 0x03fc, 0x08dc, 0xd27b, 0x06bb, 0x60a0, 0xedf4, 0x0f26];
+
+// L = ceil((ceil(log2(p)) + k) / 8) with k = 128 bits of security
+const L: usize = 48;
+
+pub fn expand_message_xmd(message: &[u8], dst: &[u8], length: usize) -> Result<Vec<u8>, EcError> {
+    // RFC 9380 section 5.3.1 with SHA-512, b_in_bytes = 64 and s_in_bytes = 128
+    let ell: usize = length.div_ceil(64);
+    if ell > 255 || length > 65535 {
+        return Err(EcError::OutputTooLong);
+    }
+
+    // DSTs longer than 255 bytes are replaced by H("H2C-OVERSIZE-DST-" || DST), section 5.3.3
+    let oversize_dst: [u8; 64];
+    let dst: &[u8] = if dst.len() > 255 {
+        oversize_dst = Sha512::new().digest(&[b"H2C-OVERSIZE-DST-".as_slice(), dst].concat());
+        &oversize_dst
+    } else {
+        dst
+    };
+
+    let dst_prime: Vec<u8> = [dst, &[dst.len() as u8]].concat();
+
+    let mut message_prime: Vec<u8> = vec![0; 128];
+    message_prime.extend_from_slice(message);
+    message_prime.extend_from_slice(&(length as u16).to_be_bytes());
+    message_prime.push(0x00);
+    message_prime.extend_from_slice(&dst_prime);
+
+    let b_0: [u8; 64] = Sha512::new().digest(&message_prime);
+    let mut b_i: [u8; 64] = Sha512::new().digest(&[b_0.as_slice(), &[0x01], &dst_prime].concat());
+    let mut output: Vec<u8> = b_i.to_vec();
+
+    for i in 2..=ell {
+        let mut xored: [u8; 64] = b_0;
+        for (x, b) in xored.iter_mut().zip(b_i.iter()) {
+            *x ^= b;
+        }
+
+        b_i = Sha512::new().digest(&[xored.as_slice(), &[i as u8], &dst_prime].concat());
+        output.extend_from_slice(&b_i);
+    }
+
+    output.truncate(length);
+    Ok(output)
+}
+
+pub fn hash_to_field(message: &[u8], dst: &[u8], count: usize) -> Result<Vec<FieldElement>, EcError> {
+    // RFC 9380 section 5.2, every element from L big-endian bytes reduced modulo p
+    let uniform_bytes: Vec<u8> = expand_message_xmd(message, dst, count * L)?;
+
+    let mut radix: FieldElement = [0; 16];
+    radix[0] = 256;
+
+    Ok(uniform_bytes.chunks(L)
+        .map(|chunk| chunk.iter().fold([0; 16], |acc: FieldElement, byte| {
+            let mut digit: FieldElement = [0; 16];
+            digit[0] = *byte as i64;
+            fadd(&fmul(&acc, &radix), &digit)
+        }))
+        .collect())
+}
+
+pub fn map_to_curve_elligator2(u: &FieldElement) -> EdwardsPoint {
+    // RFC 9380 section 6.7.1 with Z = 2 on Curve25519, the result (s, t) is
+    // moved to edwards25519 by the rational map of appendix D.1
+    let mut x_1: FieldElement = fmul(&fneg(&J), &finverse(&fadd(&one(), &fadd(&fsquare(u), &fsquare(u)))));
+    x_1 = fselect(&x_1, &fneg(&J), is_zero(&x_1) as i64);
+    let x_2: FieldElement = fsub(&fneg(&x_1), &J);
+
+    let gx_1: FieldElement = montgomery_rhs(&x_1);
+    let gx_2: FieldElement = montgomery_rhs(&x_2);
+
+    // exactly one of gx1 and gx2 is square, y is odd for x1 and even for x2
+    let e: i64 = is_square(&gx_1) as i64;
+    let s: FieldElement = fselect(&x_2, &x_1, e);
+    let t: FieldElement = fcneg(&fsqrt(&fselect(&gx_2, &gx_1, e)).unwrap(), e);
+
+    montgomery_to_edwards(&s, &t)
+}
+
+pub fn hash_to_curve_edwards25519(message: &[u8], dst: &[u8]) -> Result<EdwardsPoint, EcError> {
+    // edwards25519_XMD:SHA-512_ELL2_RO_
+    let u: Vec<FieldElement> = hash_to_field(message, dst, 2)?;
+
+    Ok(multiply_by_cofactor(&point_add(&map_to_curve_elligator2(&u[0]), &map_to_curve_elligator2(&u[1]))))
+}
+
+pub fn encode_to_curve_edwards25519(message: &[u8], dst: &[u8]) -> Result<EdwardsPoint, EcError> {
+    // edwards25519_XMD:SHA-512_ELL2_NU_, not uniformly distributed
+    let u: Vec<FieldElement> = hash_to_field(message, dst, 1)?;
+
+    Ok(multiply_by_cofactor(&map_to_curve_elligator2(&u[0])))
+}
+
+pub fn hash_to_curve25519(message: &[u8], dst: &[u8]) -> Result<Point, EcError> {
+    // curve25519_XMD:SHA-512_ELL2_RO_, the x-coordinate of the result is an X25519 u-coordinate
+    Ok(edwards_to_montgomery(&hash_to_curve_edwards25519(message, dst)?))
+}
+
+pub fn encode_to_curve25519(message: &[u8], dst: &[u8]) -> Result<Point, EcError> {
+    // curve25519_XMD:SHA-512_ELL2_NU_
+    Ok(edwards_to_montgomery(&encode_to_curve_edwards25519(message, dst)?))
+}
+
+fn montgomery_rhs(x: &FieldElement) -> FieldElement {
+    // x^3 + J * x^2 + x
+    let x_power_2: FieldElement = fsquare(x);
+
+    fadd(&fmul(&x_power_2, &fadd(x, &J)), x)
+}
+
+fn montgomery_to_edwards(s: &FieldElement, t: &FieldElement) -> EdwardsPoint {
+    // x = c1 * s / t and y = (s - 1) / (s + 1), the identity if t = 0 or s = -1
+    let s_plus_1: FieldElement = fadd(s, &one());
+    let denominator: FieldElement = fmul(t, &s_plus_1);
+    let inverse: FieldElement = finverse(&denominator);
+
+    let x: FieldElement = fmul(&fmul(&SQRT_MINUS_486664, s), &fmul(&s_plus_1, &inverse));
+    let y: FieldElement = fselect(&fmul(&fsub(s, &one()), &fmul(t, &inverse)), &one(), is_zero(&denominator) as i64);
+
+    EdwardsPoint { x, y, z: one(), t: fmul(&x, &y) }
+}
+
+fn edwards_to_montgomery(point: &EdwardsPoint) -> Point {
+    // s = (1 + y) / (1 - y) and t = c1 * s / x, in projective coordinates
+    let z_plus_y: FieldElement = fadd(&point.z, &point.y);
+    let inverse: FieldElement = finverse(&fmul(&fsub(&point.z, &point.y), &point.x));
+
+    Point {
+        x: fmul(&fmul(&z_plus_y, &point.x), &inverse),
+        y: fmul(&fmul(&SQRT_MINUS_486664, &fmul(&z_plus_y, &point.z)), &inverse),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{EcError, expand_message_xmd, hash_to_curve25519, encode_to_curve25519, hash_to_curve_edwards25519, encode_to_curve_edwards25519};
+    use crate::crypto::ec::Curve;
+    use crate::crypto::ec::x25519::{FieldElement, pack25519, finverse, fmul};
+    use crate::crypto::ec::edwards25519::EdwardsPoint;
+    use crate::hash::Sha512;
+    use crate::utils::testing::hex;
+
+    fn big_endian(x: &FieldElement) -> Vec<u8> {
+        pack25519(&mut x.clone()).iter().rev().copied().collect()
+    }
+
+    fn message(name: &str) -> Vec<u8> {
+        // "q128_" followed by 128 times "q" and "a512_" followed by 512 times "a"
+        match name {
+            "q128_" => [name.as_bytes(), &[b'q'; 128]].concat(),
+            "a512_" => [name.as_bytes(), &[b'a'; 512]].concat(),
+            _ => name.as_bytes().to_vec(),
+        }
+    }
+
+    fn affine(point: &EdwardsPoint) -> (FieldElement, FieldElement) {
+        let z_inverse: FieldElement = finverse(&point.z);
+        (fmul(&point.x, &z_inverse), fmul(&point.y, &z_inverse))
+    }
+
+    type Vectors = [(&'static str, &'static str, &'static str); 5];
+
+    #[test]
+    fn expand_message() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9380#appendix-K.3
+        let dst: &[u8] = "QUUX-V01-CS02-with-expander-SHA512-256".as_bytes();
+
+        assert_eq!(expand_message_xmd(&[], dst, 0x20).unwrap(), hex("6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"));
+        assert_eq!(expand_message_xmd("abc".as_bytes(), dst, 0x20).unwrap(), hex("0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"));
+        assert_eq!(
+            expand_message_xmd(&[], dst, 0x80).unwrap(),
+            hex("41b037d1734a5f8df225dd8c7de38f851efdb45c372887be655212d07251b921b052b62eaed99b46f72f2ef4cc96bfaf254ebbbec091e1a3b9e4fb5e5b619d2e0c5414800a1d882b62bb5cd1778f098b8eb6cb399d5d9d18f5d5842cf5d13d7eb00a7cff859b605da678b318bd0e65ebff70bec88c753b159a805d2c89c55961"),
+        );
+        assert_eq!(
+            expand_message_xmd("abcdef0123456789".as_bytes(), dst, 0x80).unwrap(),
+            hex("3f721f208e6199fe903545abc26c837ce59ac6fa45733f1baaf0222f8b7acb0424814fcb5eecf6c1d38f06e9d0a6ccfbf85ae612ab8735dfdf9ce84c372a77c8f9e1c1e952c3a61b7567dd0693016af51d2745822663d0c2367e3f4f0bed827feecc2aaf98c949b5ed0d35c3f1023d64ad1407924288d366ea159f46287e61ac"),
+        );
+    }
+
+    #[test]
+    fn expand_message_limits() {
+        // a DST over 255 bytes is replaced by SHA-512("H2C-OVERSIZE-DST-" || DST) (RFC 9380 section 5.3.3)
+        let long_dst: Vec<u8> = [b"QUUX-V01-CS02-with-expander-SHA512-256-long-DST-".as_slice(), &[b'1'; 256]].concat();
+        let short_dst: [u8; 64] = Sha512::new().digest(&[b"H2C-OVERSIZE-DST-".as_slice(), &long_dst].concat());
+        assert_eq!(expand_message_xmd(b"abc", &long_dst, 0x80).unwrap(), expand_message_xmd(b"abc", &short_dst, 0x80).unwrap());
+        assert_ne!(expand_message_xmd(b"abc", &long_dst, 0x80).unwrap(), expand_message_xmd(b"abc", &long_dst[..255], 0x80).unwrap());
+
+        // at most 255 blocks of 64 bytes
+        let dst: &[u8] = "QUUX-V01-CS02-with-expander-SHA512-256".as_bytes();
+        assert_eq!(expand_message_xmd(b"abc", dst, 255 * 64).unwrap().len(), 255 * 64);
+        assert_eq!(expand_message_xmd(b"abc", dst, 255 * 64 + 1), Err(EcError::OutputTooLong));
+    }
+
+    #[test]
+    fn curve25519_suites() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9380#appendix-J.4.1 and J.4.2
+        let random_oracle: Vectors = [
+            ("", "2de3780abb67e861289f5749d16d3e217ffa722192d16bbd9d1bfb9d112b98c0", "3b5dc2a498941a1033d176567d457845637554a2fe7a3507d21abd1c1bd6e878"),
+            ("abc", "2b4419f1f2d48f5872de692b0aca72cc7b0a60915dd70bde432e826b6abc526d", "1b8235f255a268f0a6fa8763e97eb3d22d149343d495da1160eff9703f2d07dd"),
+            ("abcdef0123456789", "68ca1ea5a6acf4e9956daa101709b1eee6c1bb0df1de3b90d4602382a104c036", "2a375b656207123d10766e68b938b1812a4a6625ff83cb8d5e86f58a4be08353"),
+            ("q128_", "096e9c8bae6c06b554c1ee69383bb0e82267e064236b3a30608d4ed20b73ac5a", "1eb5a62612cafb32b16c3329794645b5b948d9f8ffe501d4e26b073fef6de355"),
+            ("a512_", "1bc61845a138e912f047b5e70ba9606ba2a447a4dade024c8ef3dd42b7bbc5fe", "623d05e47b70e25f7f1d51dda6d7c23c9a18ce015fe3548df596ea9e38c69bf1"),
+        ];
+        let nonuniform: Vectors = [
+            ("", "1bb913f0c9daefa0b3375378ffa534bda5526c97391952a7789eb976edfe4d08", "4548368f4f983243e747b62a600840ae7c1dab5c723991f85d3a9768479f3ec4"),
+            ("abc", "7c22950b7d900fa866334262fcaea47a441a578df43b894b4625c9b450f9a026", "5547bc00e4c09685dcbc6cb6765288b386d8bdcb595fa5a6e3969e08097f0541"),
+            ("abcdef0123456789", "31ad08a8b0deeb2a4d8b0206ca25f567ab4e042746f792f4b7973f3ae2096c52", "405070c28e78b4fa269427c82827261991b9718bd6c6e95d627d701a53c30db1"),
+            ("q128_", "027877759d155b1997d0d84683a313eb78bdb493271d935b622900459d52ceaa", "54d691731a53baa30707f4a87121d5169fb5d587d70fb0292b5830dedbec4c18"),
+            ("a512_", "5fd892c0958d1a75f54c3182a18d286efab784e774d1e017ba2fb252998b5dc1", "750af3c66101737423a4519ac792fb93337bd74ee751f19da4cf1e94f4d6d0b8"),
+        ];
+        let curve: Curve = Curve::montgomery();
+
+        for (name, x, y) in random_oracle {
+            let point = hash_to_curve25519(&message(name), "QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_RO_".as_bytes()).unwrap();
+
+            assert_eq!((big_endian(&point.x), big_endian(&point.y)), (hex(x), hex(y)));
+            assert!(curve.point_on_curve(&point));
+        }
+
+        for (name, x, y) in nonuniform {
+            let point = encode_to_curve25519(&message(name), "QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_NU_".as_bytes()).unwrap();
+
+            assert_eq!((big_endian(&point.x), big_endian(&point.y)), (hex(x), hex(y)));
+            assert!(curve.point_on_curve(&point));
+        }
+    }
+
+    #[test]
+    fn edwards25519_suites() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9380#appendix-J.5.1 and J.5.2
+        let random_oracle: Vectors = [
+            ("", "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6", "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21"),
+            ("abc", "608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad", "1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531"),
+            ("abcdef0123456789", "6d7fabf47a2dc03fe7d47f7dddd21082c5fb8f86743cd020f3fb147d57161472", "53060a3d140e7fbcda641ed3cf42c88a75411e648a1add71217f70ea8ec561a6"),
+            ("q128_", "5fb0b92acedd16f3bcb0ef83f5c7b7a9466b5f1e0d8d217421878ea3686f8524", "2eca15e355fcfa39d2982f67ddb0eea138e2994f5956ed37b7f72eea5e89d2f7"),
+            ("a512_", "0efcfde5898a839b00997fbe40d2ebe950bc81181afbd5cd6b9618aa336c1e8c", "6dc2fc04f266c5c27f236a80b14f92ccd051ef1ff027f26a07f8c0f327d8f995"),
+        ];
+        let nonuniform: Vectors = [
+            ("", "1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da", "222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b"),
+            ("abc", "5f13cc69c891d86927eb37bd4afc6672360007c63f68a33ab423a3aa040fd2a8", "67732d50f9a26f73111dd1ed5dba225614e538599db58ba30aaea1f5c827fa42"),
+            ("abcdef0123456789", "1dd2fefce934ecfd7aae6ec998de088d7dd03316aa1847198aecf699ba6613f1", "2f8a6c24dd1adde73909cada6a4a137577b0f179d336685c4a955a0a8e1a86fb"),
+            ("q128_", "35fbdc5143e8a97afd3096f2b843e07df72e15bfca2eaf6879bf97c5d3362f73", "2af6ff6ef5ebba128b0774f4296cb4c2279a074658b083b8dcca91f57a603450"),
+            ("a512_", "6e5e1f37e99345887fc12111575fc1c3e36df4b289b8759d23af14d774b66bff", "2c90c3d39eb18ff291d33441b35f3262cdd307162cc97c31bfcc7a4245891a37"),
+        ];
+
+        for (name, x, y) in random_oracle {
+            let (point_x, point_y) = affine(&hash_to_curve_edwards25519(&message(name), "QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_".as_bytes()).unwrap());
+
+            assert_eq!((big_endian(&point_x), big_endian(&point_y)), (hex(x), hex(y)));
+        }
+
+        for (name, x, y) in nonuniform {
+            let (point_x, point_y) = affine(&encode_to_curve_edwards25519(&message(name), "QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_NU_".as_bytes()).unwrap());
+
+            assert_eq!((big_endian(&point_x), big_endian(&point_y)), (hex(x), hex(y)));
+        }
+    }
+}
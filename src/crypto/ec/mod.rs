@@ -3,16 +3,48 @@ Warranty Disclaimer: the software is provided "as is" without any warranties or
 */
 
 mod x25519;
+mod field51;
+mod x448;
+mod edwards25519;
+mod msm;
+mod basepoint;
+mod hash_to_curve;
+mod ristretto255;
+mod pedersen;
+mod ed25519;
+mod ecvrf;
+mod ed448;
+mod field256;
+mod weierstrass;
+mod rfc6979;
+mod secp256k1;
+mod p256;
+mod schnorr;
 mod curve;
 mod point;
 mod ecdh;
+mod types;
+mod utils;
 // mod ecdsa;
 
 pub use point::Point;
 
 pub use x25519::{FieldElement, scalarmult, scalarmult_64bytes, pack25519, unpack25519, fmul, fadd, fsub};
+pub use x25519::scalarmult_checked;
+pub use x448::{FieldElement448, scalarmult448, scalarmult448_checked, pack448};
+pub use ed25519::Ed25519;
+pub use ecvrf::ECVRF;
+pub use ed448::Ed448;
+pub use ristretto255::Ristretto255;
+pub use pedersen::{Pedersen, PedersenCommitment};
+pub use hash_to_curve::hash_to_curve25519;
+pub use secp256k1::Secp256k1;
+pub use p256::P256;
+pub use schnorr::Schnorr;
 
 pub use curve::{Curve, CurveType};
 
-pub use ecdh::ECDH;
+pub use ecdh::{ECDH, ECDH448};
+
+pub use types::{EcError, BatchError};
 
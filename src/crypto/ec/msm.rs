@@ -0,0 +1,341 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://cr.yp.to/papers/pippenger-20020118-retypeset20220327.pdf and https://eprint.iacr.org/2012/549.pdf
+*/
+
+#![allow(dead_code)]
+
+use super::edwards25519::{EdwardsPoint, identity, point_add, point_negate, point_swap};
+
+// Straus is faster for few points, Pippenger's buckets pay off for large batches.
+const PIPPENGER_THRESHOLD: usize = 190;
+
+// Sum of scalar * point over all terms with 256-bit little-endian scalars, in constant
+// time with respect to the scalars. Straus' method with signed radix-16 digits, every
+// digit selects its multiple by a full scan of the table.
+pub fn multiscalar_mult(terms: &[([u8; 32], EdwardsPoint)]) -> EdwardsPoint {
+    let digits: Vec<[i8; 65]> = terms.iter().map(|(scalar, _)| radix_16(scalar)).collect();
+    let tables: Vec<[EdwardsPoint; 8]> = terms.iter().map(|(_, point)| multiples_table(point)).collect();
+
+    let mut output: EdwardsPoint = identity();
+
+    for i in (0..65).rev() {
+        for _ in 0..4 {
+            output = point_add(&output, &output);
+        }
+
+        for (digit, table) in digits.iter().zip(tables.iter()) {
+            output = point_add(&output, &select(table, digit[i]));
+        }
+    }
+
+    output
+}
+
+// Sum of scalar * point over all terms for public scalars only, the running time depends on them.
+pub fn multiscalar_mult_vartime(terms: &[([u8; 32], EdwardsPoint)]) -> EdwardsPoint {
+    if terms.len() < PIPPENGER_THRESHOLD {
+        straus_vartime(terms)
+    } else {
+        pippenger_vartime(terms)
+    }
+}
+
+pub fn straus_vartime(terms: &[([u8; 32], EdwardsPoint)]) -> EdwardsPoint {
+    // interleaved width-5 NAFs sharing the doublings, tables of P, 3P, ..., 15P
+    let nafs: Vec<[i8; 257]> = terms.iter().map(|(scalar, _)| non_adjacent_form(scalar, 5)).collect();
+    let tables: Vec<[EdwardsPoint; 8]> = terms.iter().map(|(_, point)| odd_multiples_table(point)).collect();
+
+    let mut output: EdwardsPoint = identity();
+    let mut started: bool = false;
+
+    for i in (0..257).rev() {
+        if started {
+            output = point_add(&output, &output);
+        }
+
+        for (naf, table) in nafs.iter().zip(tables.iter()) {
+            let digit: i8 = naf[i];
+
+            if digit > 0 {
+                output = point_add(&output, &table[(digit / 2) as usize]);
+                started = true;
+            } else if digit < 0 {
+                output = point_add(&output, &point_negate(&table[(-digit / 2) as usize]));
+                started = true;
+            }
+        }
+    }
+
+    output
+}
+
+pub fn pippenger_vartime(terms: &[([u8; 32], EdwardsPoint)]) -> EdwardsPoint {
+    // c-bit windows, in every window each point goes to the bucket of its digit,
+    // the running sum over the buckets weights bucket j with j + 1
+    let c: usize = if terms.len() < 500 {
+        6
+    } else if terms.len() < 800 {
+        7
+    } else {
+        8
+    };
+    let windows: usize = 256_usize.div_ceil(c);
+
+    let mut output: EdwardsPoint = identity();
+
+    for window in (0..windows).rev() {
+        for _ in 0..c {
+            output = point_add(&output, &output);
+        }
+
+        let mut buckets: Vec<Option<EdwardsPoint>> = vec![None; (1 << c) - 1];
+        for (scalar, point) in terms.iter() {
+            let digit: usize = window_digit(scalar, window * c, c);
+
+            if digit != 0 {
+                buckets[digit - 1] = Some(match buckets[digit - 1] {
+                    Some(bucket) => point_add(&bucket, point),
+                    None => *point,
+                });
+            }
+        }
+
+        let mut running: EdwardsPoint = identity();
+        let mut sum: EdwardsPoint = identity();
+        for bucket in buckets.iter().rev() {
+            if let Some(bucket) = bucket {
+                running = point_add(&running, bucket);
+            }
+            sum = point_add(&sum, &running);
+        }
+
+        output = point_add(&output, &sum);
+    }
+
+    output
+}
+
+fn window_digit(scalar: &[u8; 32], offset: usize, width: usize) -> usize {
+    let mut digit: usize = 0;
+
+    for i in (offset..(offset + width).min(256)).rev() {
+        digit = (digit << 1) | ((scalar[i >> 3] >> (i & 7)) & 1) as usize;
+    }
+
+    digit
+}
+
+pub fn radix_16(scalar: &[u8; 32]) -> [i8; 65] {
+    // digits e_i in [-8, 8) with scalar = sum e_i * 16^i, the last one is the carry
+    let mut digits: [i8; 65] = [0; 65];
+
+    for (i, byte) in scalar.iter().enumerate() {
+        digits[2 * i] = (byte & 15) as i8;
+        digits[2 * i + 1] = (byte >> 4) as i8;
+    }
+
+    let mut carry: i8 = 0;
+    for digit in digits.iter_mut().take(64) {
+        *digit += carry;
+        carry = (*digit + 8) >> 4;
+        *digit -= carry << 4;
+    }
+    digits[64] = carry;
+
+    digits
+}
+
+fn non_adjacent_form(scalar: &[u8; 32], width: usize) -> [i8; 257] {
+    // odd digits in (-2^(w-1), 2^(w-1)), at most one non-zero digit in every w consecutive ones
+    let mut naf: [i8; 257] = [0; 257];
+    let window: i32 = 1 << width;
+    let mut carry: i32 = 0;
+    let mut position: usize = 0;
+
+    while position < 257 {
+        let value: i32 = carry + window_digit(scalar, position, width) as i32;
+
+        // an even value gives a zero digit, a carry stays on the next bit
+        if value & 1 == 0 {
+            position += 1;
+            continue;
+        }
+
+        if value < window / 2 {
+            carry = 0;
+            naf[position] = value as i8;
+        } else {
+            carry = 1;
+            naf[position] = (value - window) as i8;
+        }
+
+        position += width;
+    }
+
+    naf
+}
+
+pub fn multiples_table(point: &EdwardsPoint) -> [EdwardsPoint; 8] {
+    // P, 2P, ..., 8P
+    let mut table: [EdwardsPoint; 8] = [*point; 8];
+    for i in 1..8 {
+        table[i] = point_add(&table[i - 1], point);
+    }
+
+    table
+}
+
+fn odd_multiples_table(point: &EdwardsPoint) -> [EdwardsPoint; 8] {
+    // P, 3P, ..., 15P
+    let double: EdwardsPoint = point_add(point, point);
+    let mut table: [EdwardsPoint; 8] = [*point; 8];
+    for i in 1..8 {
+        table[i] = point_add(&table[i - 1], &double);
+    }
+
+    table
+}
+
+pub fn select(table: &[EdwardsPoint; 8], digit: i8) -> EdwardsPoint {
+    // digit * P for digit in [-8, 8], reading every table entry
+    let negative: u8 = (digit as u8) >> 7;
+    let absolute: u8 = (digit as u8).wrapping_sub(((negative.wrapping_neg()) & (digit as u8)) << 1);
+
+    let mut output: EdwardsPoint = identity();
+    for (i, entry) in table.iter().enumerate() {
+        let equal: u8 = (((absolute ^ (i as u8 + 1)) as u32).wrapping_sub(1) >> 31) as u8;
+        let mut candidate: EdwardsPoint = *entry;
+        point_swap(&mut output, &mut candidate, equal as i64);
+    }
+
+    let mut negated: EdwardsPoint = point_negate(&output);
+    point_swap(&mut output, &mut negated, negative as i64);
+
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{multiscalar_mult, multiscalar_mult_vartime, straus_vartime, pippenger_vartime, radix_16, non_adjacent_form};
+    use crate::crypto::ec::edwards25519::{EdwardsPoint, base_point, encode, identity, point_add, scalarmult_point};
+
+    fn test_input(size: usize) -> Vec<([u8; 32], EdwardsPoint)> {
+        // deterministic pseudo-random scalars over the full 256 bits and multiples of B
+        let mut terms: Vec<([u8; 32], EdwardsPoint)> = Vec::new();
+        let mut state: u64 = 0x0123456789abcdef;
+
+        for i in 0..size {
+            let mut scalar: [u8; 32] = [0; 32];
+            for byte in scalar.iter_mut() {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                *byte = (state >> 56) as u8;
+            }
+
+            let mut multiple: [u8; 32] = [0; 32];
+            multiple[0] = i as u8 + 1;
+            multiple[1] = (i >> 8) as u8;
+            terms.push((scalar, scalarmult_point(&base_point(), &multiple)));
+        }
+
+        terms
+    }
+
+    fn naive(terms: &[([u8; 32], EdwardsPoint)]) -> [u8; 32] {
+        let mut output: EdwardsPoint = identity();
+        for (scalar, point) in terms.iter() {
+            output = point_add(&output, &scalarmult_point(point, scalar));
+        }
+
+        encode(&output)
+    }
+
+    #[test]
+    fn scalar_recodings() {
+        let scalars: Vec<[u8; 32]> = test_input(4).iter().map(|(scalar, _)| *scalar).collect();
+
+        for scalar in scalars.iter().chain([[0xff; 32], [0; 32]].iter()) {
+            // both recodings sum up to the scalar again, compared as 257-bit numbers
+            let mut from_radix_16: Vec<i64> = vec![0; 66];
+            for (i, digit) in radix_16(scalar).iter().enumerate() {
+                assert!((-8..=8).contains(digit));
+                from_radix_16[i] = *digit as i64;
+            }
+
+            let naf: [i8; 257] = non_adjacent_form(scalar, 5);
+            for i in 0..257 {
+                assert!(naf[i] == 0 || (naf[i] % 2 != 0 && (-16..16).contains(&naf[i])));
+                if naf[i] != 0 {
+                    assert!(naf[i + 1..(i + 5).min(257)].iter().all(|d| *d == 0));
+                }
+            }
+
+            let mut expected: Vec<i64> = vec![0; 66];
+            for (i, byte) in scalar.iter().enumerate() {
+                expected[2 * i] = (byte & 15) as i64;
+                expected[2 * i + 1] = (byte >> 4) as i64;
+            }
+
+            assert_eq!(normalize(&from_radix_16, 16), expected);
+
+            let mut from_naf: Vec<i64> = vec![0; 258];
+            let mut expected_bits: Vec<i64> = vec![0; 258];
+            for i in 0..257 {
+                from_naf[i] = naf[i] as i64;
+            }
+            for i in 0..256 {
+                expected_bits[i] = ((scalar[i >> 3] >> (i & 7)) & 1) as i64;
+            }
+
+            assert_eq!(normalize(&from_naf, 2), expected_bits);
+        }
+    }
+
+    fn normalize(digits: &[i64], base: i64) -> Vec<i64> {
+        // carries signed digits into digits in [0, base)
+        let mut output: Vec<i64> = digits.to_vec();
+        for i in 0..output.len() - 1 {
+            let carry: i64 = output[i].div_euclid(base);
+            output[i] -= carry * base;
+            output[i + 1] += carry;
+        }
+
+        output
+    }
+
+    #[test]
+    fn matches_single_scalar_multiplication() {
+        for size in [0, 1, 2, 7] {
+            let terms: Vec<([u8; 32], EdwardsPoint)> = test_input(size);
+            let expected: [u8; 32] = naive(&terms);
+
+            assert_eq!(encode(&multiscalar_mult(&terms)), expected);
+            assert_eq!(encode(&multiscalar_mult_vartime(&terms)), expected);
+            assert_eq!(encode(&straus_vartime(&terms)), expected);
+            assert_eq!(encode(&pippenger_vartime(&terms)), expected);
+        }
+    }
+
+    #[test]
+    fn extreme_scalars() {
+        let scalars: [[u8; 32]; 3] = [[0xff; 32], [0; 32], [0x88; 32]];
+        let terms: Vec<([u8; 32], EdwardsPoint)> = scalars.iter().zip(test_input(3)).map(|(scalar, (_, point))| (*scalar, point)).collect();
+
+        let expected: [u8; 32] = naive(&terms);
+
+        assert_eq!(encode(&multiscalar_mult(&terms)), expected);
+        assert_eq!(encode(&straus_vartime(&terms)), expected);
+        assert_eq!(encode(&pippenger_vartime(&terms)), expected);
+    }
+
+    #[test]
+    fn large_batches() {
+        // above the threshold the vartime dispatch uses Pippenger
+        let terms: Vec<([u8; 32], EdwardsPoint)> = test_input(200);
+        let expected: [u8; 32] = encode(&straus_vartime(&terms));
+
+        assert_eq!(encode(&multiscalar_mult_vartime(&terms)), expected);
+        assert_eq!(encode(&pippenger_vartime(&terms[..120])), encode(&straus_vartime(&terms[..120])));
+    }
+}
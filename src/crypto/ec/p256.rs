@@ -0,0 +1,323 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Ar3.pdf (5.6.2.3, 5.7.1.2) and https://www.secg.org/sec1-v2.pdf (4.1)
+*/
+
+#![allow(dead_code)]
+
+use super::{Curve, EcError, Point};
+use super::field256::{FieldElement256, pack256};
+use super::weierstrass::Weierstrass;
+
+use crate::hash::{Sha256, HashType};
+use crate::utils::rng;
+
+pub struct P256 {
+    weierstrass: Weierstrass,
+    pub sec_key: [u8; 32],
+    pub pub_key: [u8; 65],
+}
+
+impl P256 {
+    pub fn new() -> Self {
+        Self {
+            weierstrass: Weierstrass::new(Curve::p256()),
+            sec_key: [0; 32],
+            pub_key: [0; 65],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut secret_key: [u8; 32] = [0; 32];
+
+        // rejection sampling, a random value is out of range with probability ~2^-32
+        loop {
+            rng::fill_array(&mut secret_key);
+
+            if self.set_secret_key(&secret_key).is_ok() {
+                break;
+            }
+        }
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) -> Result<(), EcError> {
+        if !self.weierstrass.is_valid_scalar(secret_key) {
+            return Err(EcError::InvalidScalar);
+        }
+
+        let public_key: Point<FieldElement256> = self.weierstrass
+            .to_affine(&self.weierstrass.scalarmult(&self.weierstrass.generator(), secret_key))
+            .ok_or(EcError::InvalidScalar)?;
+
+        self.sec_key = *secret_key;
+        self.pub_key.copy_from_slice(&self.weierstrass.encode_point(&public_key, false));
+
+        Ok(())
+    }
+
+    pub fn symmetric_key(&self, public_key: &[u8]) -> Result<[u8; 32], EcError> {
+        // Full public key validation: the encoding is canonical and the point lies on
+        // the curve. With cofactor 1 every such point is in the subgroup of order n.
+        let public_key: Point<FieldElement256> = self.weierstrass.decode_point(public_key)?;
+
+        // The shared secret is the x-coordinate of d * Q.
+        let shared: Point<FieldElement256> = self.weierstrass
+            .to_affine(&self.weierstrass.scalarmult(&self.weierstrass.jacobian(&public_key), &self.sec_key))
+            .ok_or(EcError::InvalidPoint)?;
+
+        Ok(pack256(&shared.x))
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        let message_hash: [u8; 32] = Sha256::new().digest(message);
+
+        self.weierstrass.ecdsa_sign_deterministic(HashType::Sha256Type, &self.sec_key, &message_hash).0
+    }
+
+    pub fn verify(public_key: &[u8], message: &[u8], signature: &[u8; 64]) -> Result<(), EcError> {
+        let p256: P256 = P256::new();
+
+        let public_key: Point<FieldElement256> = p256.weierstrass.decode_point(public_key)?;
+        let message_hash: [u8; 32] = Sha256::new().digest(message);
+
+        p256.weierstrass.ecdsa_verify(&public_key, &message_hash, signature)
+    }
+}
+
+impl Default for P256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::P256;
+    use crate::crypto::ec::EcError;
+    use crate::hash::{Sha256, Sha512, HashType};
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn cavp_ecdh_vectors() {
+        // Test vectors from the CAVP KAS_ECC_CDH_PrimitiveTest.txt, [P-256], COUNT = 0 and 1:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/component-testing
+        fn check_ecdh(peer_x: &str, peer_y: &str, secret_key: &str, public_x: &str, public_y: &str, shared: &str) {
+            let mut p256: P256 = P256::new();
+            p256.set_secret_key(&hex(secret_key).try_into().unwrap()).unwrap();
+
+            assert_eq!(p256.pub_key.to_vec(), [hex("04"), hex(public_x), hex(public_y)].concat());
+
+            let peer: Vec<u8> = [hex("04"), hex(peer_x), hex(peer_y)].concat();
+            assert_eq!(p256.symmetric_key(&peer).unwrap().to_vec(), hex(shared));
+        }
+
+        check_ecdh(
+            "700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287",
+            "db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac",
+            "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534",
+            "ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b230",
+            "28af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141",
+            "46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b",
+        );
+        check_ecdh(
+            "809f04289c64348c01515eb03d5ce7ac1a8cb9498f5caa50197e58d43a86a7ae",
+            "b29d84e811197f25eba8f5194092cb6ff440e26d4421011372461f579271cda3",
+            "38f65d6dce47676044d58ce5139582d568f64bb16098d179dbab07741dd5caf5",
+            "119f2f047902782ab0c9e27a54aff5eb9b964829ca99c06b02ddba95b0a3f6d0",
+            "8f52b726664cac366fc98ac7a012b2682cbd962e5acb544671d41b9445704d1d",
+            "057d636096cb80b67a8c038c890e887d1adfa4195e9b3ce241c8a778c59cda67",
+        );
+    }
+
+    #[test]
+    fn ecdh_point_validation() {
+        let mut p256: P256 = P256::new();
+        p256.gen_key_pair();
+
+        let mut bob: P256 = P256::new();
+        bob.gen_key_pair();
+
+        assert_eq!(p256.symmetric_key(&bob.pub_key), bob.symmetric_key(&p256.pub_key));
+
+        // compressed points decode to the same key
+        let mut compressed: Vec<u8> = bob.pub_key[..33].to_vec();
+        compressed[0] = 0x02 | (bob.pub_key[64] & 1);
+        assert_eq!(p256.symmetric_key(&compressed), bob.symmetric_key(&p256.pub_key));
+
+        let mut not_on_curve: [u8; 65] = bob.pub_key;
+        not_on_curve[64] ^= 0x01;
+        assert_eq!(p256.symmetric_key(&not_on_curve), Err(EcError::InvalidPoint));
+
+        // x = p is not a canonical coordinate
+        let mut non_canonical: Vec<u8> = hex("04ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        non_canonical.extend_from_slice(&bob.pub_key[33..]);
+        assert_eq!(p256.symmetric_key(&non_canonical), Err(EcError::NonCanonicalEncoding));
+
+        assert_eq!(p256.symmetric_key(&[0x00]), Err(EcError::InvalidPoint));
+        assert_eq!(p256.symmetric_key(&bob.pub_key[..64]), Err(EcError::InvalidPoint));
+    }
+
+    #[test]
+    fn ecdsa_known_answers() {
+        // Key, nonces and signatures for the messages "sample" and "test" with SHA-256 from
+        // https://datatracker.ietf.org/doc/html/rfc6979#appendix-A.2.5
+        fn check_ecdsa(message: &str, nonce: &str, signature: &str) {
+            let mut p256: P256 = P256::new();
+            p256.set_secret_key(&hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").try_into().unwrap()).unwrap();
+
+            assert_eq!(
+                p256.pub_key.to_vec(),
+                hex("0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"),
+            );
+
+            let message_hash: [u8; 32] = Sha256::new().digest(message.as_bytes());
+            let (computed, _) = p256.weierstrass.ecdsa_sign(&p256.sec_key, &message_hash, &hex(nonce).try_into().unwrap()).unwrap();
+            let expected: [u8; 64] = hex(signature).try_into().unwrap();
+
+            assert_eq!(computed, expected);
+            assert_eq!(p256.sign(message.as_bytes()), expected);
+            assert_eq!(P256::verify(&p256.pub_key, message.as_bytes(), &expected), Ok(()));
+        }
+
+        check_ecdsa(
+            "sample",
+            "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        );
+        check_ecdsa(
+            "test",
+            "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+        );
+    }
+
+    #[test]
+    fn cavp_ecdsa_sigver_vectors() {
+        // Test vectors from the CAVP SigVer.rsp, [P-256,SHA-256], COUNT = 0 to 4:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/digital-signatures
+        fn check_sigver(message: &str, public_x: &str, public_y: &str, signature: &str, expected: Result<(), EcError>) {
+            let public_key: Vec<u8> = [hex("04"), hex(public_x), hex(public_y)].concat();
+
+            assert_eq!(P256::verify(&public_key, &hex(message), &hex(signature).try_into().unwrap()), expected);
+        }
+
+        // Result = F (3 - S changed)
+        check_sigver(
+            "e4796db5f785f207aa30d311693b3702821dff1168fd2e04c0836825aefd850d9aa60326d88cde1a23c7745351392ca2288d632c264f197d05cd424a30336c19fd09bb229654f0222fcb881a4b35c290a093ac159ce13409111ff0358411133c24f5b8e2090d6db6558afc36f06ca1f6ef779785adba68db27a409859fc4c4a0",
+            "87f8f2b218f49845f6f10eec3877136269f5c1a54736dbdf69f89940cad41555",
+            "e15f369036f49842fac7a86c8a2b0557609776814448b8f5e84aa9f4395205e9",
+            "d19ff48b324915576416097d2544f7cbdf8768b1454ad20e0baac50e211f23b0a3e81e59311cdfff2d4784949f7a2cb50ba6c3a91fa54710568e61aca3e847c6",
+            Err(EcError::InvalidSignature),
+        );
+        // Result = F (2 - R changed)
+        check_sigver(
+            "069a6e6b93dfee6df6ef6997cd80dd2182c36653cef10c655d524585655462d683877f95ecc6d6c81623d8fac4e900ed0019964094e7de91f1481989ae1873004565789cbf5dc56c62aedc63f62f3b894c9c6f7788c8ecaadc9bd0e81ad91b2b3569ea12260e93924fdddd3972af5273198f5efda0746219475017557616170e",
+            "5cf02a00d205bdfee2016f7421807fc38ae69e6b7ccd064ee689fc1a94a9f7d2",
+            "ec530ce3cc5c9d1af463f264d685afe2b4db4b5828d7e61b748930f3ce622a85",
+            "dc23d130c6117fb5751201455e99f36f59aba1a6a21cf2d0e7481a97451d6693d6ce7708c18dbf35d4f8aa7240922dc6823f2e7058cbc1484fcad1599db5018c",
+            Err(EcError::InvalidSignature),
+        );
+        // Result = F (4 - Q changed)
+        check_sigver(
+            "df04a346cf4d0e331a6db78cca2d456d31b0a000aa51441defdb97bbeb20b94d8d746429a393ba88840d661615e07def615a342abedfa4ce912e562af714959896858af817317a840dcff85a057bb91a3c2bf90105500362754a6dd321cdd86128cfc5f04667b57aa78c112411e42da304f1012d48cd6a7052d7de44ebcc01de",
+            "2ddfd145767883ffbb0ac003ab4a44346d08fa2570b3120dcce94562422244cb",
+            "5f70c7d11ac2b7a435ccfbbae02c3df1ea6b532cc0e9db74f93fffca7c6f9a64",
+            "9913111cff6f20c5bf453a99cd2c2019a4e749a49724a08774d14e4c113edda89467cd4cd21ecb56b0cab0a9a453b43386845459127a952421f5c6382866c5cc",
+            Err(EcError::InvalidSignature),
+        );
+        check_sigver(
+            "e1130af6a38ccb412a9c8d13e15dbfc9e69a16385af3c3f1e5da954fd5e7c45fd75e2b8c36699228e92840c0562fbf3772f07e17f1add56588dd45f7450e1217ad239922dd9c32695dc71ff2424ca0dec1321aa47064a044b7fe3c2b97d03ce470a592304c5ef21eed9f93da56bb232d1eeb0035f9bf0dfafdcc4606272b20a3",
+            "e424dc61d4bb3cb7ef4344a7f8957a0c5134e16f7a67c074f82e6e12f49abf3c",
+            "970eed7aa2bc48651545949de1dddaf0127e5965ac85d1243d6f60e7dfaee927",
+            "bf96b99aa49c705c910be33142017c642ff540c76349b9dab72f981fd9347f4f17c55095819089c2e03b9cd415abdf12444e323075d98f31920b9e0f57ec871c",
+            Ok(()),
+        );
+        check_sigver(
+            "73c5f6a67456ae48209b5f85d1e7de7758bf235300c6ae2bdceb1dcb27a7730fb68c950b7fcada0ecc4661d3578230f225a875e69aaa17f1e71c6be5c831f22663bac63d0c7a9635edb0043ff8c6f26470f02a7bc56556f1437f06dfa27b487a6c4290d8bad38d4879b334e341ba092dde4e4ae694a9c09302e2dbf443581c08",
+            "e0fc6a6f50e1c57475673ee54e3a57f9a49f3328e743bf52f335e3eeaa3d2864",
+            "7f59d689c91e463607d9194d99faf316e25432870816dde63f5d4b373f12f22a",
+            "1d75830cd36f4c9aa181b2c4221e87f176b7f05b7c87824e82e396c88315c407cb2acb01dac96efc53a32d4a0d85d0c2e48955214783ecf50a4f0414a319c05a",
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn cavp_ecdsa_siggen_vectors() {
+        // Test vectors from the CAVP SigGen.rsp, [P-256,SHA-256], COUNT = 0 to 2:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/digital-signatures
+        fn check_siggen(message: &str, secret_key: &str, public_x: &str, public_y: &str, nonce: &str, signature: &str) {
+            let mut p256: P256 = P256::new();
+            p256.set_secret_key(&hex(secret_key).try_into().unwrap()).unwrap();
+
+            assert_eq!(p256.pub_key.to_vec(), [hex("04"), hex(public_x), hex(public_y)].concat());
+
+            let message_hash: [u8; 32] = Sha256::new().digest(&hex(message));
+            let (computed, _) = p256.weierstrass.ecdsa_sign(&p256.sec_key, &message_hash, &hex(nonce).try_into().unwrap()).unwrap();
+            let expected: [u8; 64] = hex(signature).try_into().unwrap();
+
+            assert_eq!(computed, expected);
+            assert_eq!(P256::verify(&p256.pub_key, &hex(message), &expected), Ok(()));
+        }
+
+        check_siggen(
+            "5905238877c77421f73e43ee3da6f2d9e2ccad5fc942dcec0cbd25482935faaf416983fe165b1a045ee2bcd2e6dca3bdf46c4310a7461f9a37960ca672d3feb5473e253605fb1ddfd28065b53cb5858a8ad28175bf9bd386a5e471ea7a65c17cc934a9d791e91491eb3754d03799790fe2d308d16146d5c9b0d0debd97d79ce8",
+            "519b423d715f8b581f4fa8ee59f4771a5b44c8130b4e3eacca54a56dda72b464",
+            "1ccbe91c075fc7f4f033bfa248db8fccd3565de94bbfb12f3c59ff46c271bf83",
+            "ce4014c68811f9a21a1fdb2c0e6113e06db7ca93b7404e78dc7ccd5ca89a4ca9",
+            "94a1bbb14b906a61a280f245f9e93c7f3b4a6247824f5d33b9670787642a68de",
+            "f3ac8061b514795b8843e3d6629527ed2afd6b1f6a555a7acabb5e6f79c8c2ac8bf77819ca05a6b2786c76262bf7371cef97b218e96f175a3ccdda2acc058903",
+        );
+        check_siggen(
+            "c35e2f092553c55772926bdbe87c9796827d17024dbb9233a545366e2e5987dd344deb72df987144b8c6c43bc41b654b94cc856e16b96d7a821c8ec039b503e3d86728c494a967d83011a0e090b5d54cd47f4e366c0912bc808fbb2ea96efac88fb3ebec9342738e225f7c7c2b011ce375b56621a20642b4d36e060db4524af1",
+            "0f56db78ca460b055c500064824bed999a25aaf48ebb519ac201537b85479813",
+            "e266ddfdc12668db30d4ca3e8f7749432c416044f2d2b8c10bf3d4012aeffa8a",
+            "bfa86404a2e9ffe67d47c587ef7a97a7f456b863b4d02cfc6928973ab5b1cb39",
+            "6d3e71882c3b83b156bb14e0ab184aa9fb728068d3ae9fac421187ae0b2f34c6",
+            "976d3a4e9d23326dc0baa9fa560b7c4e53f42864f508483a6473b6a11079b2db1b766e9ceb71ba6c01dcd46e0af462cd4cfa652ae5017d4555b8eeefe36e1932",
+        );
+        check_siggen(
+            "3c054e333a94259c36af09ab5b4ff9beb3492f8d5b4282d16801daccb29f70fe61a0b37ffef5c04cd1b70e85b1f549a1c4dc672985e50f43ea037efa9964f096b5f62f7ffdf8d6bfb2cc859558f5a393cb949dbd48f269343b5263dcdb9c556eca074f2e98e6d94c2c29a677afaf806edf79b15a3fcd46e7067b7669f83188ee",
+            "e283871239837e13b95f789e6e1af63bf61c918c992e62bca040d64cad1fc2ef",
+            "74ccd8a62fba0e667c50929a53f78c21b8ff0c3c737b0b40b1750b2302b0bde8",
+            "29074e21f3a0ef88b9efdf10d06aa4c295cc1671f758ca0e4cd108803d0f2614",
+            "ad5e887eb2b380b8d8280ad6e5ff8a60f4d26243e0124c2f31a297b5d0835de2",
+            "35fb60f5ca0f3ca08542fb3cc641c8263a2cab7a90ee6a5e1583fac2bb6f6bd1ee59d81bc9db1055cc0ed97b159d8784af04e98511d0a9a407b99bb292572e96",
+        );
+    }
+
+    #[test]
+    fn deterministic_sha512_signatures() {
+        // Signatures for the messages "sample" and "test" with SHA-512 from
+        // https://datatracker.ietf.org/doc/html/rfc6979#appendix-A.2.5
+        let mut p256: P256 = P256::new();
+        p256.set_secret_key(&hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").try_into().unwrap()).unwrap();
+
+        let (signature, _) = p256.weierstrass.ecdsa_sign_deterministic(HashType::Sha512Type, &p256.sec_key, &Sha512::new().digest("sample".as_bytes()));
+        assert_eq!(signature.to_vec(), hex("8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f002362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe"));
+
+        let (signature, _) = p256.weierstrass.ecdsa_sign_deterministic(HashType::Sha512Type, &p256.sec_key, &Sha512::new().digest("test".as_bytes()));
+        assert_eq!(signature.to_vec(), hex("461d93f31b6540894788fd206c07cfa0cc35f46fa3c91816fff1040ad1581a0439af9f15de0db8d97e72719c74820d304ce5226e32dedae67519e840d1194e55"));
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut p256: P256 = P256::new();
+        p256.gen_key_pair();
+
+        let message: &[u8] = "lightweight blockchain".as_bytes();
+        let signature: [u8; 64] = p256.sign(message);
+
+        assert_eq!(P256::verify(&p256.pub_key, message, &signature), Ok(()));
+        assert_eq!(P256::verify(&p256.pub_key, "other message".as_bytes(), &signature), Err(EcError::InvalidSignature));
+
+        let mut modified: [u8; 64] = signature;
+        modified[10] ^= 0x01;
+        assert_eq!(P256::verify(&p256.pub_key, message, &modified), Err(EcError::InvalidSignature));
+
+        // s = n is out of range
+        let mut out_of_range: [u8; 64] = signature;
+        out_of_range[32..].copy_from_slice(&hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"));
+        assert_eq!(P256::verify(&p256.pub_key, message, &out_of_range), Err(EcError::InvalidSignature));
+    }
+}
@@ -0,0 +1,184 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://link.springer.com/content/pdf/10.1007/3-540-46766-1_9.pdf
+*/
+
+#![allow(dead_code)]
+
+use std::ops::{Add, Sub};
+
+use super::{EcError, Ristretto255};
+use super::edwards25519::{EdwardsPoint, reduce_mod_l, mul_add_mod_l, L_MINUS_1};
+use super::msm::multiscalar_mult;
+
+use crate::utils::rng;
+
+// C = value * G + blinding * H in the Ristretto255 group, where G is the generator and
+// H = hash_to_group(encode(G)), so nobody knows the discrete logarithm of H to base G.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PedersenCommitment {
+    point: Ristretto255,
+}
+
+pub struct Pedersen {
+    g: Ristretto255,
+    h: Ristretto255,
+}
+
+impl Pedersen {
+    pub fn new() -> Self {
+        let g: Ristretto255 = Ristretto255::generator();
+
+        Self {
+            h: Ristretto255::hash_to_group(&g.encode()),
+            g,
+        }
+    }
+
+    pub fn commit(&self, value: u64, blinding: &[u8; 32]) -> PedersenCommitment {
+        // constant time in value and blinding
+        let terms: [([u8; 32], EdwardsPoint); 2] = [
+            (value_scalar(value), self.g.edwards_point()),
+            (reduce_mod_l(blinding), self.h.edwards_point()),
+        ];
+
+        PedersenCommitment {
+            point: Ristretto255::from_edwards_point(&multiscalar_mult(&terms)),
+        }
+    }
+
+    pub fn commit_random(&self, value: u64) -> (PedersenCommitment, [u8; 32]) {
+        // a uniform blinding factor from 64 random bytes reduced modulo L
+        let mut random: [u8; 64] = [0; 64];
+        rng::fill_bytes(&mut random);
+        let blinding: [u8; 32] = reduce_mod_l(&random);
+
+        (self.commit(value, &blinding), blinding)
+    }
+
+    pub fn open(&self, commitment: &PedersenCommitment, value: u64, blinding: &[u8; 32]) -> bool {
+        self.commit(value, blinding) == *commitment
+    }
+
+    // The blinding factor of the sum or difference of two commitments.
+    pub fn add_blindings(x: &[u8; 32], y: &[u8; 32]) -> [u8; 32] {
+        let mut one: [u8; 32] = [0; 32];
+        one[0] = 1;
+
+        mul_add_mod_l(x, &one, y)
+    }
+
+    pub fn sub_blindings(x: &[u8; 32], y: &[u8; 32]) -> [u8; 32] {
+        mul_add_mod_l(y, &L_MINUS_1, x)
+    }
+}
+
+impl Default for Pedersen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PedersenCommitment {
+    pub fn encode(&self) -> [u8; 32] {
+        self.point.encode()
+    }
+
+    pub fn decode(input: &[u8; 32]) -> Result<Self, EcError> {
+        Ok(Self { point: Ristretto255::decode(input)? })
+    }
+}
+
+impl Add for PedersenCommitment {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { point: self.point + other.point }
+    }
+}
+
+impl Sub for PedersenCommitment {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self { point: self.point - other.point }
+    }
+}
+
+fn value_scalar(value: u64) -> [u8; 32] {
+    let mut scalar: [u8; 32] = [0; 32];
+    scalar[..8].copy_from_slice(&value.to_le_bytes());
+    scalar
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{Pedersen, PedersenCommitment};
+    use crate::crypto::ec::{EcError, Ristretto255};
+    use crate::hash::Sha512;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn generators() {
+        // H = from_uniform_bytes(Sha512(encode(G))), from_uniform_bytes is covered by the RFC 9496 vectors in ristretto255.rs
+        let pedersen: Pedersen = Pedersen::new();
+        let g: Ristretto255 = Ristretto255::generator();
+        assert_eq!(pedersen.h, Ristretto255::from_uniform_bytes(&Sha512::new().digest(&g.encode())));
+
+        let mut blinding: [u8; 32] = [0; 32];
+        blinding[0] = 7;
+        let mut value: [u8; 32] = [0; 32];
+        value[0] = 42;
+        let commitment: PedersenCommitment = pedersen.commit(42, &blinding);
+        assert_eq!(commitment.point, g.scalar_mult(&value) + pedersen.h.scalar_mult(&blinding));
+
+        // Self-generated with a Python Ristretto255 implementation, not taken from a published source.
+        assert_eq!(pedersen.h.encode().to_vec(), hex("90ca11cd6c6227cb0abc39e2710c444ae6617ea81898e716353f3410d9656605"));
+        assert_eq!(commitment.encode().to_vec(), hex("72ea15227214e3caf4294f188b01c78052e5bbaf4999c9efb0f20a39f8378870"));
+    }
+
+    #[test]
+    fn commit_and_open() {
+        let pedersen: Pedersen = Pedersen::new();
+        let (commitment, blinding) = pedersen.commit_random(1000);
+
+        assert!(pedersen.open(&commitment, 1000, &blinding));
+        assert!(!pedersen.open(&commitment, 1001, &blinding));
+        assert!(!pedersen.open(&commitment, 1000, &Pedersen::add_blindings(&blinding, &blinding)));
+
+        // hiding: the same value with another blinding factor gives another commitment
+        let (other, _) = pedersen.commit_random(1000);
+        assert_ne!(commitment, other);
+
+        let decoded: PedersenCommitment = PedersenCommitment::decode(&commitment.encode()).unwrap();
+        assert!(pedersen.open(&decoded, 1000, &blinding));
+
+        assert_eq!(PedersenCommitment::decode(&[0xff; 32]), Err(EcError::NonCanonicalEncoding));
+    }
+
+    #[test]
+    fn homomorphic_property() {
+        let pedersen: Pedersen = Pedersen::new();
+        let (input_1, blinding_1) = pedersen.commit_random(700);
+        let (input_2, blinding_2) = pedersen.commit_random(300);
+
+        // C(a, r) + C(b, s) = C(a + b, r + s)
+        let sum: PedersenCommitment = input_1 + input_2;
+        let sum_blinding: [u8; 32] = Pedersen::add_blindings(&blinding_1, &blinding_2);
+        assert!(pedersen.open(&sum, 1000, &sum_blinding));
+        assert_eq!(sum, pedersen.commit(1000, &sum_blinding));
+
+        // C(a, r) - C(b, s) = C(a - b, r - s)
+        let difference: PedersenCommitment = input_1 - input_2;
+        assert!(pedersen.open(&difference, 400, &Pedersen::sub_blindings(&blinding_1, &blinding_2)));
+
+        // a transaction balances if inputs - outputs commits to 0 with the known blinding difference
+        let (output_1, blinding_3) = pedersen.commit_random(999);
+        let fee: PedersenCommitment = pedersen.commit(1, &[0; 32]);
+        let excess: [u8; 32] = Pedersen::sub_blindings(&sum_blinding, &blinding_3);
+
+        assert!(pedersen.open(&(input_1 + input_2 - output_1 - fee), 0, &excess));
+        assert_eq!(pedersen.commit(0, &[0; 32]).encode(), [0; 32]);
+    }
+}
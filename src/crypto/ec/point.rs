@@ -6,9 +6,9 @@ Implementation according to
 use super::x25519::{FieldElement, unpack25519};
 
 #[derive(Copy, Clone, Debug)]
-pub struct Point {
-    pub x: FieldElement,
-    pub y: FieldElement,
+pub struct Point<F = FieldElement> {
+    pub x: F,
+    pub y: F,
 }
 
 impl Point {
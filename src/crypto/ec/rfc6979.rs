@@ -0,0 +1,119 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc6979#section-3.2
+*/
+
+#![allow(dead_code)]
+
+use super::field256::{FieldElement256, unpack256, pack256, sub256, less_than256};
+
+use crate::hash::{HMAC, HashType};
+
+// Deterministic ECDSA nonces for group orders of 256 bits (qlen = 256),
+// derived from the secret key and the message hash only.
+pub struct RFC6979 {
+    hmac: HMAC,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    order: FieldElement256,
+}
+
+impl RFC6979 {
+    pub fn new(hash_type: HashType, secret_key: &[u8; 32], message_hash: &[u8], order: &[u8; 32]) -> Self {
+        let outlen: usize = match hash_type {
+            HashType::Sha1Type => 20,
+            HashType::Sha256Type => 32,
+            HashType::Sha512Type => 64,
+        };
+
+        let mut rfc6979: RFC6979 = Self {
+            hmac: HMAC::new(hash_type),
+            k: vec![0x00; outlen],
+            v: vec![0x01; outlen],
+            order: unpack256(order),
+        };
+
+        let hash_octets: [u8; 32] = rfc6979.bits2octets(message_hash);
+
+        // step d - g
+        for separator in [0x00, 0x01] {
+            let input: Vec<u8> = [rfc6979.v.as_slice(), &[separator], secret_key, &hash_octets].concat();
+            rfc6979.k = rfc6979.hmac.digest(&rfc6979.k, &input).clone();
+            rfc6979.v = rfc6979.hmac.digest(&rfc6979.k, &rfc6979.v).clone();
+        }
+
+        rfc6979
+    }
+
+    pub fn next_nonce(&mut self) -> [u8; 32] {
+        // step h, each call continues with the next candidate, so a nonce rejected
+        // by the signature algorithm (r = 0 or s = 0) is replaced as in the RFC.
+        loop {
+            let mut t: Vec<u8> = Vec::with_capacity(32 + self.v.len());
+            while t.len() < 32 {
+                self.v = self.hmac.digest(&self.k, &self.v).clone();
+                t.extend_from_slice(&self.v);
+            }
+
+            let candidate: FieldElement256 = unpack256(t[..32].try_into().unwrap());
+
+            self.k = self.hmac.digest(&self.k, &[self.v.as_slice(), &[0x00]].concat()).clone();
+            self.v = self.hmac.digest(&self.k, &self.v).clone();
+
+            if candidate != [0; 4] && less_than256(&candidate, &self.order) {
+                return pack256(&candidate);
+            }
+        }
+    }
+
+    fn bits2octets(&self, message_hash: &[u8]) -> [u8; 32] {
+        // bits2int keeps the leftmost 256 bits, one subtraction reduces modulo q
+        // since q > 2^255 for the supported curves
+        let mut bytes: [u8; 32] = [0; 32];
+        if message_hash.len() >= 32 {
+            bytes.copy_from_slice(&message_hash[..32]);
+        } else {
+            bytes[32 - message_hash.len()..].copy_from_slice(message_hash);
+        }
+
+        let value: FieldElement256 = unpack256(&bytes);
+        if less_than256(&value, &self.order) {
+            return bytes;
+        }
+
+        pack256(&sub256(&value, &self.order).0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::RFC6979;
+    use crate::hash::{HashType, Sha256, Sha512};
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn p256_nonces() {
+        // Test vectors from https://datatracker.ietf.org/doc/html/rfc6979#appendix-A.2.5
+        let secret_key: [u8; 32] = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").try_into().unwrap();
+        let order: [u8; 32] = hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551").try_into().unwrap();
+
+        fn check_nonce(hash_type: HashType, secret_key: &[u8; 32], order: &[u8; 32], message_hash: &[u8], nonce: &str) {
+            let mut rfc6979: RFC6979 = RFC6979::new(hash_type, secret_key, message_hash, order);
+
+            assert_eq!(rfc6979.next_nonce().to_vec(), hex(nonce));
+        }
+
+        check_nonce(HashType::Sha256Type, &secret_key, &order, &Sha256::new().digest("sample".as_bytes()), "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60");
+        check_nonce(HashType::Sha256Type, &secret_key, &order, &Sha256::new().digest("test".as_bytes()), "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0");
+        check_nonce(HashType::Sha512Type, &secret_key, &order, &Sha512::new().digest("sample".as_bytes()), "5fa81c63109badb88c1f367b47da606da28cad69aa22c4fe6ad7df73a7173aa5");
+        check_nonce(HashType::Sha512Type, &secret_key, &order, &Sha512::new().digest("test".as_bytes()), "6915d11632aca3c40d5d51c08daf9c555933819548784480e93499000d9f0b7f");
+    }
+
+    #[test]
+    fn candidates_differ() {
+        let mut rfc6979: RFC6979 = RFC6979::new(HashType::Sha256Type, &[0x11; 32], &[0x22; 32], &[0xff; 32]);
+
+        assert_ne!(rfc6979.next_nonce(), rfc6979.next_nonce());
+    }
+}
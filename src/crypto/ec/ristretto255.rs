@@ -0,0 +1,359 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9496#section-4 (draft-irtf-cfrg-ristretto255-decaf448)
+*/
+
+#![allow(dead_code)]
+
+use std::ops::{Add, Neg, Sub};
+
+use super::EcError;
+use super::x25519::{FieldElement, SQRT_M1, pack25519, unpack25519, fadd, fsub, fmul, swap25519, is_canonical, fcneg, is_zero, is_negative};
+use super::edwards25519::{EdwardsPoint, D, base_point, identity, one, point_add, point_negate, pow_p_minus_5_div_8, scalarmult_point};
+
+use crate::hash::Sha512;
+
+// sqrt(a * d - 1), 1 / sqrt(a - d), 1 - d^2 and (d - 1)^2 with a = -1, in 16-bit limbs
+const SQRT_AD_MINUS_ONE: FieldElement = [0x2e1b, 0x497b, 0xf6a0, 0x7e97, 0x54bd, 0x1b78, 0x8e0c, 0xaf9d, 0xd1fd, 0x31f5, 0xfcc9, 0x0f3c, 0x48ac, 0x2b83, 0x31bf, 0x3769];
+const INVSQRT_A_MINUS_D: FieldElement = [0x40ea, 0x805d, 0xfdaa, 0x99c8, 0x72be, 0x5a41, 0x1617, 0x9d2f, 0xd840, 0xfe01, 0x7b91, 0x16c2, 0xfca2, 0xcfaf, 0x8905, 0x786c];
+const ONE_MINUS_D_SQ: FieldElement = [0xc176, 0x945f, 0x09c1, 0xe27c, 0x350f, 0xcd5e, 0xa138, 0x2c81, 0xdfe4, 0xbe70, 0xabdd, 0x9994, 0xe0d7, 0xb2b3, 0x72a8, 0x0290];
+const D_MINUS_ONE_SQ: FieldElement = [0x4d20, 0x44ed, 0x5aaa, 0x31ad, 0x1999, 0xb01e, 0x4a2c, 0xd29e, 0x4eeb, 0x529b, 0xd32f, 0x4cdc, 0x2241, 0xf66c, 0xb37a, 0x5968];
+
+// Element of the prime-order group, represented by one of the Edwards points of its coset.
+// Two representatives differing by a point of order 2 or 4 are the same element.
+#[derive(Copy, Clone, Debug)]
+pub struct Ristretto255 {
+    point: EdwardsPoint,
+}
+
+impl Ristretto255 {
+    pub fn identity() -> Self {
+        Self { point: identity() }
+    }
+
+    pub fn generator() -> Self {
+        Self { point: base_point() }
+    }
+
+    pub fn decode(input: &[u8; 32]) -> Result<Self, EcError> {
+        // RFC 9496 section 4.3.1
+        if !is_canonical(input) || input[0] & 1 == 1 {
+            return Err(EcError::NonCanonicalEncoding);
+        }
+        let s: FieldElement = unpack25519(input);
+
+        let s_power_2: FieldElement = fmul(&s, &s);
+        let u1: FieldElement = fsub(&one(), &s_power_2);
+        let u2: FieldElement = fadd(&one(), &s_power_2);
+        let u2_power_2: FieldElement = fmul(&u2, &u2);
+
+        // v = -(d * u1^2) - u2^2
+        let v: FieldElement = fsub(&fsub(&[0; 16], &fmul(&D, &fmul(&u1, &u1))), &u2_power_2);
+
+        let (was_square, invsqrt) = sqrt_ratio_m1(&one(), &fmul(&v, &u2_power_2));
+
+        let den_x: FieldElement = fmul(&invsqrt, &u2);
+        let den_y: FieldElement = fmul(&fmul(&invsqrt, &den_x), &v);
+
+        let x: FieldElement = abs(&fmul(&fadd(&s, &s), &den_x));
+        let y: FieldElement = fmul(&u1, &den_y);
+        let t: FieldElement = fmul(&x, &y);
+
+        if !was_square || is_negative(&t) || is_zero(&y) {
+            return Err(EcError::InvalidPoint);
+        }
+
+        Ok(Self { point: EdwardsPoint { x, y, z: one(), t } })
+    }
+
+    pub fn encode(&self) -> [u8; 32] {
+        // RFC 9496 section 4.3.2
+        let EdwardsPoint { x: x0, y: y0, z: z0, t: t0 } = self.point;
+
+        let u1: FieldElement = fmul(&fadd(&z0, &y0), &fsub(&z0, &y0));
+        let u2: FieldElement = fmul(&x0, &y0);
+
+        // ignore was_square since this is always square
+        let (_, invsqrt) = sqrt_ratio_m1(&one(), &fmul(&u1, &fmul(&u2, &u2)));
+
+        let den1: FieldElement = fmul(&invsqrt, &u1);
+        let den2: FieldElement = fmul(&invsqrt, &u2);
+        let z_inv: FieldElement = fmul(&fmul(&den1, &den2), &t0);
+
+        let ix0: FieldElement = fmul(&x0, &SQRT_M1);
+        let iy0: FieldElement = fmul(&y0, &SQRT_M1);
+        let enchanted_denominator: FieldElement = fmul(&den1, &INVSQRT_A_MINUS_D);
+
+        let rotate: i64 = is_negative(&fmul(&t0, &z_inv)) as i64;
+
+        // x = iy0, y = ix0 and den_inv = enchanted_denominator if rotate, else x0, y0, den2
+        let mut x: FieldElement = x0;
+        let mut y: FieldElement = y0;
+        let mut den_inv: FieldElement = den2;
+        let mut rotated_x: FieldElement = iy0;
+        let mut rotated_y: FieldElement = ix0;
+        let mut rotated_den_inv: FieldElement = enchanted_denominator;
+        swap25519(&mut x, &mut rotated_x, rotate);
+        swap25519(&mut y, &mut rotated_y, rotate);
+        swap25519(&mut den_inv, &mut rotated_den_inv, rotate);
+
+        let mut negated_y: FieldElement = fsub(&[0; 16], &y);
+        swap25519(&mut y, &mut negated_y, is_negative(&fmul(&x, &z_inv)) as i64);
+
+        let mut s: FieldElement = abs(&fmul(&den_inv, &fsub(&z0, &y)));
+        pack25519(&mut s)
+    }
+
+    pub fn from_uniform_bytes(input: &[u8; 64]) -> Self {
+        // RFC 9496 section 4.3.4, the top bit of both halves is masked by unpack25519
+        let r0: FieldElement = unpack25519(input[..32].try_into().unwrap());
+        let r1: FieldElement = unpack25519(input[32..].try_into().unwrap());
+
+        Self { point: point_add(&map(&r0), &map(&r1)) }
+    }
+
+    pub fn hash_to_group(message: &[u8]) -> Self {
+        Ristretto255::from_uniform_bytes(&Sha512::new().digest(message))
+    }
+
+    pub fn scalar_mult(&self, scalar: &[u8; 32]) -> Self {
+        Self { point: scalarmult_point(&self.point, scalar) }
+    }
+
+    pub fn edwards_point(&self) -> EdwardsPoint {
+        self.point
+    }
+
+    // only for points that are sums of multiples of group elements
+    pub(crate) fn from_edwards_point(point: &EdwardsPoint) -> Self {
+        Self { point: *point }
+    }
+}
+
+impl PartialEq for Ristretto255 {
+    fn eq(&self, other: &Self) -> bool {
+        // RFC 9496 section 4.5: x1 * y2 == y1 * x2 or y1 * y2 == x1 * x2
+        let p: &EdwardsPoint = &self.point;
+        let q: &EdwardsPoint = &other.point;
+
+        let equal_1: bool = is_zero(&fsub(&fmul(&p.x, &q.y), &fmul(&p.y, &q.x)));
+        let equal_2: bool = is_zero(&fsub(&fmul(&p.y, &q.y), &fmul(&p.x, &q.x)));
+
+        equal_1 | equal_2
+    }
+}
+
+impl Add for Ristretto255 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { point: point_add(&self.point, &other.point) }
+    }
+}
+
+impl Sub for Ristretto255 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self { point: point_add(&self.point, &point_negate(&other.point)) }
+    }
+}
+
+impl Neg for Ristretto255 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { point: point_negate(&self.point) }
+    }
+}
+
+fn abs(x: &FieldElement) -> FieldElement {
+    fcneg(x, is_negative(x) as i64)
+}
+
+fn sqrt_ratio_m1(u: &FieldElement, v: &FieldElement) -> (bool, FieldElement) {
+    // RFC 9496 section 4.2, (true, +sqrt(u / v)) if u / v is square, (false, +sqrt(i * u / v)) otherwise
+    let v_power_3: FieldElement = fmul(&fmul(v, v), v);
+    let v_power_7: FieldElement = fmul(&fmul(&v_power_3, &v_power_3), v);
+    let mut r: FieldElement = fmul(&fmul(u, &v_power_3), &pow_p_minus_5_div_8(&fmul(u, &v_power_7)));
+
+    let check: FieldElement = fmul(v, &fmul(&r, &r));
+    let u_negated: FieldElement = fsub(&[0; 16], u);
+
+    let correct_sign_sqrt: bool = is_zero(&fsub(&check, u));
+    let flipped_sign_sqrt: bool = is_zero(&fsub(&check, &u_negated));
+    let flipped_sign_sqrt_i: bool = is_zero(&fsub(&check, &fmul(&u_negated, &SQRT_M1)));
+
+    let mut r_prime: FieldElement = fmul(&SQRT_M1, &r);
+    swap25519(&mut r, &mut r_prime, (flipped_sign_sqrt | flipped_sign_sqrt_i) as i64);
+
+    (correct_sign_sqrt | flipped_sign_sqrt, abs(&r))
+}
+
+fn map(t: &FieldElement) -> EdwardsPoint {
+    // RFC 9496 section 4.3.4, Elligator 2 onto the Jacobi quartic
+    let r: FieldElement = fmul(&SQRT_M1, &fmul(t, t));
+    let u: FieldElement = fmul(&fadd(&r, &one()), &ONE_MINUS_D_SQ);
+    let v: FieldElement = fmul(&fsub(&fsub(&[0; 16], &one()), &fmul(&r, &D)), &fadd(&r, &D));
+
+    let (was_square, mut s) = sqrt_ratio_m1(&u, &v);
+    let mut s_prime: FieldElement = fsub(&[0; 16], &abs(&fmul(&s, t)));
+    let mut c: FieldElement = fsub(&[0; 16], &one());
+    let mut c_prime: FieldElement = r;
+    swap25519(&mut s, &mut s_prime, !was_square as i64);
+    swap25519(&mut c, &mut c_prime, !was_square as i64);
+
+    let n: FieldElement = fsub(&fmul(&fmul(&c, &fsub(&r, &one())), &D_MINUS_ONE_SQ), &v);
+    let s_power_2: FieldElement = fmul(&s, &s);
+
+    let w0: FieldElement = fmul(&fadd(&s, &s), &v);
+    let w1: FieldElement = fmul(&n, &SQRT_AD_MINUS_ONE);
+    let w2: FieldElement = fsub(&one(), &s_power_2);
+    let w3: FieldElement = fadd(&one(), &s_power_2);
+
+    EdwardsPoint {
+        x: fmul(&w0, &w3),
+        y: fmul(&w2, &w1),
+        z: fmul(&w1, &w3),
+        t: fmul(&w0, &w2),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Ristretto255;
+    use crate::crypto::ec::EcError;
+    use crate::crypto::ec::edwards25519::{EdwardsPoint, point_add, one};
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn generator_multiples() {
+        // Encodings of 0 * B, 1 * B, ..., 15 * B from https://ristretto.group/test_vectors/ristretto255.html
+        let multiples: [&str; 16] = [
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+            "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+            "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+            "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+            "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+            "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+            "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+            "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+            "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+            "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+            "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+            "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+            "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+            "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+        ];
+
+        let mut multiple: Ristretto255 = Ristretto255::identity();
+        for (i, encoding) in multiples.iter().enumerate() {
+            let encoding: [u8; 32] = hex(encoding).try_into().unwrap();
+
+            assert_eq!(multiple.encode(), encoding, "encoding of {} * B", i);
+
+            let decoded: Ristretto255 = Ristretto255::decode(&encoding).unwrap();
+            assert_eq!(decoded.encode(), encoding);
+            assert_eq!(decoded, multiple);
+
+            let mut scalar: [u8; 32] = [0; 32];
+            scalar[0] = i as u8;
+            assert_eq!(Ristretto255::generator().scalar_mult(&scalar), multiple);
+
+            multiple = multiple + Ristretto255::generator();
+        }
+    }
+
+    #[test]
+    fn bad_encodings() {
+        // Invalid encodings from https://ristretto.group/test_vectors/ristretto255.html
+        let non_canonical: [&str; 12] = [
+            // non-canonical field encodings
+            "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // negative field elements
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+            "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+            "c940e5a4404157cfb1628b108db051a8d439e1a421394ec4ebccb9ec92a8ac78",
+            "47cfc5497c53dc8e61c91d17fd626ffb1c49e2bca94eed052281b510b1117a24",
+            "f1c6165d33367351b0da8f6e4511010c68174a03b6581212c71c0e1d026c3c72",
+            "87260f7a2f12495118360f02c26a470f450dadf34a413d21042b43b9d93e1309",
+        ];
+        let invalid: [&str; 17] = [
+            // non-square x^2
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+            "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+            "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+            "2a292df7e32cababbd9de088d1d1abec9fc0440f637ed2fba145094dc14bea08",
+            "f4a9e534fc0d216c44b218fa0c42d99635a0127ee2e53c712f70609649fdff22",
+            "8268436f8c4126196cf64b3c7ddbda90746a378625f9813dd9b8457077256731",
+            "2810e5cbc2cc4d4eece54f61c6f69758e289aa7ab440b3cbeaa21995c2f4232b",
+            // negative x * y
+            "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+            "a45fdc55c76448c049a1ab33f17023edfb2be3581e9c7aade8a6125215e04220",
+            "d483fe813c6ba647ebbfd3ec41adca1c6130c2beeee9d9bf065c8d151c5f396e",
+            "8a2e1d30050198c65a54483123960ccc38aef6848e1ec8f5f780e8523769ba32",
+            "32888462f8b486c68ad7dd9610be5192bbeaf3b443951ac1a8118419d9fa097b",
+            "227142501b9d4355ccba290404bde41575b037693cef1f438c47f8fbf35d1165",
+            "5c37cc491da847cfeb9281d407efc41e15144c876e0170b499a96a22ed31e01e",
+            "445425117cb8c90edcbc7c1cc0e74f747f2c1efa5630a967c64f287792a48a4b",
+            // s = -1, which causes y = 0
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        ];
+
+        for encoding in non_canonical {
+            assert_eq!(Ristretto255::decode(&hex(encoding).try_into().unwrap()).unwrap_err(), EcError::NonCanonicalEncoding, "{}", encoding);
+        }
+        for encoding in invalid {
+            assert_eq!(Ristretto255::decode(&hex(encoding).try_into().unwrap()).unwrap_err(), EcError::InvalidPoint, "{}", encoding);
+        }
+    }
+
+    #[test]
+    fn hash_to_group() {
+        // Encodings of from_uniform_bytes(Sha512(label)) from https://ristretto.group/test_vectors/ristretto255.html
+        let vectors: [(&str, &str); 7] = [
+            ("Ristretto is traditionally a short shot of espresso coffee", "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46"),
+            ("made with the normal amount of ground coffee but extracted with", "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b"),
+            ("about half the amount of water in the same amount of time", "006ccd2a9e6867e6a2c5cea83d3302cc9de128dd2a9a57dd8ee7b9d7ffe02826"),
+            ("by using a finer grind.", "f8f0c87cf237953c5890aec3998169005dae3eca1fbb04548c635953c817f92a"),
+            ("This produces a concentrated shot of coffee per volume.", "ae81e7dedf20a497e10c304a765c1767a42d6e06029758d2d7e8ef7cc4c41179"),
+            ("Just pulling a normal shot short will produce a weaker shot", "e2705652ff9f5e44d3e841bf1c251cf7dddb77d140870d1ab2ed64f1a9ce8628"),
+            ("and is not a Ristretto as some believe.", "80bd07262511cdde4863f8a7434cef696750681cb9510eea557088f76d9e5065"),
+        ];
+
+        for (label, encoding) in vectors {
+            assert_eq!(Ristretto255::hash_to_group(label.as_bytes()).encode().to_vec(), hex(encoding));
+        }
+    }
+
+    #[test]
+    fn group_operations() {
+        let p: Ristretto255 = Ristretto255::hash_to_group("p".as_bytes());
+        let q: Ristretto255 = Ristretto255::hash_to_group("q".as_bytes());
+
+        assert_eq!(p + q - q, p);
+        assert_eq!(p - p, Ristretto255::identity());
+        assert_eq!(-p + p, Ristretto255::identity());
+        assert_ne!(p, q);
+
+        let mut three: [u8; 32] = [0; 32];
+        three[0] = 3;
+        assert_eq!(p.scalar_mult(&three), p + p + p);
+
+        // adding the point (0, -1) of order 2 to the representative gives the same element
+        let order_2: EdwardsPoint = EdwardsPoint { x: [0; 16], y: [-1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], z: one(), t: [0; 16] };
+        let torsion: Ristretto255 = Ristretto255 { point: point_add(&p.edwards_point(), &order_2) };
+
+        assert_eq!(torsion, p);
+        assert_eq!(torsion.encode(), p.encode());
+    }
+}
@@ -0,0 +1,220 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
+*/
+
+#![allow(dead_code)]
+
+use super::{Curve, EcError, Point};
+use super::field256::{FieldElement256, unpack256, pack256};
+use super::weierstrass::{Weierstrass, JacobianPoint};
+
+use crate::hash::Sha256;
+use crate::utils::rng;
+
+// BIP-340 Schnorr signatures on secp256k1 with x-only public keys
+pub struct Schnorr {
+    weierstrass: Weierstrass,
+    pub sec_key: [u8; 32],
+    pub pub_key: [u8; 32],
+}
+
+impl Schnorr {
+    pub fn new() -> Self {
+        Self {
+            weierstrass: Weierstrass::new(Curve::secp256k1()),
+            sec_key: [0; 32],
+            pub_key: [0; 32],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut secret_key: [u8; 32] = [0; 32];
+
+        loop {
+            rng::fill_array(&mut secret_key);
+
+            if self.set_secret_key(&secret_key).is_ok() {
+                break;
+            }
+        }
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) -> Result<(), EcError> {
+        if !self.weierstrass.is_valid_scalar(secret_key) {
+            return Err(EcError::InvalidScalar);
+        }
+
+        let public_key: Point<FieldElement256> = self.public_point(secret_key);
+
+        self.sec_key = *secret_key;
+        self.pub_key = pack256(&public_key.x);
+
+        Ok(())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Result<[u8; 64], EcError> {
+        // fresh auxiliary randomness protects against side channels, but is not needed for security
+        let mut aux_rand: [u8; 32] = [0; 32];
+        rng::fill_array(&mut aux_rand);
+
+        self.sign_with_aux(message, &aux_rand)
+    }
+
+    pub fn sign_with_aux(&self, message: &[u8], aux_rand: &[u8; 32]) -> Result<[u8; 64], EcError> {
+        let scalar = &self.weierstrass.scalar;
+
+        // d = d' if P has an even y-coordinate, n - d' otherwise
+        let public_key: Point<FieldElement256> = self.public_point(&self.sec_key);
+        let mut d: FieldElement256 = scalar.unpack(&self.sec_key);
+        if public_key.y[0] & 1 == 1 {
+            d = scalar.neg(&d);
+        }
+        let d_bytes: [u8; 32] = scalar.pack(&d);
+
+        let mut t: [u8; 32] = tagged_hash("BIP0340/aux", aux_rand);
+        for (t_i, d_i) in t.iter_mut().zip(d_bytes.iter()) {
+            *t_i ^= d_i;
+        }
+
+        let k_prime: [u8; 32] = scalar.pack(&scalar.unpack(&tagged_hash("BIP0340/nonce", &[t.as_slice(), &self.pub_key, message].concat())));
+        if k_prime == [0; 32] {
+            return Err(EcError::InvalidScalar);
+        }
+
+        // k = k' if R has an even y-coordinate, n - k' otherwise
+        let big_r: Point<FieldElement256> = self.weierstrass
+            .to_affine(&self.weierstrass.scalarmult(&self.weierstrass.generator(), &k_prime))
+            .ok_or(EcError::InvalidScalar)?;
+        let mut k: FieldElement256 = scalar.unpack(&k_prime);
+        if big_r.y[0] & 1 == 1 {
+            k = scalar.neg(&k);
+        }
+
+        let r: [u8; 32] = pack256(&big_r.x);
+        let e: FieldElement256 = scalar.unpack(&tagged_hash("BIP0340/challenge", &[r.as_slice(), &self.pub_key, message].concat()));
+
+        let mut signature: [u8; 64] = [0; 64];
+        signature[..32].copy_from_slice(&r);
+        signature[32..].copy_from_slice(&scalar.pack(&scalar.add(&k, &scalar.mul(&e, &d))));
+
+        // the signature is checked before it leaves, as recommended by BIP-340
+        Schnorr::verify(&self.pub_key, message, &signature)?;
+
+        Ok(signature)
+    }
+
+    pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> Result<(), EcError> {
+        let schnorr: Schnorr = Schnorr::new();
+        let weierstrass: &Weierstrass = &schnorr.weierstrass;
+        let scalar = &weierstrass.scalar;
+
+        let public_key_point: Point<FieldElement256> = weierstrass.lift_x(&unpack256(public_key), false)?;
+
+        let r: FieldElement256 = unpack256(signature[..32].try_into().unwrap());
+        let s: [u8; 32] = signature[32..].try_into().unwrap();
+
+        if !weierstrass.field.is_canonical(&pack256(&r)) || !scalar.is_canonical(&s) {
+            return Err(EcError::InvalidSignature);
+        }
+
+        let e: FieldElement256 = scalar.unpack(&tagged_hash("BIP0340/challenge", &[&signature[..32], public_key, message].concat()));
+
+        // R = s * G - e * P
+        let big_r: JacobianPoint = weierstrass.add(
+            &weierstrass.scalarmult(&weierstrass.generator(), &s),
+            &weierstrass.scalarmult(&weierstrass.jacobian(&public_key_point), &scalar.pack(&scalar.neg(&e))),
+        );
+
+        match weierstrass.to_affine(&big_r) {
+            Some(big_r) if big_r.y[0] & 1 == 0 && big_r.x == r => Ok(()),
+            _ => Err(EcError::InvalidSignature),
+        }
+    }
+
+    fn public_point(&self, secret_key: &[u8; 32]) -> Point<FieldElement256> {
+        self.weierstrass
+            .to_affine(&self.weierstrass.scalarmult(&self.weierstrass.generator(), secret_key))
+            .unwrap()
+    }
+}
+
+impl Default for Schnorr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn tagged_hash(tag: &str, message: &[u8]) -> [u8; 32] {
+    // Sha256(Sha256(tag) || Sha256(tag) || message)
+    let tag_hash: [u8; 32] = Sha256::new().digest(tag.as_bytes());
+
+    Sha256::new().digest(&[tag_hash.as_slice(), &tag_hash, message].concat())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Schnorr;
+    use crate::crypto::ec::EcError;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn bip340_test_vectors() {
+        // Test vectors from https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv,
+        // columns: index, secret key, public key, aux_rand, message, signature, verification result
+        let vectors: [(u32, &str, &str, &str, &str, &str, bool); 19] = [
+            (0, "0000000000000000000000000000000000000000000000000000000000000003", "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9", "0000000000000000000000000000000000000000000000000000000000000000", "0000000000000000000000000000000000000000000000000000000000000000", "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0", true),
+            (1, "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "0000000000000000000000000000000000000000000000000000000000000001", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A", true),
+            (2, "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9", "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8", "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906", "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C", "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7", true),
+            (3, "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710", "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517", "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3", true),
+            (4, "", "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9", "", "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703", "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4", true),
+            (5, "", "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B", false),
+            (6, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2", false),
+            (7, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD", false),
+            (8, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6", false),
+            (9, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051", false),
+            (10, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197", false),
+            (11, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B", false),
+            (12, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B", false),
+            (13, "", "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141", false),
+            (14, "", "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30", "", "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89", "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B", false),
+            (15, "0340034003400340034003400340034003400340034003400340034003400340", "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117", "0000000000000000000000000000000000000000000000000000000000000000", "", "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63", true),
+            (16, "0340034003400340034003400340034003400340034003400340034003400340", "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117", "0000000000000000000000000000000000000000000000000000000000000000", "11", "08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF", true),
+            (17, "0340034003400340034003400340034003400340034003400340034003400340", "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117", "0000000000000000000000000000000000000000000000000000000000000000", "0102030405060708090A0B0C0D0E0F1011", "5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5", true),
+            (18, "0340034003400340034003400340034003400340034003400340034003400340", "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117", "0000000000000000000000000000000000000000000000000000000000000000", "99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999", "403B12B0D8555A344175EA7EC746566303321E5DBFA8BE6F091635163ECA79A8585ED3E3170807E7C03B720FC54C7B23897FCBA0E9D0B4A06894CFD249F22367", true),
+        ];
+
+        for (index, secret_key, public_key, aux_rand, message, signature, result) in vectors {
+            let public_key: [u8; 32] = hex(public_key).try_into().unwrap();
+            let signature: [u8; 64] = hex(signature).try_into().unwrap();
+
+            if !secret_key.is_empty() {
+                let mut schnorr: Schnorr = Schnorr::new();
+                schnorr.set_secret_key(&hex(secret_key).try_into().unwrap()).unwrap();
+
+                assert_eq!(schnorr.pub_key, public_key, "public key of vector {}", index);
+                assert_eq!(schnorr.sign_with_aux(&hex(message), &hex(aux_rand).try_into().unwrap()), Ok(signature), "signature of vector {}", index);
+            }
+
+            assert_eq!(Schnorr::verify(&public_key, &hex(message), &signature).is_ok(), result, "verification of vector {}", index);
+        }
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut schnorr: Schnorr = Schnorr::new();
+        schnorr.gen_key_pair();
+
+        let message: &[u8] = "lightweight blockchain".as_bytes();
+        let signature: [u8; 64] = schnorr.sign(message).unwrap();
+
+        assert_eq!(Schnorr::verify(&schnorr.pub_key, message, &signature), Ok(()));
+        assert_eq!(Schnorr::verify(&schnorr.pub_key, "other message".as_bytes(), &signature), Err(EcError::InvalidSignature));
+
+        // x-coordinate 5 has no point on secp256k1
+        let mut invalid_key: [u8; 32] = [0; 32];
+        invalid_key[31] = 0x05;
+        assert_eq!(Schnorr::verify(&invalid_key, message, &signature), Err(EcError::InvalidPoint));
+    }
+}
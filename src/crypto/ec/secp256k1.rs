@@ -0,0 +1,256 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://www.secg.org/sec1-v2.pdf (4.1) and https://github.com/bitcoin/bips/blob/master/bip-0062.mediawiki (low S)
+*/
+
+#![allow(dead_code)]
+
+use super::{Curve, EcError, Point};
+use super::field256::{FieldElement256, unpack256, pack256, sub256, less_than256};
+use super::weierstrass::Weierstrass;
+
+use crate::hash::HashType;
+use crate::utils::rng;
+
+pub struct Secp256k1 {
+    weierstrass: Weierstrass,
+    pub sec_key: [u8; 32],
+    pub pub_key: [u8; 33],
+}
+
+impl Secp256k1 {
+    pub fn new() -> Self {
+        Self {
+            weierstrass: Weierstrass::new(Curve::secp256k1()),
+            sec_key: [0; 32],
+            pub_key: [0; 33],
+        }
+    }
+
+    pub fn gen_key_pair(&mut self) {
+        let mut secret_key: [u8; 32] = [0; 32];
+
+        // rejection sampling, a random value is out of range with probability ~2^-128
+        loop {
+            rng::fill_array(&mut secret_key);
+
+            if self.set_secret_key(&secret_key).is_ok() {
+                break;
+            }
+        }
+    }
+
+    pub fn set_secret_key(&mut self, secret_key: &[u8; 32]) -> Result<(), EcError> {
+        if !self.weierstrass.is_valid_scalar(secret_key) {
+            return Err(EcError::InvalidScalar);
+        }
+
+        let public_key: Point<FieldElement256> = self.weierstrass
+            .to_affine(&self.weierstrass.scalarmult(&self.weierstrass.generator(), secret_key))
+            .ok_or(EcError::InvalidScalar)?;
+
+        self.sec_key = *secret_key;
+        self.pub_key.copy_from_slice(&self.weierstrass.encode_point(&public_key, true));
+
+        Ok(())
+    }
+
+    pub fn uncompressed_public_key(&self) -> [u8; 65] {
+        let public_key: Point<FieldElement256> = self.weierstrass.decode_point(&self.pub_key).unwrap();
+
+        self.weierstrass.encode_point(&public_key, false).try_into().unwrap()
+    }
+
+    pub fn sign(&self, message_hash: &[u8; 32]) -> [u8; 64] {
+        self.sign_recoverable(message_hash).0
+    }
+
+    pub fn sign_recoverable(&self, message_hash: &[u8; 32]) -> ([u8; 64], u8) {
+        // deterministic nonces with HMAC-SHA256 as in libsecp256k1
+        let (signature, recovery_id) = self.weierstrass.ecdsa_sign_deterministic(HashType::Sha256Type, &self.sec_key, message_hash);
+
+        self.normalize(&signature, recovery_id)
+    }
+
+    pub fn verify(public_key: &[u8], message_hash: &[u8; 32], signature: &[u8; 64]) -> Result<(), EcError> {
+        // Only low S signatures are accepted, as in Bitcoin Core.
+        let secp256k1: Secp256k1 = Secp256k1::new();
+
+        if !secp256k1.is_low_s(signature) {
+            return Err(EcError::InvalidSignature);
+        }
+
+        let public_key: Point<FieldElement256> = secp256k1.weierstrass.decode_point(public_key)?;
+
+        secp256k1.weierstrass.ecdsa_verify(&public_key, message_hash, signature)
+    }
+
+    pub fn recover(message_hash: &[u8; 32], signature: &[u8; 64], recovery_id: u8) -> Result<[u8; 33], EcError> {
+        let secp256k1: Secp256k1 = Secp256k1::new();
+
+        let public_key: Point<FieldElement256> = secp256k1.weierstrass.ecdsa_recover(message_hash, signature, recovery_id)?;
+
+        Ok(secp256k1.weierstrass.encode_point(&public_key, true).try_into().unwrap())
+    }
+
+    pub fn normalize_s(signature: &[u8; 64]) -> [u8; 64] {
+        // (r, n - s) is valid for the same message and key as (r, s)
+        Secp256k1::new().normalize(signature, 0).0
+    }
+
+    fn normalize(&self, signature: &[u8; 64], recovery_id: u8) -> ([u8; 64], u8) {
+        if self.is_low_s(signature) {
+            return (*signature, recovery_id);
+        }
+
+        // negating s mirrors R, which flips the parity of its y-coordinate
+        let s: FieldElement256 = unpack256(signature[32..].try_into().unwrap());
+        let (negated, _) = sub256(&self.weierstrass.scalar.modulus, &s);
+
+        let mut normalized: [u8; 64] = *signature;
+        normalized[32..].copy_from_slice(&pack256(&negated));
+
+        (normalized, recovery_id ^ 1)
+    }
+
+    fn is_low_s(&self, signature: &[u8; 64]) -> bool {
+        // s <= n / 2
+        let s: FieldElement256 = unpack256(signature[32..].try_into().unwrap());
+        let n: FieldElement256 = self.weierstrass.scalar.modulus;
+        let half_n: FieldElement256 = [
+            (n[0] >> 1) | (n[1] << 63),
+            (n[1] >> 1) | (n[2] << 63),
+            (n[2] >> 1) | (n[3] << 63),
+            n[3] >> 1,
+        ];
+
+        !less_than256(&half_n, &s)
+    }
+}
+
+impl Default for Secp256k1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Secp256k1;
+    use crate::crypto::ec::EcError;
+    use crate::hash::Sha256;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn public_keys() {
+        // Expected public keys computed with the secp256k1 implementation of pyca/cryptography
+        fn check_public_key(secret_key: &str, public_key: &str) {
+            let mut secp256k1: Secp256k1 = Secp256k1::new();
+            secp256k1.set_secret_key(&hex(secret_key).try_into().unwrap()).unwrap();
+
+            assert_eq!(secp256k1.pub_key.to_vec(), hex(public_key));
+        }
+
+        check_public_key("0000000000000000000000000000000000000000000000000000000000000001", "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        check_public_key("0000000000000000000000000000000000000000000000000000000000000002", "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+        check_public_key("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", "032c8c31fc9f990c6b55e3865a184a4ce50e09481f2eaeb3e60ec1cea13a6ae645");
+        check_public_key("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140", "0379be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+
+        let mut secp256k1: Secp256k1 = Secp256k1::new();
+        assert_eq!(secp256k1.set_secret_key(&[0; 32]), Err(EcError::InvalidScalar));
+        assert_eq!(secp256k1.set_secret_key(&hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").try_into().unwrap()), Err(EcError::InvalidScalar));
+    }
+
+    #[test]
+    fn rfc6979_signatures() {
+        // secp256k1 RFC 6979 (HMAC-SHA256) vectors circulated in the Bitcoin community and used in the tests of
+        // bitcoinjs and the Trezor crypto library, the message hash is Sha256(message) and S is low
+        let vectors: [(&str, &str, &str); 4] = [
+            ("0000000000000000000000000000000000000000000000000000000000000001", "Satoshi Nakamoto", "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"),
+            ("0000000000000000000000000000000000000000000000000000000000000001", "All those moments will be lost in time, like tears in rain. Time to die...", "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21"),
+            ("f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181", "Alan Turing", "7063ae83e7f62bbb171798131b4a0564b956930092b33b07b395615d9ec7e15c58dfcc1e00a35e1572f366ffe34ba0fc47db1e7189759b9fb233c5b05ab388ea"),
+            ("e91671c46231f833a6406ccbea0e3e392c76c167bac1cb013f6f1013980455c2", "There is a computer disease that anybody who works with computers knows about. It's a very serious disease and it interferes completely with the work. The trouble with computers is that you 'play' with them!", "b552edd27580141f3b2a5463048cb7cd3e047b97c9f98076c32dbdf85a68718b279fa72dd19bfae05577e06c7c0c1900c371fcd5893f7e1d56a37d30174671f6"),
+        ];
+
+        for (secret_key, message, signature) in vectors {
+            let mut secp256k1: Secp256k1 = Secp256k1::new();
+            secp256k1.set_secret_key(&hex(secret_key).try_into().unwrap()).unwrap();
+            let message_hash: [u8; 32] = Sha256::new().digest(message.as_bytes());
+
+            assert_eq!(secp256k1.sign(&message_hash).to_vec(), hex(signature), "{}", message);
+            assert_eq!(Secp256k1::verify(&secp256k1.pub_key, &message_hash, &hex(signature).try_into().unwrap()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn signatures_with_fixed_nonce() {
+        // message hash is Sha256("sample"), signatures checked with pyca/cryptography
+        let mut secp256k1: Secp256k1 = Secp256k1::new();
+        secp256k1.set_secret_key(&hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").try_into().unwrap()).unwrap();
+        let message_hash: [u8; 32] = hex("af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf").try_into().unwrap();
+
+        let (signature, recovery_id) = secp256k1.weierstrass.ecdsa_sign(
+            &secp256k1.sec_key,
+            &message_hash,
+            &hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60").try_into().unwrap(),
+        ).unwrap();
+        assert_eq!(signature.to_vec(), hex("432310e32cb80eb6503a26ce83cc165c783b870845fb8aad6d970889fcd7a6c8530128b6b81c548874a6305d93ed071ca6e05074d85863d4056ce89b02bfab69"));
+        assert_eq!(recovery_id, 0);
+
+        // this nonce yields a high S, which is replaced by n - S
+        let (signature, recovery_id) = secp256k1.weierstrass.ecdsa_sign(
+            &secp256k1.sec_key,
+            &message_hash,
+            &hex("648f8e193a06c30767e71fb32a4ab2abee2dc640da96782b93e82e3db5a8f016").try_into().unwrap(),
+        ).unwrap();
+        assert_eq!(signature.to_vec(), hex("3f264a873d551fc758e7937965b5ff440eee0b7182265240dba111dd0b49bf77b59656bb18821bde5c7ea936d943b554b01e8e99735e70aa267adb7c7d4ba391"));
+        assert_eq!(recovery_id, 0);
+        assert_eq!(Secp256k1::verify(&secp256k1.pub_key, &message_hash, &signature), Err(EcError::InvalidSignature));
+
+        let (normalized, recovery_id) = secp256k1.normalize(&signature, recovery_id);
+        assert_eq!(normalized[32..].to_vec(), hex("4a69a944e77de421a38156c926bc4aaa0a904e4d3bea2f919957831052ea9db0"));
+        assert_eq!(recovery_id, 1);
+        assert_eq!(Secp256k1::normalize_s(&signature), normalized);
+        assert_eq!(Secp256k1::verify(&secp256k1.pub_key, &message_hash, &normalized), Ok(()));
+        assert_eq!(Secp256k1::recover(&message_hash, &normalized, recovery_id), Ok(secp256k1.pub_key));
+    }
+
+    #[test]
+    fn foreign_signature() {
+        // signature over Sha256("lightweight blockchain") created by pyca/cryptography
+        let public_key: Vec<u8> = hex("032c8c31fc9f990c6b55e3865a184a4ce50e09481f2eaeb3e60ec1cea13a6ae645");
+        let message_hash: [u8; 32] = hex("514f809111bac8a5b011921909fde70eea54dac74001d8224dbf26d9bfa6fcee").try_into().unwrap();
+        let signature: [u8; 64] = hex("130e373e9d0858bad8d41de71b597776ca04f7009ab9954f880f58ee611ed2c23e9c8f7572c569856e54430944c21031835400e35fe8204dd47d6b0c92b6e942").try_into().unwrap();
+
+        assert_eq!(Secp256k1::verify(&public_key, &message_hash, &signature), Ok(()));
+
+        let mut secp256k1: Secp256k1 = Secp256k1::new();
+        secp256k1.set_secret_key(&hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").try_into().unwrap()).unwrap();
+        assert_eq!(Secp256k1::verify(&secp256k1.uncompressed_public_key(), &message_hash, &signature), Ok(()));
+
+        let mut modified: [u8; 32] = message_hash;
+        modified[0] ^= 0x01;
+        assert_eq!(Secp256k1::verify(&public_key, &modified, &signature), Err(EcError::InvalidSignature));
+    }
+
+    #[test]
+    fn sign_verify_recover() {
+        let mut secp256k1: Secp256k1 = Secp256k1::new();
+        secp256k1.gen_key_pair();
+
+        let message_hash: [u8; 32] = [0x42; 32];
+        let (signature, recovery_id) = secp256k1.sign_recoverable(&message_hash);
+
+        assert_eq!(Secp256k1::verify(&secp256k1.pub_key, &message_hash, &signature), Ok(()));
+        assert_eq!(Secp256k1::recover(&message_hash, &signature, recovery_id), Ok(secp256k1.pub_key));
+        assert_ne!(Secp256k1::recover(&message_hash, &signature, recovery_id ^ 1), Ok(secp256k1.pub_key));
+        assert_eq!(Secp256k1::recover(&message_hash, &signature, 4), Err(EcError::InvalidSignature));
+
+        assert_eq!(Secp256k1::verify(&secp256k1.pub_key, &message_hash, &[0; 64]), Err(EcError::InvalidSignature));
+
+        let mut not_on_curve: [u8; 65] = secp256k1.uncompressed_public_key();
+        not_on_curve[64] ^= 0x01;
+        assert_eq!(Secp256k1::verify(&not_on_curve, &message_hash, &signature), Err(EcError::InvalidPoint));
+    }
+}
@@ -0,0 +1,33 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum EcError {
+    // Point of small order, e.g. one of the X25519 u-coordinates from RFC 7748 section 6.1.
+    LowOrderPoint,
+    // Encoding of a value that is not fully reduced modulo p.
+    NonCanonicalEncoding,
+    // Key agreement produced the all-zero shared secret.
+    ZeroSharedSecret,
+    // Encoding that does not decode to a point on the curve.
+    InvalidPoint,
+    // Signature that does not verify for the given public key and message.
+    InvalidSignature,
+    // Context strings are limited to 255 bytes (RFC 8032 section 5.2).
+    ContextTooLong,
+    // Secret scalar outside of [1, n - 1].
+    InvalidScalar,
+    // VRF proof that does not verify for the given public key and input.
+    InvalidProof,
+    // Requested output longer than 255 hash blocks or 65535 bytes (RFC 9380 section 5.3.1).
+    OutputTooLong,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BatchError {
+    // Public keys, messages and signatures of different lengths, nothing was verified.
+    LengthMismatch,
+    // Indices of the signatures that do not verify.
+    InvalidSignatures(Vec<usize>),
+}
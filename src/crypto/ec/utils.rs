@@ -0,0 +1,64 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+// Scalar arithmetic modulo the group order L, shared by edwards25519 (32 bytes) and Ed448 (57 bytes).
+// L is given as little-endian 32-bit limbs with one spare limb for the shift.
+
+pub fn reduce_mod<const LIMBS: usize, const BYTES: usize>(input: &[u8], modulus: &[u64; LIMBS]) -> [u8; BYTES] {
+    // Bitwise long division, r = 2r + bit and r - L replaces r if it does not borrow.
+    let mut r: [u64; LIMBS] = [0; LIMBS];
+
+    for i in (0..input.len() * 8).rev() {
+        let bit: u64 = ((input[i >> 3] >> (i & 7)) & 1) as u64;
+
+        let mut carry: u64 = bit;
+        for limb in r.iter_mut() {
+            let shifted: u64 = (*limb << 1) | carry;
+            carry = shifted >> 32;
+            *limb = shifted & 0xffffffff;
+        }
+
+        let mut t: [u64; LIMBS] = [0; LIMBS];
+        let mut borrow: u64 = 0;
+        for j in 0..LIMBS {
+            let difference: u64 = r[j].wrapping_sub(modulus[j]).wrapping_sub(borrow);
+            borrow = (difference >> 63) & 1;
+            t[j] = difference & 0xffffffff;
+        }
+
+        // keep r if subtracting L borrowed
+        let mask: u64 = borrow.wrapping_sub(1);
+        for j in 0..LIMBS {
+            r[j] = (t[j] & mask) | (r[j] & !mask);
+        }
+    }
+
+    let mut output: [u8; BYTES] = [0; BYTES];
+    for (i, byte) in output.iter_mut().enumerate() {
+        *byte = (r[i / 4] >> (8 * (i % 4))) as u8;
+    }
+    output
+}
+
+pub fn mul_add_mod<const LIMBS: usize, const BYTES: usize>(a: &[u8; BYTES], b: &[u8; BYTES], c: &[u8; BYTES], modulus: &[u64; LIMBS]) -> [u8; BYTES] {
+    // (a * b + c) mod L, with the product computed bytewise
+    let mut product: Vec<u64> = vec![0; 2 * BYTES + 1];
+
+    for i in 0..BYTES {
+        for j in 0..BYTES {
+            product[i + j] += (a[i] as u64) * (b[j] as u64);
+        }
+        product[i] += c[i] as u64;
+    }
+
+    let mut output: Vec<u8> = vec![0; 2 * BYTES + 1];
+    let mut carry: u64 = 0;
+    for i in 0..output.len() {
+        let value: u64 = product[i] + carry;
+        output[i] = value as u8;
+        carry = value >> 8;
+    }
+
+    reduce_mod(&output, modulus)
+}
@@ -0,0 +1,467 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://www.secg.org/sec1-v2.pdf and https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian.html
+*/
+
+#![allow(dead_code)]
+
+use super::{Curve, EcError, Point};
+use super::field256::{FieldElement256, PrimeField256, unpack256, pack256, less_than256};
+use super::rfc6979::RFC6979;
+
+use crate::hash::HashType;
+
+// (X, Y, Z) stands for the affine point (X / Z^2, Y / Z^3), coordinates in
+// Montgomery form. Z = 0 is the point at infinity.
+#[derive(Copy, Clone, Debug)]
+pub struct JacobianPoint {
+    pub x: FieldElement256,
+    pub y: FieldElement256,
+    pub z: FieldElement256,
+}
+
+// Arithmetic on y^2 = x^3 + a * x + b over the field of p, with the
+// scalar field of the group order n for ECDSA.
+pub struct Weierstrass {
+    pub curve: Curve<FieldElement256, 32>,
+    pub field: PrimeField256,
+    pub scalar: PrimeField256,
+    a: FieldElement256,
+    b: FieldElement256,
+}
+
+impl Weierstrass {
+    pub fn new(curve: Curve<FieldElement256, 32>) -> Self {
+        let field: PrimeField256 = PrimeField256::new(&unpack256(&curve.p));
+        let scalar: PrimeField256 = PrimeField256::new(&unpack256(&curve.n));
+
+        Self {
+            a: field.unpack(&curve.a),
+            b: field.unpack(&curve.b),
+            curve,
+            field,
+            scalar,
+        }
+    }
+
+    pub fn infinity(&self) -> JacobianPoint {
+        JacobianPoint {
+            x: self.field.one(),
+            y: self.field.one(),
+            z: self.field.zero(),
+        }
+    }
+
+    pub fn is_infinity(&self, point: &JacobianPoint) -> bool {
+        self.field.is_zero(&point.z)
+    }
+
+    pub fn generator(&self) -> JacobianPoint {
+        self.jacobian(&self.curve.g)
+    }
+
+    pub fn jacobian(&self, point: &Point<FieldElement256>) -> JacobianPoint {
+        JacobianPoint {
+            x: self.field.montgomery_form(&point.x),
+            y: self.field.montgomery_form(&point.y),
+            z: self.field.one(),
+        }
+    }
+
+    pub fn to_affine(&self, point: &JacobianPoint) -> Option<Point<FieldElement256>> {
+        if self.is_infinity(point) {
+            return None;
+        }
+
+        let z_inverse: FieldElement256 = self.field.inverse(&point.z);
+        let z_inverse_2: FieldElement256 = self.field.square(&z_inverse);
+        let z_inverse_3: FieldElement256 = self.field.mul(&z_inverse_2, &z_inverse);
+
+        Some(Point {
+            x: self.field.canonical_form(&self.field.mul(&point.x, &z_inverse_2)),
+            y: self.field.canonical_form(&self.field.mul(&point.y, &z_inverse_3)),
+        })
+    }
+
+    pub fn point_on_curve(&self, point: &Point<FieldElement256>) -> bool {
+        if !less_than256(&point.x, &self.field.modulus) || !less_than256(&point.y, &self.field.modulus) {
+            return false;
+        }
+
+        let x: FieldElement256 = self.field.montgomery_form(&point.x);
+        let y: FieldElement256 = self.field.montgomery_form(&point.y);
+
+        self.field.square(&y) == self.right_hand_side(&x)
+    }
+
+    fn right_hand_side(&self, x: &FieldElement256) -> FieldElement256 {
+        // x^3 + a * x + b
+        let x_power_3: FieldElement256 = self.field.mul(&self.field.square(x), x);
+        self.field.add(&self.field.add(&x_power_3, &self.field.mul(&self.a, x)), &self.b)
+    }
+
+    pub fn double(&self, point: &JacobianPoint) -> JacobianPoint {
+        // dbl-2007-bl
+        let f: &PrimeField256 = &self.field;
+
+        let xx: FieldElement256 = f.square(&point.x);
+        let yy: FieldElement256 = f.square(&point.y);
+        let yyyy: FieldElement256 = f.square(&yy);
+        let zz: FieldElement256 = f.square(&point.z);
+
+        let s: FieldElement256 = f.sub(&f.sub(&f.square(&f.add(&point.x, &yy)), &xx), &yyyy);
+        let s: FieldElement256 = f.add(&s, &s);
+        let m: FieldElement256 = f.add(&f.add(&f.add(&xx, &xx), &xx), &f.mul(&self.a, &f.square(&zz)));
+
+        let x: FieldElement256 = f.sub(&f.square(&m), &f.add(&s, &s));
+        let yyyy_8: FieldElement256 = f.add(&yyyy, &yyyy);
+        let yyyy_8: FieldElement256 = f.add(&yyyy_8, &yyyy_8);
+        let yyyy_8: FieldElement256 = f.add(&yyyy_8, &yyyy_8);
+        let y: FieldElement256 = f.sub(&f.mul(&m, &f.sub(&s, &x)), &yyyy_8);
+        let z: FieldElement256 = f.sub(&f.sub(&f.square(&f.add(&point.y, &point.z)), &yy), &zz);
+
+        JacobianPoint { x, y, z }
+    }
+
+    pub fn add(&self, p: &JacobianPoint, q: &JacobianPoint) -> JacobianPoint {
+        // add-2007-bl, the exceptional cases are selected in constant time, so the
+        // run time does not depend on whether an input is infinity or p = q
+        let f: &PrimeField256 = &self.field;
+
+        let z1z1: FieldElement256 = f.square(&p.z);
+        let z2z2: FieldElement256 = f.square(&q.z);
+        let u1: FieldElement256 = f.mul(&p.x, &z2z2);
+        let u2: FieldElement256 = f.mul(&q.x, &z1z1);
+        let s1: FieldElement256 = f.mul(&p.y, &f.mul(&q.z, &z2z2));
+        let s2: FieldElement256 = f.mul(&q.y, &f.mul(&p.z, &z1z1));
+
+        let h: FieldElement256 = f.sub(&u2, &u1);
+        let r: FieldElement256 = f.sub(&s2, &s1);
+
+        // p = -q gives h = 0 and therefore z = 0 without special treatment
+        let i: FieldElement256 = f.square(&f.add(&h, &h));
+        let j: FieldElement256 = f.mul(&h, &i);
+        let r_2: FieldElement256 = f.add(&r, &r);
+        let v: FieldElement256 = f.mul(&u1, &i);
+
+        let x: FieldElement256 = f.sub(&f.sub(&f.square(&r_2), &j), &f.add(&v, &v));
+        let s1_j: FieldElement256 = f.mul(&s1, &j);
+        let y: FieldElement256 = f.sub(&f.mul(&r_2, &f.sub(&v, &x)), &f.add(&s1_j, &s1_j));
+        let z: FieldElement256 = f.mul(&f.sub(&f.sub(&f.square(&f.add(&p.z, &q.z)), &z1z1), &z2z2), &h);
+
+        let mut output: JacobianPoint = JacobianPoint { x, y, z };
+        output = select_point(zero_mask(&h) & zero_mask(&r), &self.double(p), &output);
+        output = select_point(zero_mask(&p.z), q, &output);
+        output = select_point(zero_mask(&q.z), p, &output);
+
+        output
+    }
+
+    pub fn negate(&self, point: &JacobianPoint) -> JacobianPoint {
+        JacobianPoint {
+            x: point.x,
+            y: self.field.neg(&point.y),
+            z: point.z,
+        }
+    }
+
+    pub fn scalarmult(&self, point: &JacobianPoint, scalar: &[u8; 32]) -> JacobianPoint {
+        // Montgomery ladder over k + n or k + 2n, whichever has bit 256 set. Both give the
+        // same point for the prime order curves here, and every scalar takes 256 steps.
+        let k: [u64; 4] = self.fixed_length_scalar(scalar);
+
+        // the implicit top bit is handled by the start values, r1 - r0 = point holds throughout
+        let mut r0: JacobianPoint = *point;
+        let mut r1: JacobianPoint = self.double(point);
+
+        for i in (0..256).rev() {
+            let bit: u64 = (k[i >> 6] >> (i & 63)) & 1;
+
+            swap_points(&mut r0, &mut r1, bit);
+            r1 = self.add(&r0, &r1);
+            r0 = self.double(&r0);
+            swap_points(&mut r0, &mut r1, bit);
+        }
+
+        r0
+    }
+
+    fn fixed_length_scalar(&self, scalar: &[u8; 32]) -> [u64; 4] {
+        // lower 256 bits of k + n if it reaches 2^256, otherwise of k + 2n
+        let n: &FieldElement256 = &self.scalar.modulus;
+        let k: FieldElement256 = unpack256(scalar);
+
+        let mut k_n: [u64; 4] = [0; 4];
+        let mut carry: u128 = 0;
+        for i in 0..4 {
+            let value: u128 = k[i] as u128 + n[i] as u128 + carry;
+            k_n[i] = value as u64;
+            carry = value >> 64;
+        }
+        let mask: u64 = (carry as u64).wrapping_neg();
+
+        let mut k_2n: [u64; 4] = [0; 4];
+        let mut carry: u128 = 0;
+        for i in 0..4 {
+            let value: u128 = k_n[i] as u128 + n[i] as u128 + carry;
+            k_2n[i] = value as u64;
+            carry = value >> 64;
+        }
+
+        core::array::from_fn(|i| (k_n[i] & mask) | (k_2n[i] & !mask))
+    }
+
+    pub fn lift_x(&self, x: &FieldElement256, odd: bool) -> Result<Point<FieldElement256>, EcError> {
+        // the point with the given x-coordinate and parity of y
+        if !less_than256(x, &self.field.modulus) {
+            return Err(EcError::NonCanonicalEncoding);
+        }
+
+        let x_montgomery: FieldElement256 = self.field.montgomery_form(x);
+        let mut y: FieldElement256 = match self.field.sqrt(&self.right_hand_side(&x_montgomery)) {
+            Some(y) => self.field.canonical_form(&y),
+            None => return Err(EcError::InvalidPoint),
+        };
+
+        if (y[0] & 1 == 1) != odd {
+            y = self.field.canonical_form(&self.field.neg(&self.field.montgomery_form(&y)));
+        }
+
+        Ok(Point { x: *x, y })
+    }
+
+    pub fn encode_point(&self, point: &Point<FieldElement256>, compressed: bool) -> Vec<u8> {
+        // SEC 1 section 2.3.3
+        if compressed {
+            [&[0x02 | (point.y[0] & 1) as u8], pack256(&point.x).as_slice()].concat()
+        } else {
+            [&[0x04], pack256(&point.x).as_slice(), &pack256(&point.y)].concat()
+        }
+    }
+
+    pub fn decode_point(&self, input: &[u8]) -> Result<Point<FieldElement256>, EcError> {
+        // SEC 1 section 2.3.4, the point at infinity is rejected
+        match (input.len(), input.first()) {
+            (33, Some(0x02)) | (33, Some(0x03)) => {
+                self.lift_x(&unpack256(input[1..33].try_into().unwrap()), input[0] == 0x03)
+            },
+            (65, Some(0x04)) => {
+                let point: Point<FieldElement256> = Point {
+                    x: unpack256(input[1..33].try_into().unwrap()),
+                    y: unpack256(input[33..65].try_into().unwrap()),
+                };
+
+                if !less_than256(&point.x, &self.field.modulus) || !less_than256(&point.y, &self.field.modulus) {
+                    return Err(EcError::NonCanonicalEncoding);
+                }
+                if !self.point_on_curve(&point) {
+                    return Err(EcError::InvalidPoint);
+                }
+
+                Ok(point)
+            },
+            _ => Err(EcError::InvalidPoint),
+        }
+    }
+
+    pub fn is_valid_scalar(&self, scalar: &[u8; 32]) -> bool {
+        // 1 <= scalar < n
+        let value: FieldElement256 = unpack256(scalar);
+        value != [0; 4] && less_than256(&value, &self.scalar.modulus)
+    }
+
+    pub fn hash_to_scalar(&self, message_hash: &[u8]) -> FieldElement256 {
+        // SEC 1 section 4.1.3 step 5: the leftmost 256 bits of the hash, reduced
+        // modulo n, to Montgomery form of the scalar field.
+        let mut bytes: [u8; 32] = [0; 32];
+        if message_hash.len() >= 32 {
+            bytes.copy_from_slice(&message_hash[..32]);
+        } else {
+            bytes[32 - message_hash.len()..].copy_from_slice(message_hash);
+        }
+
+        self.scalar.unpack(&bytes)
+    }
+
+    pub fn ecdsa_sign(&self, secret_key: &[u8; 32], message_hash: &[u8], nonce: &[u8; 32]) -> Option<([u8; 64], u8)> {
+        // SEC 1 section 4.1.3, None if the nonce has to be replaced. The recovery id
+        // holds the parity of R.y in bit 0 and whether R.x overflowed n in bit 1.
+        if !self.is_valid_scalar(nonce) {
+            return None;
+        }
+
+        let big_r: Point<FieldElement256> = self.to_affine(&self.scalarmult(&self.generator(), nonce))?;
+
+        let r: FieldElement256 = self.scalar.montgomery_form(&big_r.x);
+        if self.scalar.is_zero(&r) {
+            return None;
+        }
+
+        let z: FieldElement256 = self.hash_to_scalar(message_hash);
+        let d: FieldElement256 = self.scalar.unpack(secret_key);
+        let k: FieldElement256 = self.scalar.unpack(nonce);
+
+        // s = k^-1 * (z + r * d)
+        let s: FieldElement256 = self.scalar.mul(&self.scalar.inverse(&k), &self.scalar.add(&z, &self.scalar.mul(&r, &d)));
+        if self.scalar.is_zero(&s) {
+            return None;
+        }
+
+        let mut signature: [u8; 64] = [0; 64];
+        signature[..32].copy_from_slice(&self.scalar.pack(&r));
+        signature[32..].copy_from_slice(&self.scalar.pack(&s));
+
+        let overflow: u8 = !less_than256(&big_r.x, &self.scalar.modulus) as u8;
+        let recovery_id: u8 = (big_r.y[0] & 1) as u8 | (overflow << 1);
+
+        Some((signature, recovery_id))
+    }
+
+    pub fn ecdsa_sign_deterministic(&self, hash_type: HashType, secret_key: &[u8; 32], message_hash: &[u8]) -> ([u8; 64], u8) {
+        // nonces from RFC 6979, the next candidate is taken if one is rejected
+        let mut rfc6979: RFC6979 = RFC6979::new(hash_type, secret_key, message_hash, &self.curve.n);
+
+        loop {
+            if let Some(signature) = self.ecdsa_sign(secret_key, message_hash, &rfc6979.next_nonce()) {
+                return signature;
+            }
+        }
+    }
+
+    pub fn ecdsa_verify(&self, public_key: &Point<FieldElement256>, message_hash: &[u8], signature: &[u8; 64]) -> Result<(), EcError> {
+        // SEC 1 section 4.1.4
+        let r_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+        let s_bytes: [u8; 32] = signature[32..].try_into().unwrap();
+
+        if !self.is_valid_scalar(&r_bytes) || !self.is_valid_scalar(&s_bytes) {
+            return Err(EcError::InvalidSignature);
+        }
+
+        let z: FieldElement256 = self.hash_to_scalar(message_hash);
+        let r: FieldElement256 = self.scalar.unpack(&r_bytes);
+        let w: FieldElement256 = self.scalar.inverse(&self.scalar.unpack(&s_bytes));
+
+        let u1: [u8; 32] = self.scalar.pack(&self.scalar.mul(&z, &w));
+        let u2: [u8; 32] = self.scalar.pack(&self.scalar.mul(&r, &w));
+
+        let big_r: JacobianPoint = self.add(
+            &self.scalarmult(&self.generator(), &u1),
+            &self.scalarmult(&self.jacobian(public_key), &u2),
+        );
+
+        match self.to_affine(&big_r) {
+            Some(big_r) if self.scalar.montgomery_form(&big_r.x) == r => Ok(()),
+            _ => Err(EcError::InvalidSignature),
+        }
+    }
+
+    pub fn ecdsa_recover(&self, message_hash: &[u8], signature: &[u8; 64], recovery_id: u8) -> Result<Point<FieldElement256>, EcError> {
+        // SEC 1 section 4.1.6 with the candidate R chosen by the recovery id
+        let r_bytes: [u8; 32] = signature[..32].try_into().unwrap();
+        let s_bytes: [u8; 32] = signature[32..].try_into().unwrap();
+
+        if recovery_id > 3 || !self.is_valid_scalar(&r_bytes) || !self.is_valid_scalar(&s_bytes) {
+            return Err(EcError::InvalidSignature);
+        }
+
+        let mut x: FieldElement256 = unpack256(&r_bytes);
+        if recovery_id & 2 == 2 {
+            // x = r + n, which has to stay below p
+            let mut carry: u128 = 0;
+            for (x_i, n_i) in x.iter_mut().zip(self.scalar.modulus.iter()) {
+                let value: u128 = *x_i as u128 + *n_i as u128 + carry;
+                *x_i = value as u64;
+                carry = value >> 64;
+            }
+            if carry != 0 {
+                return Err(EcError::InvalidSignature);
+            }
+        }
+
+        let big_r: Point<FieldElement256> = self.lift_x(&x, recovery_id & 1 == 1).map_err(|_| EcError::InvalidSignature)?;
+
+        // Q = r^-1 * (s * R - z * G)
+        let z: FieldElement256 = self.hash_to_scalar(message_hash);
+        let r_inverse: FieldElement256 = self.scalar.inverse(&self.scalar.unpack(&r_bytes));
+
+        let u1: [u8; 32] = self.scalar.pack(&self.scalar.neg(&self.scalar.mul(&z, &r_inverse)));
+        let u2: [u8; 32] = self.scalar.pack(&self.scalar.mul(&self.scalar.unpack(&s_bytes), &r_inverse));
+
+        let public_key: JacobianPoint = self.add(
+            &self.scalarmult(&self.generator(), &u1),
+            &self.scalarmult(&self.jacobian(&big_r), &u2),
+        );
+
+        self.to_affine(&public_key).ok_or(EcError::InvalidSignature)
+    }
+}
+
+fn zero_mask(x: &FieldElement256) -> u64 {
+    // all ones if x is zero, without a branch on the value
+    let value: u64 = x[0] | x[1] | x[2] | x[3];
+    ((value | value.wrapping_neg()) >> 63).wrapping_sub(1)
+}
+
+fn select_point(mask: u64, p: &JacobianPoint, q: &JacobianPoint) -> JacobianPoint {
+    // p where the mask is set, q otherwise
+    let select = |a: &FieldElement256, b: &FieldElement256| -> FieldElement256 {
+        core::array::from_fn(|i| (a[i] & mask) | (b[i] & !mask))
+    };
+
+    JacobianPoint {
+        x: select(&p.x, &q.x),
+        y: select(&p.y, &q.y),
+        z: select(&p.z, &q.z),
+    }
+}
+
+fn swap_points(p: &mut JacobianPoint, q: &mut JacobianPoint, bit: u64) {
+    let mask: u64 = bit.wrapping_neg();
+
+    for (p_coordinate, q_coordinate) in [(&mut p.x, &mut q.x), (&mut p.y, &mut q.y), (&mut p.z, &mut q.z)] {
+        for i in 0..4 {
+            let t: u64 = mask & (p_coordinate[i] ^ q_coordinate[i]);
+            p_coordinate[i] ^= t;
+            q_coordinate[i] ^= t;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{JacobianPoint, Weierstrass};
+    use super::super::Curve;
+    use super::super::field256::{FieldElement256, pack256, sub256};
+
+    fn check_exceptional_cases(weierstrass: &Weierstrass) {
+        let g: JacobianPoint = weierstrass.generator();
+        let affine = |point: &JacobianPoint| -> Option<Vec<u8>> { weierstrass.to_affine(point).map(|x| weierstrass.encode_point(&x, false)) };
+        let n: FieldElement256 = weierstrass.scalar.modulus;
+
+        // additions with infinity, p = q and p = -q
+        assert_eq!(affine(&weierstrass.add(&weierstrass.infinity(), &g)), affine(&g));
+        assert_eq!(affine(&weierstrass.add(&g, &weierstrass.infinity())), affine(&g));
+        assert_eq!(affine(&weierstrass.add(&g, &g)), affine(&weierstrass.double(&g)));
+        assert!(weierstrass.is_infinity(&weierstrass.add(&g, &weierstrass.negate(&g))));
+        assert!(weierstrass.is_infinity(&weierstrass.add(&weierstrass.infinity(), &weierstrass.infinity())));
+
+        // small scalars use k + 2n, large ones k + n
+        let mut one: [u8; 32] = [0; 32];
+        one[31] = 1;
+        let mut three: [u8; 32] = [0; 32];
+        three[31] = 3;
+        assert!(weierstrass.is_infinity(&weierstrass.scalarmult(&g, &[0; 32])));
+        assert_eq!(affine(&weierstrass.scalarmult(&g, &one)), affine(&g));
+        assert_eq!(affine(&weierstrass.scalarmult(&g, &three)), affine(&weierstrass.add(&weierstrass.double(&g), &g)));
+        assert_eq!(affine(&weierstrass.scalarmult(&g, &pack256(&sub256(&n, &[1, 0, 0, 0]).0))), affine(&weierstrass.negate(&g)));
+        assert!(weierstrass.is_infinity(&weierstrass.scalarmult(&g, &pack256(&n))));
+        assert!(weierstrass.is_infinity(&weierstrass.scalarmult(&weierstrass.infinity(), &three)));
+    }
+
+    #[test]
+    fn exceptional_cases() {
+        check_exceptional_cases(&Weierstrass::new(Curve::p256()));
+        check_exceptional_cases(&Weierstrass::new(Curve::secp256k1()));
+    }
+}
@@ -5,8 +5,30 @@ Implementation according to https://martin.kleppmann.com/papers/curve25519.pdf
 
 #![allow(dead_code)]
 
+use super::EcError;
+use super::field51::{FieldElement51, unpack51, pack51, fadd51, fsub51, fmul51, fsquare51, fmul51_small, finverse51, swap51};
+
 pub type FieldElement = [i64; 16];
 
+// sqrt(-1) = 2^((p - 1) / 4) in 16-bit limbs
+pub const SQRT_M1: FieldElement = [0xa0b0, 0x4a0e, 0x1b27, 0xc4ee, 0xe478, 0xad2f, 0x1806, 0x2f43, 0xd7a7, 0x3dfb, 0x0099, 0x2b4d, 0xdf0b, 0x4fc1, 0x2480, 0x2b83];
+
+const ONE: [u8; 32] = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+// (p - 1) / 2 and (p + 3) / 8, little-endian
+const P_MINUS_1_DIV_2: [u8; 32] = [0xf6, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x3f];
+const P_PLUS_3_DIV_8: [u8; 32] = [0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f];
+
+// u-coordinates of the points of order 1, 2, 4 and 8 on Curve25519 (RFC 7748 section 6.1),
+// the non-canonical encodings p, p + 1 are rejected before this list is consulted.
+const LOW_ORDER_POINTS: [[u8; 32]; 5] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0xe0, 0xeb, 0x7a, 0x7c, 0x3b, 0x41, 0xb8, 0xae, 0x16, 0x56, 0xe3, 0xfa, 0xf1, 0x9f, 0xc4, 0x6a, 0xda, 0x09, 0x8d, 0xeb, 0x9c, 0x32, 0xb1, 0xfd, 0x86, 0x62, 0x05, 0x16, 0x5f, 0x49, 0xb8, 0x00],
+    [0x5f, 0x9c, 0x95, 0xbc, 0xa3, 0x50, 0x8c, 0x24, 0xb1, 0xd0, 0xb1, 0x55, 0x9c, 0x83, 0xef, 0x5b, 0x04, 0x44, 0x5c, 0xc4, 0x58, 0x1c, 0x8e, 0x86, 0xd8, 0x22, 0x4e, 0xdd, 0xd0, 0x9f, 0x11, 0x57],
+    [0xec, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+];
+
 fn carry25519(input: &mut FieldElement) {
     for i in 0..16 {
         let carry: i64 = input[i] >> 16;
@@ -105,7 +127,7 @@ pub fn fmul(x: &FieldElement, y: &FieldElement) -> FieldElement {
     output
 }
 
-fn finverse(input: &FieldElement) -> FieldElement {
+pub fn finverse(input: &FieldElement) -> FieldElement {
     let mut c: FieldElement = *input;
 
     for i in (0..=253).rev() {
@@ -119,7 +141,7 @@ fn finverse(input: &FieldElement) -> FieldElement {
     c
 }
 
-fn swap25519(p: &mut FieldElement, q: &mut FieldElement, bit: i64) {
+pub fn swap25519(p: &mut FieldElement, q: &mut FieldElement, bit: i64) {
     let c: i64 = !(bit - 1);
     for i in 0..16 {
         let t = c & (p[i] ^ q[i]);
@@ -128,54 +150,106 @@ fn swap25519(p: &mut FieldElement, q: &mut FieldElement, bit: i64) {
     }
 }
 
+pub fn fsquare(x: &FieldElement) -> FieldElement {
+    fmul(x, x)
+}
+
+pub fn fneg(x: &FieldElement) -> FieldElement {
+    fsub(&[0; 16], x)
+}
+
+pub fn reduce25519(x: &FieldElement) -> FieldElement {
+    // the unique representative in [0, p) with every limb in [0, 2^16)
+    unpack25519(&pack25519(&mut x.clone()))
+}
+
+pub fn fequal(x: &FieldElement, y: &FieldElement) -> bool {
+    // compares the canonical encodings in constant time, limbs of equal
+    // elements may differ before the reduction
+    let difference: u8 = pack25519(&mut x.clone()).iter()
+        .zip(pack25519(&mut y.clone()).iter())
+        .fold(0, |acc, (a, b)| acc | (a ^ b));
+
+    ((difference as u32).wrapping_sub(1) >> 31) == 1
+}
+
+pub fn fselect(x: &FieldElement, y: &FieldElement, bit: i64) -> FieldElement {
+    // x if bit is 0 and y if bit is 1, in constant time
+    let mut output: FieldElement = *x;
+    let mut other: FieldElement = *y;
+    swap25519(&mut output, &mut other, bit);
+
+    output
+}
+
+pub fn fcneg(x: &FieldElement, bit: i64) -> FieldElement {
+    fselect(x, &fneg(x), bit)
+}
+
+pub fn is_zero(x: &FieldElement) -> bool {
+    fequal(x, &[0; 16])
+}
+
+pub fn is_negative(x: &FieldElement) -> bool {
+    // RFC 8032 and RFC 9496 call the odd elements negative
+    pack25519(&mut x.clone())[0] & 1 == 1
+}
+
+pub fn fpow(x: &FieldElement, exponent: &[u8; 32]) -> FieldElement {
+    // square and multiply from the most significant bit, the exponent is public
+    let mut output: FieldElement = [0; 16];
+    output[0] = 1;
+
+    for i in (0..256).rev() {
+        output = fsquare(&output);
+
+        if (exponent[i >> 3] >> (i & 7)) & 1 == 1 {
+            output = fmul(&output, x);
+        }
+    }
+
+    output
+}
+
+pub fn legendre(x: &FieldElement) -> i8 {
+    // Euler's criterion x^((p - 1) / 2) is 1 for squares, p - 1 for non-squares and 0 for 0
+    let symbol: FieldElement = fpow(x, &P_MINUS_1_DIV_2);
+
+    if is_zero(&symbol) {
+        0
+    } else if is_zero(&fsub(&symbol, &unpack25519(&ONE))) {
+        1
+    } else {
+        -1
+    }
+}
+
+pub fn is_square(x: &FieldElement) -> bool {
+    legendre(x) != -1
+}
+
+pub fn fsqrt(x: &FieldElement) -> Option<FieldElement> {
+    // Atkin's method for p = 5 mod 8: r = x^((p + 3) / 8) is a root of x or of -x,
+    // in the latter case sqrt(-1) * r is a root of x. Returns the even root.
+    let mut root: FieldElement = fpow(x, &P_PLUS_3_DIV_8);
+
+    let correct_sign: bool = fequal(&fsquare(&root), x);
+    let flipped_sign: bool = fequal(&fsquare(&root), &fneg(x));
+    root = fselect(&root, &fmul(&root, &SQRT_M1), flipped_sign as i64);
+
+    if !correct_sign && !flipped_sign {
+        return None;
+    }
+
+    Some(reduce25519(&fcneg(&root, is_negative(&root) as i64)))
+}
+
 pub fn scalarmult(point: &[u8; 32], scalar: &[u8; 32]) -> [u8; 32] {
     let mut clamped: [u8; 32] = *scalar;
     clamped[0] &= 0xf8;
     clamped[31] = (clamped[31] & 0x7f) | 0x40;
 
-    let mut a:FieldElement = [0; 16];
-    let mut b:FieldElement = [0; 16];
-    let mut c:FieldElement = [0; 16];
-    let mut d:FieldElement = [0; 16];
-    let mut e:FieldElement;
-    let mut f:FieldElement;
-
-    let x: FieldElement = unpack25519(point);
-    for i in 0..16 {
-        b[i] = x[i];
-        (d[i], a[i], c[i]) = (0, 0, 0);
-    }
-    (a[0], d[0]) = (1, 1);
-    let mut constant: FieldElement = [0; 16];
-    constant[0] = 121665;
-    for i in (0..=254).rev() {
-        let bit = ((clamped[i >> 3] >> (i & 7)) & 1) as i64;
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
-        e = fadd(&a, &c);
-        a = fsub(&a, &c);
-        c = fadd(&b, &d);
-        b = fsub(&b, &d);
-        d = fmul(&e, &e);
-        f = fmul(&a, &a);
-        a = fmul(&c, &a);
-        c = fmul(&b, &e);
-        e = fadd(&a, &c);
-        a = fsub(&a, &c);
-        b = fmul(&a, &a);
-        c = fsub(&d, &f);
-        a = fmul(&c, &constant);
-        a = fadd(&a, &d);
-        c = fmul(&c, &a);
-        a = fmul(&d, &f);
-        d = fmul(&b, &x);
-        b = fmul(&e, &e);
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
-    }
-    c = finverse(&c);
-    a = fmul(&a, &c);
-    pack25519(&mut a)
+    montgomery_ladder(point, &clamped)
 }
 
 pub fn scalarmult_64bytes(point: &[u8; 32], scalar: &[u8; 64]) -> [u8; 32] {
@@ -183,54 +257,103 @@ pub fn scalarmult_64bytes(point: &[u8; 32], scalar: &[u8; 64]) -> [u8; 32] {
     clamped[0] &= 0xf8;
     clamped[63] = (clamped[63] & 0x7f) | 0x40;
 
-    let mut a: FieldElement = [0; 16];
-    let mut b: FieldElement = [0; 16];
-    let mut c: FieldElement = [0; 16];
-    let mut d: FieldElement = [0; 16];
-    let mut e: FieldElement;
-    let mut f: FieldElement;
+    montgomery_ladder(point, &clamped)
+}
+
+fn montgomery_ladder(point: &[u8; 32], scalar: &[u8]) -> [u8; 32] {
+    // Ladder over bits 254 to 0 of the scalar in the 51-bit limb representation,
+    // same steps as TweetNaCl's crypto_scalarmult.
+    let x: FieldElement51 = unpack51(point);
+
+    let mut a: FieldElement51 = [1, 0, 0, 0, 0];
+    let mut b: FieldElement51 = x;
+    let mut c: FieldElement51 = [0; 5];
+    let mut d: FieldElement51 = [1, 0, 0, 0, 0];
+    let mut e: FieldElement51;
+    let mut f: FieldElement51;
 
-    let x: FieldElement = unpack25519(point);
-    for i in 0..16 {
-        b[i] = x[i];
-        (d[i], a[i], c[i]) = (0, 0, 0);
-    }
-    (a[0], d[0]) = (1, 1);
-    let mut constant: FieldElement = [0; 16];
-    constant[0] = 121665;
     for i in (0..=254).rev() {
-        let bit = ((clamped[i >> 3] >> (i & 7)) & 1) as i64;
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
-        e = fadd(&a, &c);
-        a = fsub(&a, &c);
-        c = fadd(&b, &d);
-        b = fsub(&b, &d);
-        d = fmul(&e, &e);
-        f = fmul(&a, &a);
-        a = fmul(&c, &a);
-        c = fmul(&b, &e);
-        e = fadd(&a, &c);
-        a = fsub(&a, &c);
-        b = fmul(&a, &a);
-        c = fsub(&d, &f);
-        a = fmul(&c, &constant);
-        a = fadd(&a, &d);
-        c = fmul(&c, &a);
-        a = fmul(&d, &f);
-        d = fmul(&b, &x);
-        b = fmul(&e, &e);
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
+        let bit = ((scalar[i >> 3] >> (i & 7)) & 1) as u64;
+        swap51(&mut a, &mut b, bit);
+        swap51(&mut c, &mut d, bit);
+        e = fadd51(&a, &c);
+        a = fsub51(&a, &c);
+        c = fadd51(&b, &d);
+        b = fsub51(&b, &d);
+        d = fsquare51(&e);
+        f = fsquare51(&a);
+        a = fmul51(&c, &a);
+        c = fmul51(&b, &e);
+        e = fadd51(&a, &c);
+        a = fsub51(&a, &c);
+        b = fsquare51(&a);
+        c = fsub51(&d, &f);
+        a = fmul51_small(&c, 121665);
+        a = fadd51(&a, &d);
+        c = fmul51(&c, &a);
+        a = fmul51(&d, &f);
+        d = fmul51(&b, &x);
+        b = fsquare51(&e);
+        swap51(&mut a, &mut b, bit);
+        swap51(&mut c, &mut d, bit);
     }
-    c = finverse(&c);
-    a = fmul(&a, &c);
-    pack25519(&mut a)
+
+    pack51(&fmul51(&a, &finverse51(&c)))
+}
+
+pub fn is_canonical(point: &[u8; 32]) -> bool {
+    // The top bit is masked by unpack25519, so it has to be clear, and the
+    // remaining 255 bits must encode a value below p = 2^255 - 19.
+    if point[31] & 0x80 != 0 {
+        return false;
+    }
+
+    let upper_bits_set: bool = point[31] == 0x7f && point[1..31].iter().all(|b| *b == 0xff);
+
+    !(upper_bits_set && point[0] >= 0xed)
+}
+
+pub fn is_low_order(point: &[u8; 32]) -> bool {
+    let mut found: u8 = 0;
+
+    for low_order_point in LOW_ORDER_POINTS.iter() {
+        let difference: u8 = point.iter().zip(low_order_point.iter()).fold(0, |acc, (a, b)| acc | (a ^ b));
+        found |= (difference == 0) as u8;
+    }
+
+    found == 1
+}
+
+pub fn scalarmult_checked(point: &[u8; 32], scalar: &[u8; 32]) -> Result<[u8; 32], EcError> {
+    if !is_canonical(point) {
+        return Err(EcError::NonCanonicalEncoding);
+    }
+    if is_low_order(point) {
+        return Err(EcError::LowOrderPoint);
+    }
+
+    let output: [u8; 32] = scalarmult(point, scalar);
+
+    // RFC 7748 section 6.1: check for the all-zero value.
+    if output.iter().fold(0, |acc, b| acc | b) == 0 {
+        return Err(EcError::ZeroSharedSecret);
+    }
+
+    Ok(output)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::crypto::ec::scalarmult;
+    use crate::crypto::ec::{scalarmult, scalarmult_checked, EcError};
+    use super::{FieldElement, LOW_ORDER_POINTS, SQRT_M1, pack25519, unpack25519, fadd, fsub, fmul, fsquare, fneg, reduce25519};
+    use super::{fequal, fselect, fcneg, is_zero, is_negative, legendre, is_square, fsqrt};
+    use crate::utils::rng;
+
+    fn random_element() -> FieldElement {
+        let mut bytes: [u8; 32] = [0; 32];
+        rng::fill_bytes(&mut bytes);
+        unpack25519(&bytes)
+    }
 
     fn input_scalar_test(input_u_coordinate: [u8; 32], input_scalar: [u8; 32], output_u_coordinate: [u8; 32]) {
         assert_eq!(output_u_coordinate, scalarmult(&input_u_coordinate, &input_scalar));
@@ -256,5 +379,117 @@ mod tests {
         input_scalar_test(input_u_coordinate_2, input_scalar_2, output_u_coordinate_2);
     }
 
+    #[test]
+    fn low_order_points() {
+        let scalar: [u8; 32] = [0xa5, 0x46, 0xe3, 0x6b, 0xf0, 0x52, 0x7c, 0x9d, 0x3b, 0x16, 0x15, 0x4b, 0x82, 0x46, 0x5e, 0xdd, 0x62, 0x14, 0x4c, 0x0a, 0xc1, 0xfc, 0x5a, 0x18, 0x50, 0x6a, 0x22, 0x44, 0xba, 0x44, 0x9a, 0xc4];
+
+        for point in LOW_ORDER_POINTS.iter() {
+            // Unchecked, a clamped scalar (a multiple of 8) maps every point to zero.
+            assert_eq!(scalarmult(point, &scalar), [0; 32]);
+            assert_eq!(scalarmult_checked(point, &scalar), Err(EcError::LowOrderPoint));
+        }
+    }
+
+    #[test]
+    fn non_canonical_points() {
+        let scalar: [u8; 32] = [0x4b, 0x66, 0xe9, 0xd4, 0xd1, 0xb4, 0x67, 0x3c, 0x5a, 0xd2, 0x26, 0x91, 0x95, 0x7d, 0x6a, 0xf5, 0xc1, 0x1b, 0x64, 0x21, 0xe0, 0xea, 0x01, 0xd4, 0x2c, 0xa4, 0x16, 0x9e, 0x79, 0x18, 0xba, 0x0d];
+
+        // p, p + 1 and p + 2 = 2^255 - 17
+        for first_byte in [0xed, 0xee, 0xef] {
+            let mut point: [u8; 32] = [0xff; 32];
+            point[0] = first_byte;
+            point[31] = 0x7f;
+
+            assert_eq!(scalarmult_checked(&point, &scalar), Err(EcError::NonCanonicalEncoding));
+        }
+
+        // Setting the masked top bit of a valid u-coordinate
+        let mut point: [u8; 32] = [0; 32];
+        point[0] = 9;
+        point[31] = 0x80;
+
+        assert_eq!(scalarmult_checked(&point, &scalar), Err(EcError::NonCanonicalEncoding));
+    }
+
+    #[test]
+    fn checked_test_vectors() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7748#section-5.2
+        let input_scalar: [u8; 32] = [0xa5, 0x46, 0xe3, 0x6b, 0xf0, 0x52, 0x7c, 0x9d, 0x3b, 0x16, 0x15, 0x4b, 0x82, 0x46, 0x5e, 0xdd, 0x62, 0x14, 0x4c, 0x0a, 0xc1, 0xfc, 0x5a, 0x18, 0x50, 0x6a, 0x22, 0x44, 0xba, 0x44, 0x9a, 0xc4];
+        let input_u_coordinate: [u8; 32] = [0xe6, 0xdb, 0x68, 0x67, 0x58, 0x30, 0x30, 0xdb, 0x35, 0x94, 0xc1, 0xa4, 0x24, 0xb1, 0x5f, 0x7c, 0x72, 0x66, 0x24, 0xec, 0x26, 0xb3, 0x35, 0x3b, 0x10, 0xa9, 0x03, 0xa6, 0xd0, 0xab, 0x1c, 0x4c];
+        let output_u_coordinate: [u8; 32] = [0xc3, 0xda, 0x55, 0x37, 0x9d, 0xe9, 0xc6, 0x90, 0x8e, 0x94, 0xea, 0x4d, 0xf2, 0x8d, 0x08, 0x4f, 0x32, 0xec, 0xcf, 0x03, 0x49, 0x1c, 0x71, 0xf7, 0x54, 0xb4, 0x07, 0x55, 0x77, 0xa2, 0x85, 0x52];
+
+        assert_eq!(scalarmult_checked(&input_u_coordinate, &input_scalar), Ok(output_u_coordinate));
+    }
 
+    #[test]
+    fn canonical_reduction_and_equality() {
+        let mut one: FieldElement = [0; 16];
+        one[0] = 1;
+
+        // p + 1 has non-canonical limbs
+        let mut p_plus_1: [u8; 32] = [0xff; 32];
+        p_plus_1[0] = 0xee;
+        p_plus_1[31] = 0x7f;
+        let unreduced: FieldElement = unpack25519(&p_plus_1);
+
+        assert_ne!(unreduced, one);
+        assert!(fequal(&unreduced, &one));
+        assert_eq!(reduce25519(&unreduced), one);
+        assert!(is_zero(&fsub(&unreduced, &one)));
+
+        for _ in 0..50 {
+            let x: FieldElement = random_element();
+            let y: FieldElement = random_element();
+
+            let reduced: FieldElement = reduce25519(&fadd(&fmul(&x, &y), &x));
+            assert!(reduced.iter().all(|limb| (0..0x10000).contains(limb)));
+            assert_eq!(pack25519(&mut reduced.clone()), pack25519(&mut fadd(&fmul(&x, &y), &x)));
+
+            assert!(fequal(&fadd(&x, &y), &fadd(&y, &x)));
+            assert!(fequal(&fsub(&fadd(&x, &y), &y), &x));
+            assert!(!fequal(&fadd(&x, &one), &x));
+
+            assert_eq!(fselect(&x, &y, 0), x);
+            assert_eq!(fselect(&x, &y, 1), y);
+            assert_eq!(fcneg(&x, 0), x);
+            assert!(fequal(&fcneg(&x, 1), &fneg(&x)));
+
+            // exactly one of x and -x is negative for x != 0
+            assert!(is_negative(&x) != is_negative(&fneg(&x)));
+        }
+    }
+
+    #[test]
+    fn square_roots() {
+        let mut one: FieldElement = [0; 16];
+        one[0] = 1;
+        let mut two: FieldElement = [0; 16];
+        two[0] = 2;
+
+        assert!(fequal(&fsquare(&SQRT_M1), &fneg(&one)));
+        assert_eq!(legendre(&[0; 16]), 0);
+        assert_eq!(fsqrt(&[0; 16]), Some([0; 16]));
+
+        // 2 is not a square modulo p = 5 mod 8
+        assert_eq!(legendre(&two), -1);
+        assert_eq!(fsqrt(&two), None);
+
+        for _ in 0..20 {
+            let x: FieldElement = random_element();
+            let square: FieldElement = fsquare(&x);
+
+            assert_eq!(legendre(&square), 1);
+            assert!(is_square(&square));
+
+            let root: FieldElement = fsqrt(&square).unwrap();
+            assert!(fequal(&root, &x) || fequal(&root, &fneg(&x)));
+            assert!(!is_negative(&root));
+
+            // a square times a non-square is a non-square
+            assert!(!is_square(&fmul(&square, &two)));
+            assert_eq!(fsqrt(&fmul(&square, &two)), None);
+
+            assert_eq!(is_square(&x), fsqrt(&x).is_some());
+        }
+    }
 }
@@ -0,0 +1,338 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc7748
+*/
+
+#![allow(dead_code)]
+
+use super::EcError;
+
+// Sixteen 28-bit limbs, 16 * 28 = 448 bits, p = 2^448 - 2^224 - 1.
+pub type FieldElement448 = [i64; 16];
+
+const MASK: i64 = 0xfffffff;
+
+// u-coordinates of the points of order 2 and 4 on Curve448: 0, 1 and p - 1
+const LOW_ORDER_POINTS: [[u8; 56]; 3] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+];
+
+// p in 28-bit limbs, bit 224 (limb 8, bit 0) is the only zero bit.
+const P: FieldElement448 = [
+    MASK, MASK, MASK, MASK, MASK, MASK, MASK, MASK,
+    MASK - 1, MASK, MASK, MASK, MASK, MASK, MASK, MASK,
+];
+
+fn carry448(input: &mut FieldElement448) {
+    for i in 0..16 {
+        let carry: i64 = input[i] >> 28;
+        input[i] -= carry << 28;
+        if i < 15 {
+            input[i + 1] += carry;
+        } else {
+            // 2^448 = 2^224 + 1 (mod p)
+            input[0] += carry;
+            input[8] += carry;
+        }
+    }
+}
+
+pub fn unpack448(input: &[u8; 56]) -> FieldElement448 {
+    let mut output: FieldElement448 = [0; 16];
+    let mut accumulator: i64 = 0;
+    let mut bits: usize = 0;
+    let mut limb: usize = 0;
+
+    for byte in input.iter() {
+        accumulator |= (*byte as i64) << bits;
+        bits += 8;
+
+        if bits >= 28 {
+            output[limb] = accumulator & MASK;
+            accumulator >>= 28;
+            bits -= 28;
+            limb += 1;
+        }
+    }
+
+    output
+}
+
+pub fn pack448(input: &FieldElement448) -> [u8; 56] {
+    let mut t: FieldElement448 = *input;
+    let mut m: FieldElement448 = [0; 16];
+
+    carry448(&mut t);
+    carry448(&mut t);
+    carry448(&mut t);
+
+    // t is now below 2^448 < 2p, subtracting p at most twice reduces it fully.
+    for _ in 0..2 {
+        let mut borrow: i64 = 0;
+        for i in 0..16 {
+            m[i] = t[i] - P[i] - borrow;
+            borrow = (m[i] >> 28) & 1;
+            m[i] &= MASK;
+        }
+
+        swap448(&mut t, &mut m, 1 - borrow);
+    }
+
+    let mut output: [u8; 56] = [0; 56];
+    let mut accumulator: i64 = 0;
+    let mut bits: usize = 0;
+    let mut index: usize = 0;
+
+    for limb in t.iter() {
+        accumulator |= limb << bits;
+        bits += 28;
+
+        while bits >= 8 {
+            output[index] = accumulator as u8;
+            accumulator >>= 8;
+            bits -= 8;
+            index += 1;
+        }
+    }
+
+    output
+}
+
+pub fn fadd448(x: &FieldElement448, y: &FieldElement448) -> FieldElement448 {
+    let mut output: FieldElement448 = [0; 16];
+
+    for i in 0..16 {
+        output[i] = x[i] + y[i];
+    }
+
+    output
+}
+
+pub fn fsub448(x: &FieldElement448, y: &FieldElement448) -> FieldElement448 {
+    let mut output: FieldElement448 = [0; 16];
+
+    for i in 0..16 {
+        output[i] = x[i] - y[i];
+    }
+
+    output
+}
+
+pub fn fmul448(x: &FieldElement448, y: &FieldElement448) -> FieldElement448 {
+    // Limbs of unreduced sums exceed 28 bits, so products are accumulated in i128.
+    let mut product: [i128; 31] = [0; 31];
+
+    for i in 0..16 {
+        for j in 0..16 {
+            product[i + j] += (x[i] as i128) * (y[j] as i128);
+        }
+    }
+
+    // Limb k >= 16 stands for 2^(28k) = 2^(28(k-16)) * (2^224 + 1), folding
+    // from the top also catches the limbs 16..22 written by the first folds.
+    for k in (16..31).rev() {
+        product[k - 16] += product[k];
+        product[k - 8] += product[k];
+    }
+
+    for _ in 0..2 {
+        for i in 0..16 {
+            let carry: i128 = product[i] >> 28;
+            product[i] -= carry << 28;
+            if i < 15 {
+                product[i + 1] += carry;
+            } else {
+                product[0] += carry;
+                product[8] += carry;
+            }
+        }
+    }
+
+    let mut output: FieldElement448 = [0; 16];
+    for i in 0..16 {
+        output[i] = product[i] as i64;
+    }
+
+    carry448(&mut output);
+
+    output
+}
+
+pub fn finverse448(input: &FieldElement448) -> FieldElement448 {
+    // input^(p - 2), where p - 2 = 2^448 - 2^224 - 3 has
+    // all bits set except bit 224 and bit 1.
+    let mut c: FieldElement448 = *input;
+
+    for i in (0..=446).rev() {
+        c = fmul448(&c, &c);
+
+        if i != 224 && i != 1 {
+            c = fmul448(&c, input);
+        }
+    }
+
+    c
+}
+
+pub fn swap448(p: &mut FieldElement448, q: &mut FieldElement448, bit: i64) {
+    let c: i64 = !(bit - 1);
+    for i in 0..16 {
+        let t = c & (p[i] ^ q[i]);
+        p[i] ^= t;
+        q[i] ^= t;
+    }
+}
+
+pub fn scalarmult448(point: &[u8; 56], scalar: &[u8; 56]) -> [u8; 56] {
+    let mut clamped: [u8; 56] = *scalar;
+    clamped[0] &= 0xfc;
+    clamped[55] |= 0x80;
+
+    let mut a: FieldElement448 = [0; 16];
+    let mut b: FieldElement448;
+    let mut c: FieldElement448 = [0; 16];
+    let mut d: FieldElement448 = [0; 16];
+    let mut e: FieldElement448;
+    let mut f: FieldElement448;
+
+    let x: FieldElement448 = unpack448(point);
+    b = x;
+    (a[0], d[0]) = (1, 1);
+
+    // (A - 2) / 4 with A = 156326
+    let mut constant: FieldElement448 = [0; 16];
+    constant[0] = 39081;
+
+    for i in (0..=447).rev() {
+        let bit = ((clamped[i >> 3] >> (i & 7)) & 1) as i64;
+        swap448(&mut a, &mut b, bit);
+        swap448(&mut c, &mut d, bit);
+        e = fadd448(&a, &c);
+        a = fsub448(&a, &c);
+        c = fadd448(&b, &d);
+        b = fsub448(&b, &d);
+        d = fmul448(&e, &e);
+        f = fmul448(&a, &a);
+        a = fmul448(&c, &a);
+        c = fmul448(&b, &e);
+        e = fadd448(&a, &c);
+        a = fsub448(&a, &c);
+        b = fmul448(&a, &a);
+        c = fsub448(&d, &f);
+        a = fmul448(&c, &constant);
+        a = fadd448(&a, &d);
+        c = fmul448(&c, &a);
+        a = fmul448(&d, &f);
+        d = fmul448(&b, &x);
+        b = fmul448(&e, &e);
+        swap448(&mut a, &mut b, bit);
+        swap448(&mut c, &mut d, bit);
+    }
+    c = finverse448(&c);
+    a = fmul448(&a, &c);
+    pack448(&a)
+}
+
+pub fn is_canonical448(point: &[u8; 56]) -> bool {
+    // A canonical encoding survives the reduction done by pack448.
+    pack448(&unpack448(point)) == *point
+}
+
+pub fn scalarmult448_checked(point: &[u8; 56], scalar: &[u8; 56]) -> Result<[u8; 56], EcError> {
+    if !is_canonical448(point) {
+        return Err(EcError::NonCanonicalEncoding);
+    }
+    if LOW_ORDER_POINTS.iter().any(|low_order_point| low_order_point == point) {
+        return Err(EcError::LowOrderPoint);
+    }
+
+    let output: [u8; 56] = scalarmult448(point, scalar);
+
+    // RFC 7748 section 6.2: check for the all-zero value.
+    if output.iter().fold(0, |acc, b| acc | b) == 0 {
+        return Err(EcError::ZeroSharedSecret);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scalarmult448, scalarmult448_checked, pack448, unpack448, fmul448, finverse448, LOW_ORDER_POINTS};
+    use crate::crypto::ec::EcError;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn test_vectors() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7748#section-5.2
+        let input_scalar_1: [u8; 56] = hex("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3").try_into().unwrap();
+        let input_u_coordinate_1: [u8; 56] = hex("06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086").try_into().unwrap();
+        let output_u_coordinate_1: [u8; 56] = hex("ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f").try_into().unwrap();
+
+        let input_scalar_2: [u8; 56] = hex("203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f").try_into().unwrap();
+        let input_u_coordinate_2: [u8; 56] = hex("0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db").try_into().unwrap();
+        let output_u_coordinate_2: [u8; 56] = hex("884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d").try_into().unwrap();
+
+        assert_eq!(scalarmult448(&input_u_coordinate_1, &input_scalar_1), output_u_coordinate_1);
+        assert_eq!(scalarmult448(&input_u_coordinate_2, &input_scalar_2), output_u_coordinate_2);
+    }
+
+    #[test]
+    fn iterated_test_vectors() {
+        // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7748#section-5.2
+        let after_one_iteration: [u8; 56] = hex("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113").try_into().unwrap();
+        let after_1000_iterations: [u8; 56] = hex("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38").try_into().unwrap();
+
+        let mut k: [u8; 56] = [0; 56];
+        k[0] = 5;
+        let mut u: [u8; 56] = k;
+
+        for i in 0..1000 {
+            let result: [u8; 56] = scalarmult448(&u, &k);
+            u = k;
+            k = result;
+
+            if i == 0 {
+                assert_eq!(k, after_one_iteration);
+            }
+        }
+
+        assert_eq!(k, after_1000_iterations);
+    }
+
+    #[test]
+    fn field_arithmetic() {
+        // p itself packs to zero and x * x^-1 packs to one.
+        let mut p: [u8; 56] = [0xff; 56];
+        p[28] = 0xfe;
+
+        assert_eq!(pack448(&unpack448(&p)), [0; 56]);
+
+        let mut x: [u8; 56] = [0; 56];
+        x[0] = 0x07;
+        x[40] = 0x42;
+        let mut one: [u8; 56] = [0; 56];
+        one[0] = 0x01;
+
+        assert_eq!(pack448(&fmul448(&unpack448(&x), &finverse448(&unpack448(&x)))), one);
+    }
+
+    #[test]
+    fn rejected_points() {
+        let scalar: [u8; 56] = hex("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3").try_into().unwrap();
+
+        for point in LOW_ORDER_POINTS.iter() {
+            assert_eq!(scalarmult448(point, &scalar), [0; 56]);
+            assert_eq!(scalarmult448_checked(point, &scalar), Err(EcError::LowOrderPoint));
+        }
+
+        // p + 3 encodes the u-coordinate 3 non-canonically.
+        let mut p_plus_three: [u8; 56] = [0xff; 56];
+        p_plus_three[..28].copy_from_slice(&[0; 28]);
+        p_plus_three[0] = 0x02;
+
+        assert_eq!(scalarmult448_checked(&p_plus_three, &scalar), Err(EcError::NonCanonicalEncoding));
+    }
+}
@@ -0,0 +1,10 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod sealed_box;
+mod types;
+
+pub use sealed_box::SealedBox;
+
+pub use types::EciesError;
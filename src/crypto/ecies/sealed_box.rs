@@ -0,0 +1,125 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://www.secg.org/sec1-v2.pdf (section 5.1) with X25519, HKDF-SHA256 and ChaCha20-Poly1305
+*/
+
+#![allow(dead_code)]
+
+use super::EciesError;
+use crate::crypto::{ChaCha20Poly1305, ECDH};
+use crate::hash::{HKDF, HashType};
+
+// Ephemeral public key followed by the authentication tag.
+pub const SEALED_BOX_OVERHEAD: usize = 32 + 16;
+
+const LABEL: &[u8] = b"ECIES-X25519-HKDF-SHA256-ChaCha20Poly1305";
+
+// Ciphertext format: ephemeral public key || AEAD ciphertext || tag
+pub struct SealedBox;
+
+impl SealedBox {
+    pub fn seal(public_key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, EciesError> {
+        let mut ephemeral: ECDH = ECDH::new();
+        ephemeral.gen_key_pair();
+
+        SealedBox::seal_with_ephemeral(public_key, plaintext, &ephemeral)
+    }
+
+    pub fn open(recipient: &ECDH, ciphertext: &[u8]) -> Result<Vec<u8>, EciesError> {
+        if ciphertext.len() < SEALED_BOX_OVERHEAD {
+            return Err(EciesError::CiphertextTooShort);
+        }
+
+        let (ephemeral_public, ciphertext) = ciphertext.split_at(32);
+        let ephemeral_public: [u8; 32] = ephemeral_public.try_into().unwrap();
+        let shared_secret: [u8; 32] = recipient.checked_symmetric_key(&ephemeral_public).map_err(|_| EciesError::InvalidPublicKey)?;
+        let (key, nonce) = derive_key(&shared_secret, &ephemeral_public, &recipient.pub_key);
+
+        ChaCha20Poly1305::new(&key).decrypt(&nonce, &[], ciphertext).map_err(|_| EciesError::DecryptionFailed)
+    }
+
+    fn seal_with_ephemeral(public_key: &[u8; 32], plaintext: &[u8], ephemeral: &ECDH) -> Result<Vec<u8>, EciesError> {
+        let shared_secret: [u8; 32] = ephemeral.checked_symmetric_key(public_key).map_err(|_| EciesError::InvalidPublicKey)?;
+        let (key, nonce) = derive_key(&shared_secret, &ephemeral.pub_key, public_key);
+
+        let mut output: Vec<u8> = ephemeral.pub_key.to_vec();
+        output.extend_from_slice(&ChaCha20Poly1305::new(&key).encrypt(&nonce, &[], plaintext));
+
+        Ok(output)
+    }
+}
+
+fn derive_key(shared_secret: &[u8; 32], ephemeral_public: &[u8; 32], recipient_public: &[u8; 32]) -> ([u8; 32], [u8; 12]) {
+    // both public keys go into info, so the box is bound to the sender's ephemeral key and the recipient
+    let mut info: Vec<u8> = LABEL.to_vec();
+    info.extend_from_slice(ephemeral_public);
+    info.extend_from_slice(recipient_public);
+
+    // every ephemeral key is used for a single message, so the nonce can be derived as well
+    let output: Vec<u8> = HKDF::new(HashType::Sha256Type).derive(&[], shared_secret, &info, 44).unwrap();
+
+    (output[..32].try_into().unwrap(), output[32..].try_into().unwrap())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{SealedBox, SEALED_BOX_OVERHEAD};
+    use super::super::EciesError;
+    use crate::crypto::ECDH;
+    use crate::utils::testing::hex;
+
+    fn key_pair(secret_key: &str) -> ECDH {
+        let mut key_pair: ECDH = ECDH::new();
+        key_pair.set_secret_key(&hex(secret_key).try_into().unwrap());
+
+        key_pair
+    }
+
+    // Test-Vectors generated with pyca/cryptography (X25519, HKDF and ChaCha20Poly1305),
+    // Alice's and Bob's key pairs from: https://datatracker.ietf.org/doc/html/rfc7748#section-6.1
+    #[test]
+    fn seal_and_open() {
+        let ephemeral: ECDH = key_pair("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let recipient: ECDH = key_pair("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let plaintext: &[u8] = b"wallet-to-wallet message";
+        let ciphertext: Vec<u8> = hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a24cd426ae23f02b7c7bd2de1f512a88a40adcfe562d3ad971f2660c90efd7a363b8aabbf51a5c954");
+
+        assert_eq!(SealedBox::seal_with_ephemeral(&recipient.pub_key, plaintext, &ephemeral).unwrap(), ciphertext);
+        assert_eq!(SealedBox::open(&recipient, &ciphertext).unwrap(), plaintext);
+
+        // fresh ephemeral keys, the same message never gives the same box
+        let first: Vec<u8> = SealedBox::seal(&recipient.pub_key, plaintext).unwrap();
+        let second: Vec<u8> = SealedBox::seal(&recipient.pub_key, plaintext).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(first.len(), plaintext.len() + SEALED_BOX_OVERHEAD);
+        assert_eq!(SealedBox::open(&recipient, &second).unwrap(), plaintext);
+        assert_eq!(SealedBox::open(&recipient, &SealedBox::seal(&recipient.pub_key, &[]).unwrap()).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn reject_tampering_and_wrong_key() {
+        let mut recipient: ECDH = ECDH::new();
+        recipient.gen_key_pair();
+        let mut other: ECDH = ECDH::new();
+        other.gen_key_pair();
+
+        let ciphertext: Vec<u8> = SealedBox::seal(&recipient.pub_key, b"message").unwrap();
+        assert_eq!(SealedBox::open(&other, &ciphertext), Err(EciesError::DecryptionFailed));
+
+        // every byte is authenticated, the ephemeral key through the derived key
+        for i in [0, 31, 32, ciphertext.len() - 1] {
+            let mut modified: Vec<u8> = ciphertext.clone();
+            modified[i] ^= 0x01;
+            assert!(SealedBox::open(&recipient, &modified).is_err());
+        }
+
+        assert_eq!(SealedBox::open(&recipient, &ciphertext[..SEALED_BOX_OVERHEAD - 1]), Err(EciesError::CiphertextTooShort));
+
+        // a low order ephemeral key would make the shared secret known to everyone
+        let mut low_order: Vec<u8> = vec![0; 32];
+        low_order.extend_from_slice(&ciphertext[32..]);
+        assert_eq!(SealedBox::open(&recipient, &low_order), Err(EciesError::InvalidPublicKey));
+        assert_eq!(SealedBox::seal(&[0; 32], b"message"), Err(EciesError::InvalidPublicKey));
+    }
+}
@@ -0,0 +1,13 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum EciesError {
+    // Public key of small order, not reduced, or an all-zero shared secret.
+    InvalidPublicKey,
+    // Ciphertext shorter than the ephemeral public key and the authentication tag.
+    CiphertextTooShort,
+    // Ciphertext that was modified or not sealed to this key.
+    DecryptionFailed,
+}
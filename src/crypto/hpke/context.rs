@@ -0,0 +1,72 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9180#section-5.2
+*/
+
+#![allow(dead_code)]
+
+use super::kdf::labeled_expand;
+use super::{AeadType, HpkeError};
+use crate::crypto::{AesGcm, ChaCha20Poly1305};
+
+// Key, base nonce and exporter secret of the key schedule, shared by sender and recipient.
+pub struct HpkeContext {
+    aead: AeadType,
+    suite_id: Vec<u8>,
+    key: Vec<u8>,
+    base_nonce: [u8; 12],
+    seq: u64,
+    exporter_secret: Vec<u8>,
+}
+
+impl HpkeContext {
+    pub fn new(aead: AeadType, suite_id: Vec<u8>, key: Vec<u8>, base_nonce: [u8; 12], exporter_secret: Vec<u8>) -> Self {
+        Self { aead, suite_id, key, base_nonce, seq: 0, exporter_secret }
+    }
+
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        let nonce: [u8; 12] = self.compute_nonce()?;
+        let ciphertext: Vec<u8> = match self.aead {
+            AeadType::Aes128Gcm => AesGcm::new(&self.key).encrypt(&nonce, aad, plaintext),
+            AeadType::ChaCha20Poly1305 => ChaCha20Poly1305::new(&self.key.clone().try_into().unwrap()).encrypt(&nonce, aad, plaintext),
+        };
+        self.seq += 1;
+
+        Ok(ciphertext)
+    }
+
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        // the sequence number only advances for authentic ciphertexts
+        let nonce: [u8; 12] = self.compute_nonce()?;
+        let plaintext: Vec<u8> = match self.aead {
+            AeadType::Aes128Gcm => AesGcm::new(&self.key).decrypt(&nonce, aad, ciphertext),
+            AeadType::ChaCha20Poly1305 => ChaCha20Poly1305::new(&self.key.clone().try_into().unwrap()).decrypt(&nonce, aad, ciphertext),
+        }
+        .map_err(|_| HpkeError::DecryptionFailed)?;
+        self.seq += 1;
+
+        Ok(plaintext)
+    }
+
+    pub fn export(&self, exporter_context: &[u8], length: usize) -> Result<Vec<u8>, HpkeError> {
+        if length > 255 * 32 {
+            return Err(HpkeError::ExportTooLong);
+        }
+
+        labeled_expand(&self.suite_id, &self.exporter_secret, b"sec", exporter_context, length).ok_or(HpkeError::ExportTooLong)
+    }
+
+    fn compute_nonce(&self) -> Result<[u8; 12], HpkeError> {
+        // base_nonce xor I2OSP(seq, 12), the counter never wraps around
+        if self.seq == u64::MAX {
+            return Err(HpkeError::MessageLimitReached);
+        }
+
+        let mut nonce: [u8; 12] = self.base_nonce;
+        for (n, s) in nonce[4..].iter_mut().zip(self.seq.to_be_bytes().iter()) {
+            *n ^= s;
+        }
+
+        Ok(nonce)
+    }
+}
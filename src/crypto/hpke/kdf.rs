@@ -0,0 +1,32 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9180#section-4
+*/
+
+#![allow(dead_code)]
+
+use crate::hash::{HKDF, HashType};
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+
+pub fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Vec<u8> {
+    // labeled_ikm = "HPKE-v1" || suite_id || label || ikm
+    let mut labeled_ikm: Vec<u8> = VERSION_LABEL.to_vec();
+    labeled_ikm.extend_from_slice(suite_id);
+    labeled_ikm.extend_from_slice(label);
+    labeled_ikm.extend_from_slice(ikm);
+
+    HKDF::new(HashType::Sha256Type).extract(salt, &labeled_ikm)
+}
+
+pub fn labeled_expand(suite_id: &[u8], prk: &[u8], label: &[u8], info: &[u8], length: usize) -> Option<Vec<u8>> {
+    // labeled_info = I2OSP(L, 2) || "HPKE-v1" || suite_id || label || info
+    let length_prefix: u16 = u16::try_from(length).ok()?;
+    let mut labeled_info: Vec<u8> = length_prefix.to_be_bytes().to_vec();
+    labeled_info.extend_from_slice(VERSION_LABEL);
+    labeled_info.extend_from_slice(suite_id);
+    labeled_info.extend_from_slice(label);
+    labeled_info.extend_from_slice(info);
+
+    HKDF::new(HashType::Sha256Type).expand(prk, &labeled_info, length)
+}
@@ -0,0 +1,110 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9180#section-4.1
+*/
+
+#![allow(dead_code)]
+
+use super::kdf::{labeled_expand, labeled_extract};
+use super::HpkeError;
+use crate::crypto::ECDH;
+
+// DHKEM(X25519, HKDF-SHA256)
+pub const KEM_ID: u16 = 0x0020;
+pub const N_SECRET: usize = 32;
+pub const N_ENC: usize = 32;
+
+fn suite_id() -> Vec<u8> {
+    let mut output: Vec<u8> = b"KEM".to_vec();
+    output.extend_from_slice(&KEM_ID.to_be_bytes());
+
+    output
+}
+
+pub fn derive_key_pair(ikm: &[u8]) -> ECDH {
+    let dkp_prk: Vec<u8> = labeled_extract(&suite_id(), &[], b"dkp_prk", ikm);
+    let secret_key: Vec<u8> = labeled_expand(&suite_id(), &dkp_prk, b"sk", &[], 32).unwrap();
+
+    let mut key_pair: ECDH = ECDH::new();
+    key_pair.set_secret_key(&secret_key.try_into().unwrap());
+
+    key_pair
+}
+
+fn dh(secret: &ECDH, public: &[u8; 32]) -> Result<[u8; 32], HpkeError> {
+    // low order points give the all-zero output, which must be rejected
+    secret.checked_symmetric_key(public).map_err(|_| HpkeError::InvalidPublicKey)
+}
+
+fn extract_and_expand(dh: &[u8], kem_context: &[u8]) -> Vec<u8> {
+    let eae_prk: Vec<u8> = labeled_extract(&suite_id(), &[], b"eae_prk", dh);
+
+    labeled_expand(&suite_id(), &eae_prk, b"shared_secret", kem_context, N_SECRET).unwrap()
+}
+
+pub fn encap(pk_r: &[u8; 32], ephemeral: &ECDH) -> Result<(Vec<u8>, [u8; 32]), HpkeError> {
+    let dh: [u8; 32] = dh(ephemeral, pk_r)?;
+
+    // kem_context = enc || pkRm
+    let enc: [u8; 32] = ephemeral.pub_key;
+    let mut kem_context: Vec<u8> = enc.to_vec();
+    kem_context.extend_from_slice(pk_r);
+
+    Ok((extract_and_expand(&dh, &kem_context), enc))
+}
+
+pub fn decap(enc: &[u8; 32], sk_r: &ECDH) -> Result<Vec<u8>, HpkeError> {
+    let dh: [u8; 32] = dh(sk_r, enc)?;
+
+    let mut kem_context: Vec<u8> = enc.to_vec();
+    kem_context.extend_from_slice(&sk_r.pub_key);
+
+    Ok(extract_and_expand(&dh, &kem_context))
+}
+
+pub fn auth_encap(pk_r: &[u8; 32], sk_s: &ECDH, ephemeral: &ECDH) -> Result<(Vec<u8>, [u8; 32]), HpkeError> {
+    // dh = DH(skE, pkR) || DH(skS, pkR), kem_context = enc || pkRm || pkSm
+    let mut dh_output: Vec<u8> = dh(ephemeral, pk_r)?.to_vec();
+    dh_output.extend_from_slice(&dh(sk_s, pk_r)?);
+
+    let enc: [u8; 32] = ephemeral.pub_key;
+    let mut kem_context: Vec<u8> = enc.to_vec();
+    kem_context.extend_from_slice(pk_r);
+    kem_context.extend_from_slice(&sk_s.pub_key);
+
+    Ok((extract_and_expand(&dh_output, &kem_context), enc))
+}
+
+pub fn auth_decap(enc: &[u8; 32], sk_r: &ECDH, pk_s: &[u8; 32]) -> Result<Vec<u8>, HpkeError> {
+    let mut dh_output: Vec<u8> = dh(sk_r, enc)?.to_vec();
+    dh_output.extend_from_slice(&dh(sk_r, pk_s)?);
+
+    let mut kem_context: Vec<u8> = enc.to_vec();
+    kem_context.extend_from_slice(&sk_r.pub_key);
+    kem_context.extend_from_slice(pk_s);
+
+    Ok(extract_and_expand(&dh_output, &kem_context))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{decap, derive_key_pair, encap};
+    use crate::crypto::ECDH;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9180#appendix-A.1.1
+    #[test]
+    fn dhkem_x25519() {
+        let ephemeral: ECDH = derive_key_pair(&hex("7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234"));
+        let recipient: ECDH = derive_key_pair(&hex("6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037"));
+        assert_eq!(ephemeral.pub_key.to_vec(), hex("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431"));
+        assert_eq!(recipient.pub_key.to_vec(), hex("3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d"));
+
+        let shared_secret: Vec<u8> = hex("fe0e18c9f024ce43799ae393c7e8fe8fce9d218875e8227b0187c04e7d2ea1fc");
+        let (secret, enc) = encap(&recipient.pub_key, &ephemeral).unwrap();
+        assert_eq!(secret, shared_secret);
+        assert_eq!(enc, ephemeral.pub_key);
+        assert_eq!(decap(&enc, &recipient).unwrap(), shared_secret);
+    }
+}
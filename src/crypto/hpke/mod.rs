@@ -0,0 +1,15 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod kdf;
+mod kem;
+mod context;
+mod suite;
+mod types;
+
+pub use context::HpkeContext;
+pub use suite::HPKE;
+pub use types::AeadType;
+
+pub use types::HpkeError;
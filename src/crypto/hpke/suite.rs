@@ -0,0 +1,286 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9180#section-5
+*/
+
+#![allow(dead_code)]
+
+use super::kdf::{labeled_expand, labeled_extract};
+use super::kem::{self, KEM_ID, N_ENC};
+use super::{AeadType, HpkeContext, HpkeError};
+use crate::crypto::ECDH;
+
+const KDF_ID: u16 = 0x0001;
+
+const MODE_BASE: u8 = 0x00;
+const MODE_AUTH: u8 = 0x02;
+
+// HPKE with DHKEM(X25519, HKDF-SHA256), HKDF-SHA256 and the chosen AEAD.
+pub struct HPKE {
+    aead: AeadType,
+}
+
+impl HPKE {
+    pub fn new(aead: AeadType) -> Self {
+        Self { aead }
+    }
+
+    pub fn setup_base_s(&self, pk_r: &[u8; 32], info: &[u8]) -> Result<([u8; 32], HpkeContext), HpkeError> {
+        self.setup_base_s_with_ephemeral(pk_r, info, &ephemeral_key())
+    }
+
+    pub fn setup_base_r(&self, enc: &[u8; 32], sk_r: &ECDH, info: &[u8]) -> Result<HpkeContext, HpkeError> {
+        let shared_secret: Vec<u8> = kem::decap(enc, sk_r)?;
+
+        Ok(self.key_schedule(MODE_BASE, &shared_secret, info))
+    }
+
+    pub fn setup_auth_s(&self, pk_r: &[u8; 32], info: &[u8], sk_s: &ECDH) -> Result<([u8; 32], HpkeContext), HpkeError> {
+        self.setup_auth_s_with_ephemeral(pk_r, info, sk_s, &ephemeral_key())
+    }
+
+    pub fn setup_auth_r(&self, enc: &[u8; 32], sk_r: &ECDH, info: &[u8], pk_s: &[u8; 32]) -> Result<HpkeContext, HpkeError> {
+        let shared_secret: Vec<u8> = kem::auth_decap(enc, sk_r, pk_s)?;
+
+        Ok(self.key_schedule(MODE_AUTH, &shared_secret, info))
+    }
+
+    pub fn seal_base(&self, pk_r: &[u8; 32], info: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        // single-shot encryption, the output is enc || ciphertext
+        let (enc, mut context) = self.setup_base_s(pk_r, info)?;
+        let mut output: Vec<u8> = enc.to_vec();
+        output.extend_from_slice(&context.seal(aad, plaintext)?);
+
+        Ok(output)
+    }
+
+    pub fn open_base(&self, sk_r: &ECDH, info: &[u8], aad: &[u8], input: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        if input.len() < N_ENC {
+            return Err(HpkeError::InvalidEncapsulatedKey);
+        }
+
+        let (enc, ciphertext) = input.split_at(N_ENC);
+        let mut context: HpkeContext = self.setup_base_r(&enc.try_into().unwrap(), sk_r, info)?;
+
+        context.open(aad, ciphertext)
+    }
+
+    fn setup_base_s_with_ephemeral(&self, pk_r: &[u8; 32], info: &[u8], ephemeral: &ECDH) -> Result<([u8; 32], HpkeContext), HpkeError> {
+        let (shared_secret, enc) = kem::encap(pk_r, ephemeral)?;
+
+        Ok((enc, self.key_schedule(MODE_BASE, &shared_secret, info)))
+    }
+
+    fn setup_auth_s_with_ephemeral(&self, pk_r: &[u8; 32], info: &[u8], sk_s: &ECDH, ephemeral: &ECDH) -> Result<([u8; 32], HpkeContext), HpkeError> {
+        let (shared_secret, enc) = kem::auth_encap(pk_r, sk_s, ephemeral)?;
+
+        Ok((enc, self.key_schedule(MODE_AUTH, &shared_secret, info)))
+    }
+
+    fn suite_id(&self) -> Vec<u8> {
+        // "HPKE" || kem_id || kdf_id || aead_id
+        let aead_id: u16 = match self.aead {
+            AeadType::Aes128Gcm => 0x0001,
+            AeadType::ChaCha20Poly1305 => 0x0003,
+        };
+
+        let mut output: Vec<u8> = b"HPKE".to_vec();
+        output.extend_from_slice(&KEM_ID.to_be_bytes());
+        output.extend_from_slice(&KDF_ID.to_be_bytes());
+        output.extend_from_slice(&aead_id.to_be_bytes());
+
+        output
+    }
+
+    fn key_schedule(&self, mode: u8, shared_secret: &[u8], info: &[u8]) -> HpkeContext {
+        // base and auth mode use the default empty psk and psk_id
+        let suite_id: Vec<u8> = self.suite_id();
+        let key_length: usize = match self.aead {
+            AeadType::Aes128Gcm => 16,
+            AeadType::ChaCha20Poly1305 => 32,
+        };
+
+        let mut key_schedule_context: Vec<u8> = vec![mode];
+        key_schedule_context.extend_from_slice(&labeled_extract(&suite_id, &[], b"psk_id_hash", &[]));
+        key_schedule_context.extend_from_slice(&labeled_extract(&suite_id, &[], b"info_hash", info));
+
+        let secret: Vec<u8> = labeled_extract(&suite_id, shared_secret, b"secret", &[]);
+        let key: Vec<u8> = labeled_expand(&suite_id, &secret, b"key", &key_schedule_context, key_length).unwrap();
+        let base_nonce: Vec<u8> = labeled_expand(&suite_id, &secret, b"base_nonce", &key_schedule_context, 12).unwrap();
+        let exporter_secret: Vec<u8> = labeled_expand(&suite_id, &secret, b"exp", &key_schedule_context, 32).unwrap();
+
+        HpkeContext::new(self.aead, suite_id, key, base_nonce.try_into().unwrap(), exporter_secret)
+    }
+}
+
+fn ephemeral_key() -> ECDH {
+    let mut ephemeral: ECDH = ECDH::new();
+    ephemeral.gen_key_pair();
+
+    ephemeral
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{HPKE, HpkeContext};
+    use super::super::kem::derive_key_pair;
+    use super::super::{AeadType, HpkeError};
+    use crate::crypto::ECDH;
+    use crate::utils::testing::hex;
+
+    const INFO: &str = "4f6465206f6e2061204772656369616e2055726e";
+    const PLAINTEXT: &[u8] = b"Beauty is truth, truth beauty";
+
+    // sequence numbers of the encryptions listed for every suite in RFC 9180 appendix A
+    const SEQUENCE_NUMBERS: [usize; 6] = [0, 1, 2, 4, 255, 256];
+
+    fn key_pairs() -> (ECDH, ECDH) {
+        // recipient key of RFC 9180 A.1.1 and sender key of A.1.3
+        let recipient: ECDH = derive_key_pair(&hex("6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037"));
+        let sender: ECDH = derive_key_pair(&hex("94b020ce91d73fca4649006c7e7329a67b40c55e9e93cc907d282bbbff386f58"));
+
+        (recipient, sender)
+    }
+
+    fn check_vector(aead: AeadType, ikm_e: &str, ikm_r: &str, ikm_s: Option<&str>, ciphertexts: [&str; 6], exports: [&str; 3]) {
+        let ephemeral: ECDH = derive_key_pair(&hex(ikm_e));
+        let recipient: ECDH = derive_key_pair(&hex(ikm_r));
+        let hpke: HPKE = HPKE::new(aead);
+
+        let (mut sender, mut receiver) = match ikm_s {
+            Some(ikm_s) => {
+                let sender_key: ECDH = derive_key_pair(&hex(ikm_s));
+                let (enc, sender) = hpke.setup_auth_s_with_ephemeral(&recipient.pub_key, &hex(INFO), &sender_key, &ephemeral).unwrap();
+                (sender, hpke.setup_auth_r(&enc, &recipient, &hex(INFO), &sender_key.pub_key).unwrap())
+            }
+            None => {
+                let (enc, sender) = hpke.setup_base_s_with_ephemeral(&recipient.pub_key, &hex(INFO), &ephemeral).unwrap();
+                (sender, hpke.setup_base_r(&enc, &recipient, &hex(INFO)).unwrap())
+            }
+        };
+
+        for sequence_number in 0..=256 {
+            let aad: String = format!("Count-{}", sequence_number);
+            let ciphertext: Vec<u8> = sender.seal(aad.as_bytes(), PLAINTEXT).unwrap();
+            if let Some(i) = SEQUENCE_NUMBERS.iter().position(|&x| x == sequence_number) {
+                assert_eq!(ciphertext, hex(ciphertexts[i]));
+            }
+            assert_eq!(receiver.open(aad.as_bytes(), &ciphertext).unwrap(), PLAINTEXT);
+        }
+
+        let contexts: [&[u8]; 3] = [b"", &[0x00], b"TestContext"];
+        for (context, export) in contexts.iter().zip(exports.iter()) {
+            assert_eq!(sender.export(context, 32).unwrap(), hex(export));
+            assert_eq!(receiver.export(context, 32).unwrap(), hex(export));
+        }
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9180#appendix-A.1.1
+    #[test]
+    fn base_aes_128_gcm() {
+        check_vector(
+            AeadType::Aes128Gcm,
+            "7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234",
+            "6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037",
+            None,
+            [
+                "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a",
+                "af2d7e9ac9ae7e270f46ba1f975be53c09f8d875bdc8535458c2494e8a6eab251c03d0c22a56b8ca42c2063b84",
+                "498dfcabd92e8acedc281e85af1cb4e3e31c7dc394a1ca20e173cb72516491588d96a19ad4a683518973dcc180",
+                "583bd32bc67a5994bb8ceaca813d369bca7b2a42408cddef5e22f880b631215a09fc0012bc69fccaa251c0246d",
+                "7175db9717964058640a3a11fb9007941a5d1757fda1a6935c805c21af32505bf106deefec4a49ac38d71c9e0a",
+                "957f9800542b0b8891badb026d79cc54597cb2d225b54c00c5238c25d05c30e3fbeda97d2e0e1aba483a2df9f2",
+            ],
+            [
+                "3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee",
+                "2e8f0b54673c7029649d4eb9d5e33bf1872cf76d623ff164ac185da9e88c21a5",
+                "e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931",
+            ],
+        );
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9180#appendix-A.1.3
+    #[test]
+    fn auth_aes_128_gcm() {
+        check_vector(
+            AeadType::Aes128Gcm,
+            "6e6d8f200ea2fb20c30b003a8b4f433d2f4ed4c2658d5bc8ce2fef718059c9f7",
+            "f1d4a30a4cef8d6d4e3b016e6fd3799ea057db4f345472ed302a67ce1c20cdec",
+            Some("94b020ce91d73fca4649006c7e7329a67b40c55e9e93cc907d282bbbff386f58"),
+            [
+                "5fd92cc9d46dbf8943e72a07e42f363ed5f721212cd90bcfd072bfd9f44e06b80fd17824947496e21b680c141b",
+                "d3736bb256c19bfa93d79e8f80b7971262cb7c887e35c26370cfed62254369a1b52e3d505b79dd699f002bc8ed",
+                "122175cfd5678e04894e4ff8789e85dd381df48dcaf970d52057df2c9acc3b121313a2bfeaa986050f82d93645",
+                "dae12318660cf963c7bcbef0f39d64de3bf178cf9e585e756654043cc5059873bc8af190b72afc43d1e0135ada",
+                "55d53d85fe4d9e1e97903101eab0b4865ef20cef28765a47f840ff99625b7d69dee927df1defa66a036fc58ff2",
+                "42fa248a0e67ccca688f2b1d13ba4ba84755acf764bd797c8f7ba3b9b1dc3330326f8d172fef6003c79ec72319",
+            ],
+            [
+                "28c70088017d70c896a8420f04702c5a321d9cbf0279fba899b59e51bac72c85",
+                "25dfc004b0892be1888c3914977aa9c9bbaf2c7471708a49e1195af48a6f29ce",
+                "5a0131813abc9a522cad678eb6bafaabc43389934adb8097d23c5ff68059eb64",
+            ],
+        );
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9180#appendix-A.2.1
+    #[test]
+    fn base_chacha20_poly1305() {
+        check_vector(
+            AeadType::ChaCha20Poly1305,
+            "909a9b35d3dc4713a5e72a4da274b55d3d3821a37e5d099e74a647db583a904b",
+            "1ac01f181fdf9f352797655161c58b75c656a6cc2716dcb66372da835542e1df",
+            None,
+            [
+                "1c5250d8034ec2b784ba2cfd69dbdb8af406cfe3ff938e131f0def8c8b60b4db21993c62ce81883d2dd1b51a28",
+                "6b53c051e4199c518de79594e1c4ab18b96f081549d45ce015be002090bb119e85285337cc95ba5f59992dc98c",
+                "71146bd6795ccc9c49ce25dda112a48f202ad220559502cef1f34271e0cb4b02b4f10ecac6f48c32f878fae86b",
+                "63357a2aa291f5a4e5f27db6baa2af8cf77427c7c1a909e0b37214dd47db122bb153495ff0b02e9e54a50dbe16",
+                "18ab939d63ddec9f6ac2b60d61d36a7375d2070c9b683861110757062c52b8880a5f6b3936da9cd6c23ef2a95c",
+                "7a4a13e9ef23978e2c520fd4d2e757514ae160cd0cd05e556ef692370ca53076214c0c40d4c728d6ed9e727a5b",
+            ],
+            [
+                "4bbd6243b8bb54cec311fac9df81841b6fd61f56538a775e7c80a9f40160606e",
+                "8c1df14732580e5501b00f82b10a1647b40713191b7c1240ac80e2b68808ba69",
+                "5acb09211139c43b3090489a9da433e8a30ee7188ba8b0a9a1ccf0c229283e53",
+            ],
+        );
+    }
+
+    // Test-Vectors generated with the HPKE single-shot API of pyca/cryptography (enc || ciphertext, empty aad)
+    #[test]
+    fn open_pyca_ciphertexts() {
+        let (recipient, _) = key_pairs();
+
+        let sealed: Vec<u8> = hex("c35a2fd690e53ac89901160eb32aa7f2970340cd53187e2481a2ef8424b22950b32630207e6551cdb576df592c6b575fa73d921cda6272bc65dc21ce0438");
+        assert_eq!(HPKE::new(AeadType::Aes128Gcm).open_base(&recipient, &hex(INFO), &[], &sealed).unwrap(), b"encrypted memo");
+
+        let sealed: Vec<u8> = hex("9a7176a101d8d0674e6e18988fbefd5267b5c5cda983389cdc76e525e3909d2e52e77184a7a81c832591a8e922533323f893221f973d6456087d43b68f28");
+        assert_eq!(HPKE::new(AeadType::ChaCha20Poly1305).open_base(&recipient, &hex(INFO), &[], &sealed).unwrap(), b"encrypted memo");
+    }
+
+    #[test]
+    fn reject_invalid_inputs() {
+        let (recipient, sender_key) = key_pairs();
+        let hpke: HPKE = HPKE::new(AeadType::ChaCha20Poly1305);
+
+        let sealed: Vec<u8> = hpke.seal_base(&recipient.pub_key, b"info", b"aad", b"memo").unwrap();
+        assert_eq!(hpke.open_base(&recipient, b"info", b"aad", &sealed).unwrap(), b"memo");
+        assert_eq!(hpke.open_base(&recipient, b"other", b"aad", &sealed), Err(HpkeError::DecryptionFailed));
+        assert_eq!(hpke.open_base(&sender_key, b"info", b"aad", &sealed), Err(HpkeError::DecryptionFailed));
+        assert_eq!(hpke.open_base(&recipient, b"info", b"aad", &sealed[..31]), Err(HpkeError::InvalidEncapsulatedKey));
+        assert_eq!(HPKE::new(AeadType::Aes128Gcm).open_base(&recipient, b"info", b"aad", &sealed), Err(HpkeError::DecryptionFailed));
+
+        // the all-zero point has small order
+        assert!(matches!(hpke.setup_base_s(&[0; 32], b"info"), Err(HpkeError::InvalidPublicKey)));
+
+        // auth mode binds the sender, a different expected sender fails to open
+        let (enc, mut sender) = hpke.setup_auth_s(&recipient.pub_key, b"info", &sender_key).unwrap();
+        let ciphertext: Vec<u8> = sender.seal(&[], b"memo").unwrap();
+        let mut receiver: HpkeContext = hpke.setup_auth_r(&enc, &recipient, b"info", &recipient.pub_key).unwrap();
+        assert_eq!(receiver.open(&[], &ciphertext), Err(HpkeError::DecryptionFailed));
+
+        assert_eq!(receiver.export(&[], 255 * 32 + 1), Err(HpkeError::ExportTooLong));
+    }
+}
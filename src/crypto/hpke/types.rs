@@ -0,0 +1,25 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AeadType {
+    // AES-128-GCM, aead_id 0x0001
+    Aes128Gcm,
+    // ChaCha20-Poly1305, aead_id 0x0003
+    ChaCha20Poly1305,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HpkeError {
+    // Public key of small order, not reduced, or an all-zero Diffie-Hellman output.
+    InvalidPublicKey,
+    // Encapsulated key that is not 32 bytes long.
+    InvalidEncapsulatedKey,
+    // Ciphertext that does not authenticate under the context key and nonce.
+    DecryptionFailed,
+    // Sequence number that would repeat a nonce.
+    MessageLimitReached,
+    // Exporter output longer than 255 * 32 bytes.
+    ExportTooLong,
+}
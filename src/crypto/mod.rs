@@ -4,9 +4,24 @@ Warranty Disclaimer: the software is provided "as is" without any warranties or
 
 mod ec;
 mod aes;
+mod aead;
+mod noise;
+mod hpke;
+mod ecies;
+mod rsa;
 
 pub use aes::AES;
 pub use aes::Blocksize;
 pub use aes::AES_CBC;
 
-pub use ec::{ECDH, FieldElement, scalarmult};
+pub use aead::{ChaCha20Poly1305, AesGcm, AeadError};
+
+pub use noise::{HandshakeState, HandshakePattern, TransportState, CipherState, NoiseError};
+
+pub use hpke::{HPKE, HpkeContext, AeadType, HpkeError};
+
+pub use ecies::{SealedBox, EciesError};
+
+pub use ec::{ECDH, ECDH448, Ed25519, ECVRF, Ed448, Ristretto255, Pedersen, PedersenCommitment, Secp256k1, Schnorr, P256, Point, FieldElement, hash_to_curve25519, scalarmult, scalarmult_checked, scalarmult448, EcError, BatchError};
+
+pub use rsa::{RSA, RSA2048, RSA3072, RSA4096, RsaPublicKey, RsaError};
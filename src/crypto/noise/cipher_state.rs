@@ -0,0 +1,128 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to http://www.noiseprotocol.org/noise.html#the-cipherstate-object
+*/
+
+#![allow(dead_code)]
+
+use super::NoiseError;
+use crate::crypto::aead::ChaCha20Poly1305;
+
+pub const MAX_MESSAGE_LENGTH: usize = 65535;
+pub const TAG_LENGTH: usize = 16;
+
+// Key k and counter n, without a key the plaintext is passed through.
+pub struct CipherState {
+    k: Option<[u8; 32]>,
+    n: u64,
+}
+
+impl Default for CipherState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CipherState {
+    pub fn new() -> Self {
+        Self { k: None, n: 0 }
+    }
+
+    pub fn initialize_key(&mut self, key: Option<[u8; 32]>) {
+        self.k = key;
+        self.n = 0;
+    }
+
+    pub fn has_key(&self) -> bool {
+        self.k.is_some()
+    }
+
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.n = nonce;
+    }
+
+    pub fn encrypt_with_ad(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let key: [u8; 32] = match self.k {
+            Some(key) => key,
+            None => return Ok(plaintext.to_vec()),
+        };
+        if self.n == u64::MAX {
+            return Err(NoiseError::NonceExhausted);
+        }
+
+        let output: Vec<u8> = ChaCha20Poly1305::new(&key).encrypt(&nonce(self.n), ad, plaintext);
+        self.n += 1;
+
+        Ok(output)
+    }
+
+    pub fn decrypt_with_ad(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let key: [u8; 32] = match self.k {
+            Some(key) => key,
+            None => return Ok(ciphertext.to_vec()),
+        };
+        if self.n == u64::MAX {
+            return Err(NoiseError::NonceExhausted);
+        }
+
+        // n is only incremented for authentic messages
+        let output: Vec<u8> = ChaCha20Poly1305::new(&key)
+            .decrypt(&nonce(self.n), ad, ciphertext)
+            .map_err(|_| NoiseError::DecryptionFailed)?;
+        self.n += 1;
+
+        Ok(output)
+    }
+
+    pub fn rekey(&mut self) {
+        // k = first 32 bytes of ENCRYPT(k, 2^64 - 1, zerolen, zeros)
+        if let Some(key) = self.k {
+            let output: Vec<u8> = ChaCha20Poly1305::new(&key).encrypt(&nonce(u64::MAX), &[], &[0; 32]);
+            self.k = Some(output[..32].try_into().unwrap());
+        }
+    }
+}
+
+fn nonce(n: u64) -> [u8; 12] {
+    // 32 zero bits followed by the little-endian counter
+    let mut output: [u8; 12] = [0; 12];
+    output[4..].copy_from_slice(&n.to_le_bytes());
+
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::CipherState;
+    use super::super::NoiseError;
+
+    #[test]
+    fn nonces_and_rekey() {
+        let mut sender: CipherState = CipherState::new();
+        let mut receiver: CipherState = CipherState::new();
+
+        // no key, no encryption
+        assert_eq!(sender.encrypt_with_ad(&[], b"hello").unwrap(), b"hello");
+
+        sender.initialize_key(Some([0x01; 32]));
+        receiver.initialize_key(Some([0x01; 32]));
+        let first: Vec<u8> = sender.encrypt_with_ad(b"ad", b"hello").unwrap();
+        let second: Vec<u8> = sender.encrypt_with_ad(b"ad", b"hello").unwrap();
+        assert_ne!(first, second);
+
+        // out of order fails and does not advance the counter
+        assert_eq!(receiver.decrypt_with_ad(b"ad", &second), Err(NoiseError::DecryptionFailed));
+        assert_eq!(receiver.decrypt_with_ad(b"ad", &first).unwrap(), b"hello");
+        assert_eq!(receiver.decrypt_with_ad(b"ad", &second).unwrap(), b"hello");
+
+        sender.rekey();
+        let third: Vec<u8> = sender.encrypt_with_ad(&[], b"hello").unwrap();
+        assert_eq!(receiver.decrypt_with_ad(&[], &third), Err(NoiseError::DecryptionFailed));
+        receiver.rekey();
+        assert_eq!(receiver.decrypt_with_ad(&[], &third).unwrap(), b"hello");
+
+        sender.set_nonce(u64::MAX);
+        assert_eq!(sender.encrypt_with_ad(&[], b"hello"), Err(NoiseError::NonceExhausted));
+    }
+}
@@ -0,0 +1,439 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to http://www.noiseprotocol.org/noise.html#the-handshakestate-object
+*/
+
+#![allow(dead_code)]
+
+use super::cipher_state::{MAX_MESSAGE_LENGTH, TAG_LENGTH};
+use super::symmetric_state::SymmetricState;
+use super::{CipherState, NoiseError};
+use crate::crypto::ECDH;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Token {
+    E,
+    S,
+    EE,
+    ES,
+    SE,
+    SS,
+}
+
+// Interactive patterns of the Noise spec, section 7.5.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HandshakePattern {
+    // Both static keys are transmitted, nothing is known in advance.
+    XX,
+    // The initiator knows the responder's static key and sends its own immediately.
+    IK,
+}
+
+impl HandshakePattern {
+    fn name(&self) -> &'static str {
+        match self {
+            HandshakePattern::XX => "XX",
+            HandshakePattern::IK => "IK",
+        }
+    }
+
+    fn messages(&self) -> &'static [&'static [Token]] {
+        match self {
+            HandshakePattern::XX => &[&[Token::E], &[Token::E, Token::EE, Token::S, Token::ES], &[Token::S, Token::SE]],
+            HandshakePattern::IK => &[&[Token::E, Token::ES, Token::S, Token::SS], &[Token::E, Token::EE, Token::SE]],
+        }
+    }
+
+    fn responder_static_known(&self) -> bool {
+        // pre-message "<- s"
+        *self == HandshakePattern::IK
+    }
+}
+
+pub struct HandshakeState {
+    symmetric: SymmetricState,
+    pattern: HandshakePattern,
+    initiator: bool,
+    s: ECDH,
+    e: Option<ECDH>,
+    rs: Option<[u8; 32]>,
+    re: Option<[u8; 32]>,
+    message_index: usize,
+}
+
+impl HandshakeState {
+    pub fn new(pattern: HandshakePattern, initiator: bool, prologue: &[u8], s: ECDH, rs: Option<[u8; 32]>) -> Result<Self, NoiseError> {
+        let protocol_name: String = format!("Noise_{}_25519_ChaChaPoly_SHA256", pattern.name());
+        let mut symmetric: SymmetricState = SymmetricState::initialize(protocol_name.as_bytes());
+        symmetric.mix_hash(prologue);
+
+        if pattern.responder_static_known() {
+            if initiator {
+                symmetric.mix_hash(&rs.ok_or(NoiseError::MissingKey)?);
+            } else {
+                symmetric.mix_hash(&s.pub_key);
+            }
+        }
+
+        Ok(Self {
+            symmetric,
+            pattern,
+            initiator,
+            s,
+            e: None,
+            rs,
+            re: None,
+            message_index: 0,
+        })
+    }
+
+    pub fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let tokens: &[Token] = self.next_tokens(true)?;
+        // checked before anything is mixed in, a rejected payload leaves the state untouched
+        if self.message_length(tokens, payload.len()) > MAX_MESSAGE_LENGTH {
+            return Err(NoiseError::MessageTooLong);
+        }
+
+        let mut output: Vec<u8> = Vec::new();
+        for token in tokens.iter() {
+            match token {
+                Token::E => {
+                    let e: &ECDH = self.e.get_or_insert_with(|| {
+                        let mut e: ECDH = ECDH::new();
+                        e.gen_key_pair();
+                        e
+                    });
+                    let e_pub: [u8; 32] = e.pub_key;
+                    output.extend_from_slice(&e_pub);
+                    self.symmetric.mix_hash(&e_pub);
+                },
+                Token::S => {
+                    let s_pub: [u8; 32] = self.s.pub_key;
+                    output.extend(self.symmetric.encrypt_and_hash(&s_pub)?);
+                },
+                _ => {
+                    let shared: [u8; 32] = self.dh(*token)?;
+                    self.symmetric.mix_key(&shared);
+                },
+            }
+        }
+        output.extend(self.symmetric.encrypt_and_hash(payload)?);
+        self.message_index += 1;
+
+        Ok(output)
+    }
+
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if message.len() > MAX_MESSAGE_LENGTH {
+            return Err(NoiseError::MessageTooLong);
+        }
+        let tokens: &[Token] = self.next_tokens(false)?;
+
+        let mut rest: &[u8] = message;
+        for token in tokens.iter() {
+            match token {
+                Token::E => {
+                    if rest.len() < 32 {
+                        return Err(NoiseError::MessageTooShort);
+                    }
+                    let re: [u8; 32] = rest[..32].try_into().unwrap();
+                    rest = &rest[32..];
+                    self.re = Some(re);
+                    self.symmetric.mix_hash(&re);
+                },
+                Token::S => {
+                    // encrypted once a key was mixed in
+                    let length: usize = if self.symmetric.has_key() { 32 + TAG_LENGTH } else { 32 };
+                    if rest.len() < length {
+                        return Err(NoiseError::MessageTooShort);
+                    }
+                    let rs: Vec<u8> = self.symmetric.decrypt_and_hash(&rest[..length])?;
+                    rest = &rest[length..];
+                    self.rs = Some(rs.try_into().unwrap());
+                },
+                _ => {
+                    let shared: [u8; 32] = self.dh(*token)?;
+                    self.symmetric.mix_key(&shared);
+                },
+            }
+        }
+        let payload: Vec<u8> = self.symmetric.decrypt_and_hash(rest)?;
+        self.message_index += 1;
+
+        Ok(payload)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.message_index == self.pattern.messages().len()
+    }
+
+    pub fn handshake_hash(&self) -> [u8; 32] {
+        self.symmetric.handshake_hash()
+    }
+
+    pub fn remote_static(&self) -> Option<[u8; 32]> {
+        self.rs
+    }
+
+    pub fn into_transport(self) -> Result<TransportState, NoiseError> {
+        if !self.is_finished() {
+            return Err(NoiseError::HandshakeNotFinished);
+        }
+
+        // the first key encrypts from initiator to responder
+        let (c_1, c_2) = self.symmetric.split();
+        let (send, receive) = if self.initiator { (c_1, c_2) } else { (c_2, c_1) };
+
+        Ok(TransportState {
+            send,
+            receive,
+            handshake_hash: self.symmetric.handshake_hash(),
+        })
+    }
+
+    fn set_ephemeral_key(&mut self, secret_key: &[u8; 32]) {
+        // fixed ephemeral keys for the test vectors
+        let mut e: ECDH = ECDH::new();
+        e.set_secret_key(secret_key);
+        self.e = Some(e);
+    }
+
+    fn next_tokens(&self, writing: bool) -> Result<&'static [Token], NoiseError> {
+        let messages: &'static [&'static [Token]] = self.pattern.messages();
+        if self.message_index >= messages.len() {
+            return Err(NoiseError::HandshakeFinished);
+        }
+
+        // the initiator writes the even messages
+        if self.message_index.is_multiple_of(2) != (self.initiator == writing) {
+            return Err(NoiseError::OutOfOrder);
+        }
+
+        Ok(messages[self.message_index])
+    }
+
+    fn message_length(&self, tokens: &[Token], payload_length: usize) -> usize {
+        // "s" and the payload get a tag once a key was mixed in, which every DH token does
+        let mut has_key: bool = self.symmetric.has_key();
+        let mut length: usize = 0;
+        for token in tokens.iter() {
+            match token {
+                Token::E => length += 32,
+                Token::S => length += if has_key { 32 + TAG_LENGTH } else { 32 },
+                _ => has_key = true,
+            }
+        }
+
+        length + payload_length + if has_key { TAG_LENGTH } else { 0 }
+    }
+
+    fn dh(&self, token: Token) -> Result<[u8; 32], NoiseError> {
+        // "es" is DH(e, rs) for the initiator and DH(s, re) for the responder, "se" vice versa
+        let (local, remote) = match (token, self.initiator) {
+            (Token::EE, _) => (self.e.as_ref(), self.re),
+            (Token::ES, true) | (Token::SE, false) => (self.e.as_ref(), self.rs),
+            (Token::ES, false) | (Token::SE, true) => (Some(&self.s), self.re),
+            _ => (Some(&self.s), self.rs),
+        };
+
+        match (local, remote) {
+            (Some(local), Some(remote)) => Ok(local.symmetric_key(&remote)),
+            _ => Err(NoiseError::MissingKey),
+        }
+    }
+}
+
+// Cipher states after the handshake, one per direction.
+pub struct TransportState {
+    send: CipherState,
+    receive: CipherState,
+    handshake_hash: [u8; 32],
+}
+
+impl TransportState {
+    pub fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if payload.len() + TAG_LENGTH > MAX_MESSAGE_LENGTH {
+            return Err(NoiseError::MessageTooLong);
+        }
+
+        self.send.encrypt_with_ad(&[], payload)
+    }
+
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if message.len() > MAX_MESSAGE_LENGTH {
+            return Err(NoiseError::MessageTooLong);
+        }
+
+        self.receive.decrypt_with_ad(&[], message)
+    }
+
+    pub fn handshake_hash(&self) -> [u8; 32] {
+        self.handshake_hash
+    }
+
+    pub fn rekey_outgoing(&mut self) {
+        self.send.rekey();
+    }
+
+    pub fn rekey_incoming(&mut self) {
+        self.receive.rekey();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{HandshakeState, HandshakePattern, TransportState};
+    use super::super::NoiseError;
+    use crate::crypto::ECDH;
+    use crate::utils::testing::hex;
+
+    fn key_pair(secret_key: &str) -> ECDH {
+        let mut ecdh: ECDH = ECDH::new();
+        ecdh.set_secret_key(&hex(secret_key).try_into().unwrap());
+        ecdh
+    }
+
+    const INIT_STATIC: &str = "e61ef9919cde45dd5f82166404bd08e38bceb5dfdfded0a34c8df7ed542214d1";
+    const INIT_EPHEMERAL: &str = "893e28b9dc6ca8d611ab664754b8ceb7bac5117349a4439a6b0569da977c464a";
+    const RESP_STATIC: &str = "4a3acbfdb163dec651dfa3194dece676d437029c62a408b4c5ea9114246e4893";
+    const RESP_EPHEMERAL: &str = "bbdb4cdbd309f1a1f2e1456967fe288cadd6f712d65dc7b7793d5e63da6b375b";
+    const PAYLOADS: [&[u8]; 6] = [b"Ludwig von Mises", b"Murray Rothbard", b"F. A. Hayek", b"Carl Menger", b"Jean-Baptiste Say", b"Eugen B\xf6hm von Bawerk"];
+
+    fn run_vector(pattern: HandshakePattern, messages: &[&str], handshake_hash: &str) {
+        let rs: Option<[u8; 32]> = match pattern {
+            HandshakePattern::IK => Some(key_pair(RESP_STATIC).pub_key),
+            HandshakePattern::XX => None,
+        };
+        let mut initiator: HandshakeState = HandshakeState::new(pattern, true, b"John Galt", key_pair(INIT_STATIC), rs).unwrap();
+        let mut responder: HandshakeState = HandshakeState::new(pattern, false, b"John Galt", key_pair(RESP_STATIC), None).unwrap();
+        initiator.set_ephemeral_key(&hex(INIT_EPHEMERAL).try_into().unwrap());
+        responder.set_ephemeral_key(&hex(RESP_EPHEMERAL).try_into().unwrap());
+
+        // handshake messages alternate, starting with the initiator
+        let handshake_length: usize = pattern.messages().len();
+        for i in 0..handshake_length {
+            let (writer, reader) = if i % 2 == 0 { (&mut initiator, &mut responder) } else { (&mut responder, &mut initiator) };
+            let message: Vec<u8> = writer.write_message(PAYLOADS[i]).unwrap();
+            assert_eq!(message, hex(messages[i]));
+            assert_eq!(reader.read_message(&message).unwrap(), PAYLOADS[i]);
+        }
+
+        assert_eq!(initiator.remote_static(), Some(key_pair(RESP_STATIC).pub_key));
+        assert_eq!(responder.remote_static(), Some(key_pair(INIT_STATIC).pub_key));
+
+        let mut initiator: TransportState = initiator.into_transport().unwrap();
+        let mut responder: TransportState = responder.into_transport().unwrap();
+        assert_eq!(initiator.handshake_hash().to_vec(), hex(handshake_hash));
+        assert_eq!(responder.handshake_hash(), initiator.handshake_hash());
+
+        // transport messages keep alternating after the handshake
+        for i in handshake_length..PAYLOADS.len() {
+            let (writer, reader) = if i % 2 == 0 { (&mut initiator, &mut responder) } else { (&mut responder, &mut initiator) };
+            let message: Vec<u8> = writer.write_message(PAYLOADS[i]).unwrap();
+            assert_eq!(message, hex(messages[i]));
+            assert_eq!(reader.read_message(&message).unwrap(), PAYLOADS[i]);
+        }
+    }
+
+    // Test-Vectors with cacophony style inputs (prologue, payloads and fixed keys), the messages and the handshake
+    // hash are not copied from cacophony.txt but were generated with a Python implementation on top of pyca/cryptography
+    #[test]
+    fn noise_xx() {
+        run_vector(HandshakePattern::XX, &[
+            "ca35def5ae56cec33dc2036731ab14896bc4c75dbb07a61f879f8e3afa4c79444c756477696720766f6e204d69736573",
+            "95ebc60d2b1fa672c1f46a8aa265ef51bfe38e7ccb39ec5be34069f14480884381cbad1f276e038c48378ffce2b65285e08d6b68aaa3629a5a8639392490e5b9bd5269c2f1e4f488ed8831161f19b7815528f8982ffe09be9b5c412f8a0db50f8814c7194e83f23dbd8d162c9326ad",
+            "c7195ffacac1307ff99046f219750fc47693e23c3cb08b89c2af808b444850a80ae475b9df0f169ae80a89be0865b57f58c9fea0d4ec82a286427402f113e4b6ae769a1d95941d49b25030",
+            "96763ed773f8e47bb3712f0e29b3060ffc956ffc146cee53d5e1df",
+            "3e40f15f6f3a46ae446b253bf8b1d9ffb6ed9b174d272328ff91a7e2e5c79c07f5",
+            "eb3f3515110702e047a6c9da4478b6ead94873c11c0f2d710ddb3f09fce024b3a58502ae3f",
+        ], "c8e5f64e846193be2a834104c2a009868d6c9f3bd3c186299888b488b2f1f58e");
+    }
+
+    // Test-Vectors generated like the XX ones, the initiator knows the responder's static public key
+    #[test]
+    fn noise_ik() {
+        run_vector(HandshakePattern::IK, &[
+            "ca35def5ae56cec33dc2036731ab14896bc4c75dbb07a61f879f8e3afa4c7944718da798efbcd91528520204f904b9bd6c7413dccdc214d951e15253e39987f18146e8cd0873654207148333479d4d16c289f0294b29960a72f48e0b7bba2e89083169825e59642148d492020664ccf7",
+            "95ebc60d2b1fa672c1f46a8aa265ef51bfe38e7ccb39ec5be34069f1448088435361e70b2ed446e6c9ec387d1d6b3b840f194e373979d241b203c4acafccf5",
+            "050e9f3c8fac16b68dbce8f8c4bfbf6617c897f9ada4aa29aa19c8",
+            "344233a6cabb7141d80f3da2fedc311d9646bbb0f505afe403a667",
+            "62cdeeb172ad7ade7aa7d9e069da5790f12331bfa00177787a1d0810c67dc3b2b4",
+            "029bead1b40992327044d409d9a1f3ad8f36c3c452775d557e18bbeb2e8dfcead32d514024",
+        ], "0b0f68fb0c27e03ce9b97565995ed4838cc0581b762ef72b062f6a546419fad7");
+    }
+
+    #[test]
+    fn reject_invalid_handshakes() {
+        let mut initiator: HandshakeState = HandshakeState::new(HandshakePattern::XX, true, &[], key_pair(INIT_STATIC), None).unwrap();
+        let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::XX, false, &[], key_pair(RESP_STATIC), None).unwrap();
+
+        assert_eq!(responder.write_message(&[]), Err(NoiseError::OutOfOrder));
+        assert_eq!(HandshakeState::new(HandshakePattern::IK, true, &[], key_pair(INIT_STATIC), None).err(), Some(NoiseError::MissingKey));
+
+        let message: Vec<u8> = initiator.write_message(&[]).unwrap();
+        assert_eq!(responder.read_message(&message[..31]), Err(NoiseError::MessageTooShort));
+
+        let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::XX, false, &[], key_pair(RESP_STATIC), None).unwrap();
+        responder.read_message(&message).unwrap();
+        let mut message: Vec<u8> = responder.write_message(b"payload").unwrap();
+        message[40] ^= 0x01;
+        assert_eq!(initiator.read_message(&message), Err(NoiseError::DecryptionFailed));
+
+        // different prologues lead to different handshake hashes and failing decryption
+        let mut initiator: HandshakeState = HandshakeState::new(HandshakePattern::IK, true, b"one", key_pair(INIT_STATIC), Some(key_pair(RESP_STATIC).pub_key)).unwrap();
+        let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::IK, false, b"two", key_pair(RESP_STATIC), None).unwrap();
+        let message: Vec<u8> = initiator.write_message(&[]).unwrap();
+        assert_eq!(responder.read_message(&message), Err(NoiseError::DecryptionFailed));
+        assert!(initiator.into_transport().is_err());
+    }
+
+    #[test]
+    fn reject_long_payloads() {
+        let mut initiator: HandshakeState = HandshakeState::new(HandshakePattern::XX, true, &[], key_pair(INIT_STATIC), None).unwrap();
+        let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::XX, false, &[], key_pair(RESP_STATIC), None).unwrap();
+
+        // the first message is "e" and the payload without a tag, the second "e", "s" and the payload with
+        // a tag each, the third "s" and the payload with a tag each, a rejected write changes nothing
+        assert_eq!(initiator.write_message(&[0; 65535 - 32 + 1]), Err(NoiseError::MessageTooLong));
+        responder.read_message(&initiator.write_message(b"e").unwrap()).unwrap();
+        assert_eq!(responder.write_message(&[0; 65535 - 32 - 48 - 16 + 1]), Err(NoiseError::MessageTooLong));
+        initiator.read_message(&responder.write_message(b"e, ee, s, es").unwrap()).unwrap();
+        assert_eq!(initiator.write_message(&[0; 65535 - 48 - 16 + 1]), Err(NoiseError::MessageTooLong));
+        let message: Vec<u8> = initiator.write_message(&[0; 65535 - 48 - 16]).unwrap();
+        assert_eq!(message.len(), 65535);
+        responder.read_message(&message).unwrap();
+
+        let mut initiator: TransportState = initiator.into_transport().unwrap();
+        let mut responder: TransportState = responder.into_transport().unwrap();
+        assert_eq!(initiator.handshake_hash(), responder.handshake_hash());
+        assert_eq!(responder.read_message(&initiator.write_message(b"ping").unwrap()).unwrap(), b"ping");
+    }
+
+    #[test]
+    fn random_ephemeral_keys() {
+        let mut alice: ECDH = ECDH::new();
+        alice.gen_key_pair();
+        let mut bob: ECDH = ECDH::new();
+        bob.gen_key_pair();
+        let bob_pub: [u8; 32] = bob.pub_key;
+
+        let mut initiator: HandshakeState = HandshakeState::new(HandshakePattern::IK, true, &[], alice, Some(bob_pub)).unwrap();
+        let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::IK, false, &[], bob, None).unwrap();
+
+        responder.read_message(&initiator.write_message(&[]).unwrap()).unwrap();
+        initiator.read_message(&responder.write_message(&[]).unwrap()).unwrap();
+        assert!(initiator.is_finished() && responder.is_finished());
+        assert_eq!(initiator.write_message(&[]), Err(NoiseError::HandshakeFinished));
+
+        let mut initiator: TransportState = initiator.into_transport().unwrap();
+        let mut responder: TransportState = responder.into_transport().unwrap();
+        assert_eq!(responder.read_message(&initiator.write_message(b"ping").unwrap()).unwrap(), b"ping");
+        assert_eq!(initiator.read_message(&responder.write_message(b"pong").unwrap()).unwrap(), b"pong");
+
+        initiator.rekey_outgoing();
+        responder.rekey_incoming();
+        assert_eq!(responder.read_message(&initiator.write_message(b"ping").unwrap()).unwrap(), b"ping");
+        assert_eq!(initiator.write_message(&[0; 65520]), Err(NoiseError::MessageTooLong));
+    }
+}
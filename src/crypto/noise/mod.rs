@@ -0,0 +1,13 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod cipher_state;
+mod symmetric_state;
+mod handshake_state;
+mod types;
+
+pub use cipher_state::CipherState;
+pub use handshake_state::{HandshakeState, HandshakePattern, TransportState};
+
+pub use types::NoiseError;
@@ -0,0 +1,116 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to http://www.noiseprotocol.org/noise.html#the-symmetricstate-object
+*/
+
+#![allow(dead_code)]
+
+use super::{CipherState, NoiseError};
+use crate::hash::{Sha256, HKDF, HashType};
+
+// Chaining key ck and handshake hash h for SHA-256.
+pub struct SymmetricState {
+    cipher: CipherState,
+    ck: [u8; 32],
+    h: [u8; 32],
+}
+
+impl SymmetricState {
+    pub fn initialize(protocol_name: &[u8]) -> Self {
+        // names up to HASHLEN bytes are zero padded, longer ones hashed
+        let mut h: [u8; 32] = [0; 32];
+        if protocol_name.len() <= 32 {
+            h[..protocol_name.len()].copy_from_slice(protocol_name);
+        } else {
+            h = Sha256::new().digest(protocol_name);
+        }
+
+        Self { cipher: CipherState::new(), ck: h, h }
+    }
+
+    pub fn mix_key(&mut self, input_key_material: &[u8]) {
+        let (ck, temp_k) = hkdf(&self.ck, input_key_material);
+        self.ck = ck;
+        self.cipher.initialize_key(Some(temp_k));
+    }
+
+    pub fn mix_hash(&mut self, data: &[u8]) {
+        let mut input: Vec<u8> = self.h.to_vec();
+        input.extend_from_slice(data);
+        self.h = Sha256::new().digest(&input);
+    }
+
+    pub fn has_key(&self) -> bool {
+        self.cipher.has_key()
+    }
+
+    pub fn handshake_hash(&self) -> [u8; 32] {
+        self.h
+    }
+
+    pub fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let ciphertext: Vec<u8> = self.cipher.encrypt_with_ad(&self.h, plaintext)?;
+        self.mix_hash(&ciphertext);
+
+        Ok(ciphertext)
+    }
+
+    pub fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let plaintext: Vec<u8> = self.cipher.decrypt_with_ad(&self.h, ciphertext)?;
+        self.mix_hash(ciphertext);
+
+        Ok(plaintext)
+    }
+
+    pub fn split(&self) -> (CipherState, CipherState) {
+        let (k_1, k_2) = hkdf(&self.ck, &[]);
+
+        let mut c_1: CipherState = CipherState::new();
+        c_1.initialize_key(Some(k_1));
+        let mut c_2: CipherState = CipherState::new();
+        c_2.initialize_key(Some(k_2));
+
+        (c_1, c_2)
+    }
+}
+
+fn hkdf(chaining_key: &[u8; 32], input_key_material: &[u8]) -> ([u8; 32], [u8; 32]) {
+    // HKDF of the Noise spec with two outputs, RFC 5869 with the chaining key as salt and empty info
+    let output: Vec<u8> = HKDF::new(HashType::Sha256Type).derive(chaining_key, input_key_material, &[], 64).unwrap();
+
+    (output[..32].try_into().unwrap(), output[32..].try_into().unwrap())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::SymmetricState;
+
+    #[test]
+    fn protocol_name_padding() {
+        // "Noise_XX_25519_ChaChaPoly_SHA256" has exactly 32 bytes and is used as it is
+        let state: SymmetricState = SymmetricState::initialize(b"Noise_XX_25519_ChaChaPoly_SHA256");
+        assert_eq!(&state.handshake_hash(), b"Noise_XX_25519_ChaChaPoly_SHA256");
+
+        let state: SymmetricState = SymmetricState::initialize(b"Noise_N_25519_ChaChaPoly_SHA256");
+        assert_eq!(&state.handshake_hash()[..31], b"Noise_N_25519_ChaChaPoly_SHA256");
+        assert_eq!(state.handshake_hash()[31], 0);
+    }
+
+    #[test]
+    fn encrypt_after_mix_key() {
+        let mut alice: SymmetricState = SymmetricState::initialize(b"Noise_XX_25519_ChaChaPoly_SHA256");
+        let mut bob: SymmetricState = SymmetricState::initialize(b"Noise_XX_25519_ChaChaPoly_SHA256");
+
+        // before mix_key the payload is sent in the clear, but still hashed
+        assert_eq!(alice.encrypt_and_hash(b"hello").unwrap(), b"hello");
+        assert_eq!(bob.decrypt_and_hash(b"hello").unwrap(), b"hello");
+
+        alice.mix_key(&[0x07; 32]);
+        bob.mix_key(&[0x07; 32]);
+        let ciphertext: Vec<u8> = alice.encrypt_and_hash(b"hello").unwrap();
+        assert_eq!(ciphertext.len(), 5 + 16);
+        assert_eq!(bob.decrypt_and_hash(&ciphertext).unwrap(), b"hello");
+        assert_eq!(alice.handshake_hash(), bob.handshake_hash());
+    }
+}
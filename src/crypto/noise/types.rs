@@ -0,0 +1,23 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum NoiseError {
+    // Authentication failed, the handshake or session has to be aborted.
+    DecryptionFailed,
+    // Pattern needs a key that was not given, e.g. the responder's static key for IK.
+    MissingKey,
+    // Message written or read out of turn.
+    OutOfOrder,
+    // Handshake message after the last one of the pattern.
+    HandshakeFinished,
+    // Transport keys requested before the handshake completed.
+    HandshakeNotFinished,
+    // Message shorter than its public keys and tags.
+    MessageTooShort,
+    // Noise messages are limited to 65535 bytes.
+    MessageTooLong,
+    // The nonce 2^64 - 1 is reserved, the session needs new keys.
+    NonceExhausted,
+}
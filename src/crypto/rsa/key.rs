@@ -0,0 +1,431 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8017
+*/
+
+#![allow(dead_code)]
+
+use super::mgf1::{hash, hash_length};
+use super::pss::{emsa_pss_encode, emsa_pss_verify};
+use super::pkcs1v15::emsa_pkcs1_v1_5_encode;
+use super::oaep::{eme_oaep_encode, eme_oaep_decode};
+use super::prime::random_prime;
+use super::RsaError;
+use crate::hash::HashType;
+use crate::utils::bigint::{BigUint, Montgomery};
+use crate::utils::rng::fill_bytes;
+
+pub const PUBLIC_EXPONENT: u64 = 65537;
+
+// n and e of up to N 64-bit limbs.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RsaPublicKey<const N: usize> {
+    pub n: BigUint<N>,
+    pub e: BigUint<N>,
+}
+
+impl<const N: usize> RsaPublicKey<N> {
+    pub fn new(n: &BigUint<N>, e: &BigUint<N>) -> Result<Self, RsaError> {
+        if !n.is_odd() || !e.is_odd() || *e < BigUint::from_u64(3) || e >= n {
+            return Err(RsaError::InvalidKey);
+        }
+
+        Ok(Self { n: *n, e: *e })
+    }
+
+    pub fn from_be_bytes(n: &[u8], e: &[u8]) -> Result<Self, RsaError> {
+        let n: BigUint<N> = BigUint::from_be_bytes(n).map_err(|_| RsaError::InvalidKey)?;
+        let e: BigUint<N> = BigUint::from_be_bytes(e).map_err(|_| RsaError::InvalidKey)?;
+
+        Self::new(&n, &e)
+    }
+
+    // k, the length of the modulus in bytes
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    fn public_operation(&self, input: &BigUint<N>) -> Option<BigUint<N>> {
+        // RSAEP & RSAVP1, the representative has to be below n
+        if *input >= self.n {
+            return None;
+        }
+
+        Some(input.pow_mod(&self.e, &self.n))
+    }
+}
+
+pub struct RSA<const N: usize> {
+    pub pub_key: RsaPublicKey<N>,
+    d: BigUint<N>,
+    p: BigUint<N>,
+    q: BigUint<N>,
+    d_p: BigUint<N>,
+    d_q: BigUint<N>,
+    q_inv: BigUint<N>,
+}
+
+pub type RSA2048 = RSA<32>;
+pub type RSA3072 = RSA<48>;
+pub type RSA4096 = RSA<64>;
+
+impl<const N: usize> Default for RSA<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> RSA<N> {
+    pub fn new() -> Self {
+        Self {
+            pub_key: RsaPublicKey { n: BigUint::zero(), e: BigUint::from_u64(PUBLIC_EXPONENT) },
+            d: BigUint::zero(),
+            p: BigUint::zero(),
+            q: BigUint::zero(),
+            d_p: BigUint::zero(),
+            d_q: BigUint::zero(),
+            q_inv: BigUint::zero(),
+        }
+    }
+
+    pub fn gen_key_pair(&mut self, bits: usize) -> Result<(), RsaError> {
+        // FIPS 186-5 allows 2048 bits and more, smaller keys are only for tests
+        if bits < 512 || bits > 64 * N || bits % 2 == 1 {
+            return Err(RsaError::InvalidKey);
+        }
+
+        let e: BigUint<N> = BigUint::from_u64(PUBLIC_EXPONENT);
+        loop {
+            let p: BigUint<N> = random_prime(bits / 2, &e);
+            let q: BigUint<N> = random_prime(bits / 2, &e);
+
+            if p != q && self.set_secret_key(&p, &q, &e).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+
+    pub fn set_secret_key(&mut self, p: &BigUint<N>, q: &BigUint<N>, e: &BigUint<N>) -> Result<(), RsaError> {
+        let one: BigUint<N> = BigUint::one();
+        let n: BigUint<N> = p.checked_mul(q).ok_or(RsaError::InvalidKey)?;
+        let pub_key: RsaPublicKey<N> = RsaPublicKey::new(&n, e)?;
+
+        // d = e^-1 mod lcm(p - 1, q - 1)
+        let p_minus_1: BigUint<N> = p.overflowing_sub(&one).0;
+        let q_minus_1: BigUint<N> = q.overflowing_sub(&one).0;
+        let lambda: BigUint<N> = p_minus_1.div_rem(&p_minus_1.gcd(&q_minus_1)).0
+            .checked_mul(&q_minus_1)
+            .ok_or(RsaError::InvalidKey)?;
+        let d: BigUint<N> = e.inverse_mod(&lambda).ok_or(RsaError::InvalidKey)?;
+        let q_inv: BigUint<N> = q.inverse_mod(p).ok_or(RsaError::InvalidKey)?;
+
+        self.pub_key = pub_key;
+        self.d_p = d.rem(&p_minus_1);
+        self.d_q = d.rem(&q_minus_1);
+        self.d = d;
+        self.p = *p;
+        self.q = *q;
+        self.q_inv = q_inv;
+
+        Ok(())
+    }
+
+    fn private_operation(&self, input: &BigUint<N>) -> Option<BigUint<N>> {
+        // RSADP & RSASP1 with the Chinese remainder theorem (RFC 8017 section 5.1.2)
+        if *input >= self.pub_key.n {
+            return None;
+        }
+
+        let m_1: BigUint<N> = input.pow_mod(&self.d_p, &self.p);
+        let m_2: BigUint<N> = input.pow_mod(&self.d_q, &self.q);
+        // h = (m_1 - m_2) q_inv mod p, with q_inv in Montgomery form a single constant-time
+        // Montgomery multiplication gives the plain product, mul_mod branches on the bits of q_inv
+        let montgomery: Montgomery<N> = Montgomery::new(&self.p).ok()?;
+        let difference: BigUint<N> = m_1.sub_mod(&m_2.rem(&self.p), &self.p);
+        let h: BigUint<N> = montgomery.mul(&difference, &montgomery.to_montgomery(&self.q_inv));
+        let output: BigUint<N> = m_2.overflowing_add(&self.q.checked_mul(&h)?).0;
+
+        // a faulty CRT half would leak a factor of n (Boneh, DeMillo & Lipton),
+        // so the result is checked with the cheap public operation
+        if self.pub_key.public_operation(&output)? != *input {
+            return None;
+        }
+
+        Some(output)
+    }
+
+    pub fn sign_pss(&self, hash_type: HashType, message: &[u8]) -> Result<Vec<u8>, RsaError> {
+        // salt as long as the hash (RFC 8017 section 9.1, note 4)
+        let mut salt: Vec<u8> = vec![0; hash_length(&hash_type)];
+        fill_bytes(&mut salt);
+
+        self.sign_pss_with_salt(&hash_type, message, &salt)
+    }
+
+    fn sign_pss_with_salt(&self, hash_type: &HashType, message: &[u8], salt: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let em_bits: usize = self.pub_key.n.bits() - 1;
+        let em: Vec<u8> = emsa_pss_encode(hash_type, &hash(hash_type, message), em_bits, salt)?;
+
+        self.sign_encoded(&em)
+    }
+
+    pub fn verify_pss(pub_key: &RsaPublicKey<N>, hash_type: HashType, message: &[u8], signature: &[u8]) -> Result<(), RsaError> {
+        let em_bits: usize = pub_key.n.bits() - 1;
+        let em: Vec<u8> = Self::open_signature(pub_key, signature, em_bits.div_ceil(8))?;
+
+        if emsa_pss_verify(&hash_type, &hash(&hash_type, message), &em, em_bits, hash_length(&hash_type)) {
+            Ok(())
+        } else {
+            Err(RsaError::InvalidSignature)
+        }
+    }
+
+    pub fn sign_pkcs1v15(&self, hash_type: HashType, message: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let em: Vec<u8> = emsa_pkcs1_v1_5_encode(&hash_type, message, self.pub_key.size())?;
+
+        self.sign_encoded(&em)
+    }
+
+    pub fn verify_pkcs1v15(pub_key: &RsaPublicKey<N>, hash_type: HashType, message: &[u8], signature: &[u8]) -> Result<(), RsaError> {
+        // the encoding is deterministic, so it is compared instead of parsed
+        let em: Vec<u8> = Self::open_signature(pub_key, signature, pub_key.size())?;
+
+        if emsa_pkcs1_v1_5_encode(&hash_type, message, pub_key.size())? == em {
+            Ok(())
+        } else {
+            Err(RsaError::InvalidSignature)
+        }
+    }
+
+    pub fn encrypt_oaep(pub_key: &RsaPublicKey<N>, hash_type: HashType, message: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let mut seed: Vec<u8> = vec![0; hash_length(&hash_type)];
+        fill_bytes(&mut seed);
+
+        Self::encrypt_oaep_with_seed(pub_key, &hash_type, message, label, &seed)
+    }
+
+    fn encrypt_oaep_with_seed(pub_key: &RsaPublicKey<N>, hash_type: &HashType, message: &[u8], label: &[u8], seed: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k: usize = pub_key.size();
+        let em: Vec<u8> = eme_oaep_encode(hash_type, message, label, k, seed)?;
+
+        // EM starts with 0x00, so it is always below n
+        let m: BigUint<N> = BigUint::from_be_bytes(&em).map_err(|_| RsaError::MessageTooLong)?;
+        let c: BigUint<N> = pub_key.public_operation(&m).ok_or(RsaError::MessageTooLong)?;
+
+        Ok(c.to_be_bytes(k).unwrap())
+    }
+
+    pub fn decrypt_oaep(&self, hash_type: HashType, ciphertext: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k: usize = self.pub_key.size();
+        if ciphertext.len() != k {
+            return Err(RsaError::DecryptionError);
+        }
+
+        let c: BigUint<N> = BigUint::from_be_bytes(ciphertext).map_err(|_| RsaError::DecryptionError)?;
+        let m: BigUint<N> = self.private_operation(&c).ok_or(RsaError::DecryptionError)?;
+
+        eme_oaep_decode(&hash_type, &m.to_be_bytes(k).unwrap(), label)
+    }
+
+    fn sign_encoded(&self, em: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let m: BigUint<N> = BigUint::from_be_bytes(em).map_err(|_| RsaError::InvalidKey)?;
+        let s: BigUint<N> = self.private_operation(&m).ok_or(RsaError::InvalidKey)?;
+
+        Ok(s.to_be_bytes(self.pub_key.size()).unwrap())
+    }
+
+    fn open_signature(pub_key: &RsaPublicKey<N>, signature: &[u8], em_len: usize) -> Result<Vec<u8>, RsaError> {
+        // RSAVP1 and I2OSP, a signature of the wrong length or not below n is invalid
+        if signature.len() != pub_key.size() {
+            return Err(RsaError::InvalidSignature);
+        }
+
+        let s: BigUint<N> = BigUint::from_be_bytes(signature).map_err(|_| RsaError::InvalidSignature)?;
+        let m: BigUint<N> = pub_key.public_operation(&s).ok_or(RsaError::InvalidSignature)?;
+
+        m.to_be_bytes(em_len).map_err(|_| RsaError::InvalidSignature)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{RSA, RSA2048, RsaPublicKey};
+    use super::super::RsaError;
+    use crate::hash::HashType;
+    use crate::utils::bigint::{BigUint, U1024, U2048};
+    use crate::utils::testing::hex;
+
+    const P: &str = "faae88af35474bfb1b95a099022c95f5e2422ebc61546651bb01102743f4ca3bd2d636cf0b8593cdcb83c51a46616dc758f01de2a7fd2bff9a5696e376428a7bad50e21685f0a61f5a9c697e38396c99408b9b6b56699cd5fe24a85915989c439c295327d0cb81ba9f9aa2379fbc18902de8c41686aedcf533cc462008a34155";
+    const Q: &str = "d1b7784dfa00ddd9c48f539eed9cb91e835b7c3063dd6fd5a67ef776be9337ef57f51a64cd6308cc70a6a32e0fdb4534126bbc034beff336172d5a997f148d6698c6a3f74cbdad4ea0fcf55756f236b54bec809870c74ae59356711d4b26146f142adf9729060ec282ab55ad4037acb710256ed77b88e649f83113df26a18105";
+    const N: &str = "cd5c2628eb1ed364c8882744cbc6d86baac32c61d5c7e18af66ca2a9fe5c1fee7e88ad7103fbd1b5dfb5c7fc5a885842f102235a1e4fd42fbb7f0928d6b43562939e98e542d4833193bd16882ca2d946c3e5251b8da2827674f2e2a32939bb5eaa5ada8546344cb51c437c43ceea92047adb26ad38d01f6a44183b99a032e08fc8f1ac954382f6757b039e2b1f45ce979c663f9e4222db7b55c4f8f126fe8437387a9bec6d37a4ced1ffd461b95cf6cbde4f243c79f0695787c2e40eb17f500fabbb92f16f7972ecd420fd85243d9020bd90cecbc791e0a9edbcdc89e26820a5958b7dbda2ca6744b5e08d2aa3e41c50163c83f386b50a174b15656423911ba9";
+    const MESSAGE: &[u8] = b"payment request #42: 1000 units to alice";
+
+    fn test_key() -> RSA2048 {
+        let mut rsa: RSA2048 = RSA2048::new();
+        rsa.set_secret_key(&U2048::from_hex(P).unwrap(), &U2048::from_hex(Q).unwrap(), &U2048::from_u64(65537)).unwrap();
+
+        rsa
+    }
+
+    // Test-Vectors generated with pyca/cryptography 48.0.0 (OpenSSL), the PSS salt and OAEP seed
+    // are fixed and the encodings cross-checked by verifying and decrypting there
+    #[test]
+    fn pkcs1v15_signatures() {
+        let rsa: RSA2048 = test_key();
+        assert_eq!(rsa.pub_key.n, U2048::from_hex(N).unwrap());
+
+        let signature: Vec<u8> = rsa.sign_pkcs1v15(HashType::Sha256Type, MESSAGE).unwrap();
+        assert_eq!(signature, hex("a99ca4e09cfb5cd86cb60b65064d39fee105c3e749e2fee88560ab58a613c7fe582b411d8c0460bb5c7a0b0dd1f7478c2c1d1b962d76bced5a640132da5f9f59edd7553ca64d8f553401fd02c4287817e15566902c62f55dc5479a439a018309e6d295bb7f2d603dbd4954b84ab7286b25ae4e53bab114033a430276a83acd9e2349a004a6cef20911eeb378c7eb338421abe37bccfe4aa894fe9d7073bf9580c3a82f76d7a1e42715284306eb5ee167c01d534d4b61bf27078ba99c4f18badefa3d4904faf0a802d783e13fedbdedea17ef577299b29ba528d906a72b298e934f1c1cd87968510770b910db3a2f196063b952c4ed23d27152c484f6db4ccb71"));
+        assert!(RSA::verify_pkcs1v15(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature).is_ok());
+
+        let signature: Vec<u8> = rsa.sign_pkcs1v15(HashType::Sha512Type, MESSAGE).unwrap();
+        assert_eq!(signature, hex("2ddc741da2251ec3f71b5c93bddeaa7bd90c591dfed820d4b9e6262791de916e65f6cffdb2bbc3e03111cae704185fb99017a6f72ef8958c4b5c694d0546590c82153c1d0facc0c0ddc33dfa1205a09a7c89eed95df9ca5a45785707e905633075b0d8fcad288dae9a9edf76051df27096ddcb3b34a6809f06311b96b184c0da4a2bcbd8c5a16325dd15ee82208f05671a0e115a1753e6a5fc4a3a4e3704cfe13ffad657c744bf94f189d555dc8e997701090212007d5851978b74417e5cafea118418869116ad29071ddf65756e99eb2abb08430ff62e7b13f3690c190154e916bc24e544cc5132a9ed9638eb16ab09db6e20edd563d0dfb971e9c6c73e7905"));
+        assert!(RSA::verify_pkcs1v15(&rsa.pub_key, HashType::Sha512Type, MESSAGE, &signature).is_ok());
+        assert_eq!(RSA::verify_pkcs1v15(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature), Err(RsaError::InvalidSignature));
+    }
+
+    #[test]
+    fn cavp_pkcs1v15_vector() {
+        // Test-Vector from the CAVP SigGen15_186-3.txt, [mod = 2048], SHA256, first message:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/digital-signatures
+        // The file lists n, e and d, p and q are the factors of n recovered from them.
+        let n: &str = "cea80475324c1dc8347827818da58bac069d3419c614a6ea1ac6a3b510dcd72cc516954905e9fef908d45e13006adf27d467a7d83c111d1a5df15ef293771aefb920032a5bb989f8e4f5e1b05093d3f130f984c07a772a3683f4dc6fb28a96815b32123ccdd13954f19d5b8b24a103e771a34c328755c65ed64e1924ffd04d30b2142cc262f6e0048fef6dbc652f21479ea1c4b1d66d28f4d46ef7185e390cbfa2e02380582f3188bb94ebbf05d31487a09aff01fcbb4cd4bfd1f0a833b38c11813c84360bb53c7d4481031c40bad8713bb6b835cb08098ed15ba31ee4ba728a8c8e10f7294e1b4163b7aee57277bfd881a6f9d43e02c6925aa3a043fb7fb78d";
+        let e: &str = "260445";
+        let p: &str = "f364e16ef12017ec95b192308c01e087cee619ab50a5d537cc01841dc92b30bcef0d9f2c6bbd5dc10bdf5b9f6c354a4f9f210520caa72b4f5c36b8d33f10324c55956141891e45b84b49f59ea5bfac6ffa38900aca5099afcd02f6a8257c41ce5bb2e4153832b5c22f91eb389fa2035c3cf9b3374531c483cb30ceb007259b1d";
+        let q: &str = "d95c0995fabdfcbccfe63e0f3262f806869ab571e1793e97234cbb9bd4b6872a7695389955cf6ce7245345a5df8021f7d9519563afbc2667f5311fad093de2c02cd069109b630d68e3bf767f8a788a6add7ab199f2d8f6a40b7c1910d9dab52ac80d0d333aacab321a9309dc884ddd4db637a0c1115ae3c08efa683f99eb7331";
+        let message: Vec<u8> = hex("5af283b1b76ab2a695d794c23b35ca7371fc779e92ebf589e304c7f923d8cf976304c19818fcd89d6f07c8d8e08bf371068bdf28ae6ee83b2e02328af8c0e2f96e528e16f852f1fc5455e4772e288a68f159ca6bdcf902b858a1f94789b3163823e2d0717ff56689eec7d0e54d93f520d96e1eb04515abc70ae90578ff38d31b");
+        let signature: Vec<u8> = hex("6b8be97d9e518a2ede746ff4a7d91a84a1fc665b52f154a927650db6e7348c69f8c8881f7bcf9b1a6d3366eed30c3aed4e93c203c43f5528a45de791895747ade9c5fa5eee81427edee02082147aa311712a6ad5fb1732e93b3d6cd23ffd46a0b3caf62a8b69957cc68ae39f9993c1a779599cdda949bdaababb77f248fcfeaa44059be5459fb9b899278e929528ee130facd53372ecbc42f3e8de2998425860406440f248d817432de687112e504d734028e6c5620fa282ca07647006cf0a2ff83e19a916554cc61810c2e855305db4e5cf893a6a96767365794556ff033359084d7e38a8456e68e21155b76151314a29875feee09557161cbc654541e89e42");
+
+        let mut rsa: RSA2048 = RSA2048::new();
+        rsa.set_secret_key(&U2048::from_hex(p).unwrap(), &U2048::from_hex(q).unwrap(), &U2048::from_hex(e).unwrap()).unwrap();
+        assert_eq!(rsa.pub_key.n, U2048::from_hex(n).unwrap());
+        assert_eq!(rsa.sign_pkcs1v15(HashType::Sha256Type, &message).unwrap(), signature);
+
+        let pub_key: RsaPublicKey<32> = RsaPublicKey::from_be_bytes(&hex(n), &hex(e)).unwrap();
+        assert!(RSA::verify_pkcs1v15(&pub_key, HashType::Sha256Type, &message, &signature).is_ok());
+        assert_eq!(RSA::verify_pkcs1v15(&pub_key, HashType::Sha512Type, &message, &signature), Err(RsaError::InvalidSignature));
+        assert_eq!(RSA::verify_pkcs1v15(&pub_key, HashType::Sha256Type, &message[1..], &signature), Err(RsaError::InvalidSignature));
+    }
+
+    #[test]
+    fn rfc8017_oaep_vector() {
+        // Test-Vector from oaep-vect.txt of the RSA Laboratories PKCS #1 v2.1 vectors, Example 1.1 (SHA-1, 1024 bit):
+        // https://github.com/pyca/cryptography/tree/main/vectors/cryptography_vectors/asymmetric/RSA/pkcs-1v2-1d2-vec
+        let p: &str = "d32737e7267ffe1341b2d5c0d150a81b586fb3132bed2f8d5262864a9cb9f30af38be448598d413a172efb802c21acf1c11c520c2f26a471dcad212eac7ca39d";
+        let q: &str = "cc8853d1d54da630fac004f471f281c7b8982d8224a490edbeb33d3e3d5cc93c4765703d1dd791642f1f116a0dd852be2419b2af72bfe9a030e860b0288b5d77";
+        let n: Vec<u8> = hex("a8b3b284af8eb50b387034a860f146c4919f318763cd6c5598c8ae4811a1e0abc4c7e0b082d693a5e7fced675cf4668512772c0cbc64a742c6c630f533c8cc72f62ae833c40bf25842e984bb78bdbf97c0107d55bdb662f5c4e0fab9845cb5148ef7392dd3aaff93ae1e6b667bb3d4247616d4f5ba10d4cfd226de88d39f16fb");
+        let message: Vec<u8> = hex("6628194e12073db03ba94cda9ef9532397d50dba79b987004afefe34");
+        let seed: Vec<u8> = hex("18b776ea21069d69776a33e96bad48e1dda0a5ef");
+        let ciphertext: Vec<u8> = hex("354fe67b4a126d5d35fe36c777791a3f7ba13def484e2d3908aff722fad468fb21696de95d0be911c2d3174f8afcc201035f7b6d8e69402de5451618c21a535fa9d7bfc5b8dd9fc243f8cf927db31322d6e881eaa91a996170e657a05a266426d98c88003f8477c1227094a0d9fa1e8c4024309ce1ecccb5210035d47ac72e8a");
+
+        let mut rsa: RSA<16> = RSA::new();
+        rsa.set_secret_key(&U1024::from_hex(p).unwrap(), &U1024::from_hex(q).unwrap(), &U1024::from_u64(65537)).unwrap();
+        assert_eq!(rsa.pub_key.n.to_be_bytes(128).unwrap(), n);
+
+        assert_eq!(RSA::encrypt_oaep_with_seed(&rsa.pub_key, &HashType::Sha1Type, &message, &[], &seed).unwrap(), ciphertext);
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha1Type, &ciphertext, &[]).unwrap(), message);
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha256Type, &ciphertext, &[]), Err(RsaError::DecryptionError));
+    }
+
+    #[test]
+    fn rfc8017_pss_vector() {
+        // Test-Vector from pss-vect.txt of the RSA Laboratories PKCS #1 v2.1 vectors, Example 1.1 (SHA-1, 1024 bit)
+        let p: &str = "e7e8942720a877517273a356053ea2a1bc0c94aa72d55c6e86296b2dfc967948c0a72cbccca7eacb35706e09a1df55a1535bd9b3cc34160b3b6dcd3eda8e6443";
+        let q: &str = "b69dca1cf7d4d7ec81e75b90fcca874abcde123fd2700180aa90479b6e48de8d67ed24f9f19d85ba275874f542cd20dc723e6963364a1f9425452b269a6799fd";
+        let n: Vec<u8> = hex("a56e4a0e701017589a5187dc7ea841d156f2ec0e36ad52a44dfeb1e61f7ad991d8c51056ffedb162b4c0f283a12a88a394dff526ab7291cbb307ceabfce0b1dfd5cd9508096d5b2b8b6df5d671ef6377c0921cb23c270a70e2598e6ff89d19f105acc2d3f0cb35f29280e1386b6f64c4ef22e1e1f20d0ce8cffb2249bd9a2137");
+        let message: Vec<u8> = hex("cdc87da223d786df3b45e0bbbc721326d1ee2af806cc315475cc6f0d9c66e1b62371d45ce2392e1ac92844c310102f156a0d8d52c1f4c40ba3aa65095786cb769757a6563ba958fed0bcc984e8b517a3d5f515b23b8a41e74aa867693f90dfb061a6e86dfaaee64472c00e5f20945729cbebe77f06ce78e08f4098fba41f9d6193c0317e8b60d4b6084acb42d29e3808a3bc372d85e331170fcbf7cc72d0b71c296648b3a4d10f416295d0807aa625cab2744fd9ea8fd223c42537029828bd16be02546f130fd2e33b936d2676e08aed1b73318b750a0167d0");
+        let salt: Vec<u8> = hex("dee959c7e06411361420ff80185ed57f3e6776af");
+        let signature: Vec<u8> = hex("9074308fb598e9701b2294388e52f971faac2b60a5145af185df5287b5ed2887e57ce7fd44dc8634e407c8e0e4360bc226f3ec227f9d9e54638e8d31f5051215df6ebb9c2f9579aa77598a38f914b5b9c1bd83c4e2f9f382a0d0aa3542ffee65984a601bc69eb28deb27dca12c82c2d4c3f66cd500f1ff2b994d8a4e30cbb33c");
+
+        let mut rsa: RSA<16> = RSA::new();
+        rsa.set_secret_key(&U1024::from_hex(p).unwrap(), &U1024::from_hex(q).unwrap(), &U1024::from_u64(65537)).unwrap();
+        assert_eq!(rsa.pub_key.n.to_be_bytes(128).unwrap(), n);
+
+        assert_eq!(rsa.sign_pss_with_salt(&HashType::Sha1Type, &message, &salt).unwrap(), signature);
+        let pub_key: RsaPublicKey<16> = RsaPublicKey::from_be_bytes(&n, &[0x01, 0x00, 0x01]).unwrap();
+        assert!(RSA::verify_pss(&pub_key, HashType::Sha1Type, &message, &signature).is_ok());
+        assert_eq!(RSA::verify_pss(&pub_key, HashType::Sha1Type, &message[1..], &signature), Err(RsaError::InvalidSignature));
+    }
+
+    #[test]
+    fn pss_signatures() {
+        let rsa: RSA2048 = test_key();
+        let salt: Vec<u8> = (0..32).collect();
+
+        let signature: Vec<u8> = rsa.sign_pss_with_salt(&HashType::Sha256Type, MESSAGE, &salt).unwrap();
+        assert_eq!(signature, hex("a7d68634d45833fc2a96ba2afd445b738d8ed5b8c94a4b0c27d11f8124a6214ca587b4c174db94fe9823247f9106424fb22a48eb8b37474c0b5808e4e5fe738f264a0a7dbc6cb0002e80b7c67a1df8920e82816b7b9b7edd50b7a425ef796ee1bd5f42b6602137d58e2a331750ddd39b864afed4b401cb57562447e15344c338bc220ff9374de095cbb3dfe547a15511f8cb04d3d7918759a3d168ab903c8c24ecbf0ab08dd1f7d257c4397a5d2d5ad60e726cba8a45ced30ab248bf0f5b213cc07c83f30480e7903d64f2157cd0c5cfaf67f813a86ae0e7299a656e52ee75b59205a9367fe483cc3ddc156bbce4d73a62c7735f5dd5a9ec2b68623a7e1fc786"));
+        assert!(RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature).is_ok());
+
+        let signature: Vec<u8> = hex("a8e77ae8c4810dc12cc81b36914b5de0616a46a38338ed31efd81e19976a3fb42856889316eb050d30ef34d2d1ebe93fa55c5c2d5def5fb686f69136606401e77dfd3550ecd9c80943cb304919db2bbb936536adb50c8c36673e3f0aa8667b3e3d44b059613cc097aac5b3f73dc43dc11b8c0e50e529d24e0d47b6d70bcd1ea94eb9fed5a1a6506c67f1efe6d7cc05a3865aa69f4f77e19dcec206a61f969317b25574687cba4bdc9d75c367ce00577f41be2fa129c6f86e3f51ce059e29c99d27d212fb30b208482e7ed08986049864c5ed8cd377b15b06cc5f7a4b66f8e522d6f0112de78710a86f5ae0ab2fa80fe2b4a424f7fb5599b1387836371b139d33");
+        assert!(RSA::verify_pss(&rsa.pub_key, HashType::Sha512Type, MESSAGE, &signature).is_ok());
+        assert_eq!(RSA::verify_pss(&rsa.pub_key, HashType::Sha512Type, b"payment request #43", &signature), Err(RsaError::InvalidSignature));
+
+        // fresh salts give different signatures that both verify
+        let signature_1: Vec<u8> = rsa.sign_pss(HashType::Sha256Type, MESSAGE).unwrap();
+        let signature_2: Vec<u8> = rsa.sign_pss(HashType::Sha256Type, MESSAGE).unwrap();
+        assert_ne!(signature_1, signature_2);
+        assert!(RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature_1).is_ok());
+        assert!(RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature_2).is_ok());
+    }
+
+    #[test]
+    fn oaep_encryption() {
+        let rsa: RSA2048 = test_key();
+        let seed: Vec<u8> = (0x40..0x60).collect();
+
+        let ciphertext: Vec<u8> = RSA::encrypt_oaep_with_seed(&rsa.pub_key, &HashType::Sha256Type, MESSAGE, &[], &seed).unwrap();
+        assert_eq!(ciphertext, hex("4f62aee00ac0a8c38c09ce803a4f723a974107497af1fce9162c07babd1357a1331b4f34a5df57252bb0bb32c7c2840770707572f2f0005240fd160cc0e2e2b1ea7d14da8d41ce12548e06f27b97ed31f18e3a767a3e1d6ad488c7b6f77321e8ce88f03bd1be43afecc8101031a28c377c4e74c74faa3457d13983ccc0b97fbdb5d1b57f6eb6f457eee982b437b972f4dba999a45666b71a7ef2e447ea0891729e0653a565b4f16f34f4610934ec3a62c9f9a05a989c881633d9ac8b6627c9666befdd004ece3fbdfb10e2c6299f8c61bf6942d77206bd1cf414fcf0307bbc181d5f6296f353203281c807d5e4806aac08ddcefdfde29da2b27dd6d7177b9264"));
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha256Type, &ciphertext, &[]).unwrap(), MESSAGE);
+
+        let ciphertext: Vec<u8> = hex("240c16a22dbcc4fb28112be2cb7c7a50065b05bc381b41fcc2ccf8d6db7aaeb2744d2e6e7047d0a4c3fa9e674f788ec5c28ebcc55506586d6820aae871c01b217be8eab43fcebf78a7f9475faa7e802170d2f8562beccee407cb53ebb35eaceba5ec9f8ebd9c3b96e45015378ff1c519a2db48c36403bf7bef76d4f0ab716cdc743160b120c6fba727335c91c6b37ca176c4b8ef960cdf32523be24f39e40f947dbb71c846d531394071099cb80f2a945850363862dbaa1795bf3ca93e7997f47330a0c28e157eaba9c66dc0ac899800d2f875dbc9c97e1da077adeee3630224af4274b83e9eb81973fe8d38254c5b6a60c78f968436766029daac9422bfca99");
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha512Type, &ciphertext, b"invoice").unwrap(), MESSAGE);
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha512Type, &ciphertext, b"receipt"), Err(RsaError::DecryptionError));
+
+        let ciphertext: Vec<u8> = RSA::encrypt_oaep(&rsa.pub_key, HashType::Sha512Type, MESSAGE, b"invoice").unwrap();
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha512Type, &ciphertext, b"invoice").unwrap(), MESSAGE);
+
+        // 256 - 2 * 64 - 2 = 126 bytes at most with SHA-512
+        assert_eq!(RSA::encrypt_oaep(&rsa.pub_key, HashType::Sha512Type, &[0; 127], &[]), Err(RsaError::MessageTooLong));
+    }
+
+    #[test]
+    fn reject_invalid_input() {
+        let rsa: RSA2048 = test_key();
+        let signature: Vec<u8> = hex("a99ca4e09cfb5cd86cb60b65064d39fee105c3e749e2fee88560ab58a613c7fe582b411d8c0460bb5c7a0b0dd1f7478c2c1d1b962d76bced5a640132da5f9f59edd7553ca64d8f553401fd02c4287817e15566902c62f55dc5479a439a018309e6d295bb7f2d603dbd4954b84ab7286b25ae4e53bab114033a430276a83acd9e2349a004a6cef20911eeb378c7eb338421abe37bccfe4aa894fe9d7073bf9580c3a82f76d7a1e42715284306eb5ee167c01d534d4b61bf27078ba99c4f18badefa3d4904faf0a802d783e13fedbdedea17ef577299b29ba528d906a72b298e934f1c1cd87968510770b910db3a2f196063b952c4ed23d27152c484f6db4ccb71");
+
+        let mut modified: Vec<u8> = signature.clone();
+        modified[100] ^= 0x01;
+        assert_eq!(RSA::verify_pkcs1v15(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &modified), Err(RsaError::InvalidSignature));
+        assert_eq!(RSA::verify_pkcs1v15(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature[1..]), Err(RsaError::InvalidSignature));
+
+        // a representative not below n
+        let n: Vec<u8> = rsa.pub_key.n.to_be_bytes(256).unwrap();
+        assert_eq!(RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &n), Err(RsaError::InvalidSignature));
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha256Type, &n, &[]), Err(RsaError::DecryptionError));
+
+        let mut ciphertext: Vec<u8> = hex("4f62aee00ac0a8c38c09ce803a4f723a974107497af1fce9162c07babd1357a1331b4f34a5df57252bb0bb32c7c2840770707572f2f0005240fd160cc0e2e2b1ea7d14da8d41ce12548e06f27b97ed31f18e3a767a3e1d6ad488c7b6f77321e8ce88f03bd1be43afecc8101031a28c377c4e74c74faa3457d13983ccc0b97fbdb5d1b57f6eb6f457eee982b437b972f4dba999a45666b71a7ef2e447ea0891729e0653a565b4f16f34f4610934ec3a62c9f9a05a989c881633d9ac8b6627c9666befdd004ece3fbdfb10e2c6299f8c61bf6942d77206bd1cf414fcf0307bbc181d5f6296f353203281c807d5e4806aac08ddcefdfde29da2b27dd6d7177b9264");
+        ciphertext[0] ^= 0x01;
+        assert_eq!(rsa.decrypt_oaep(HashType::Sha256Type, &ciphertext, &[]), Err(RsaError::DecryptionError));
+
+        // even modulus, p * q too wide and e not coprime to lambda
+        assert_eq!(RsaPublicKey::new(&U2048::from_u64(10), &U2048::from_u64(3)), Err(RsaError::InvalidKey));
+        let mut other: RSA2048 = RSA2048::new();
+        let p: U2048 = U2048::from_hex(P).unwrap();
+        assert_eq!(other.set_secret_key(&p, &p.checked_mul(&p).unwrap(), &U2048::from_u64(65537)), Err(RsaError::InvalidKey));
+        assert_eq!(other.set_secret_key(&U2048::from_u64(7), &U2048::from_u64(11), &U2048::from_u64(5)), Err(RsaError::InvalidKey));
+    }
+
+    #[test]
+    fn key_generation() {
+        let mut rsa: RSA<16> = RSA::new();
+        rsa.gen_key_pair(1024).unwrap();
+        assert_eq!(rsa.pub_key.n.bits(), 1024);
+        assert_eq!(rsa.pub_key.e, BigUint::from_u64(65537));
+
+        let signature: Vec<u8> = rsa.sign_pss(HashType::Sha256Type, MESSAGE).unwrap();
+        assert!(RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, MESSAGE, &signature).is_ok());
+
+        assert_eq!(rsa.gen_key_pair(2048), Err(RsaError::InvalidKey));
+    }
+}
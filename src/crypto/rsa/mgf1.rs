@@ -0,0 +1,61 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8017#appendix-B.2.1
+*/
+
+#![allow(dead_code)]
+
+use crate::hash::{HashType, Sha1, Sha256, Sha512};
+
+pub fn hash(hash_type: &HashType, input: &[u8]) -> Vec<u8> {
+    match hash_type {
+        HashType::Sha1Type => Sha1::new().digest(input).to_vec(),
+        HashType::Sha256Type => Sha256::new().digest(input).to_vec(),
+        HashType::Sha512Type => Sha512::new().digest(input).to_vec(),
+    }
+}
+
+pub fn hash_length(hash_type: &HashType) -> usize {
+    match hash_type {
+        HashType::Sha1Type => 20,
+        HashType::Sha256Type => 32,
+        HashType::Sha512Type => 64,
+    }
+}
+
+pub fn mgf1(hash_type: &HashType, seed: &[u8], length: usize) -> Vec<u8> {
+    // T = Hash(seed || C) for C = 0, 1, ... as 4-byte big-endian counter
+    let mut output: Vec<u8> = Vec::with_capacity(length + hash_length(hash_type));
+    let mut counter: u32 = 0;
+    while output.len() < length {
+        let mut input: Vec<u8> = seed.to_vec();
+        input.extend_from_slice(&counter.to_be_bytes());
+        output.extend_from_slice(&hash(hash_type, &input));
+        counter += 1;
+    }
+    output.truncate(length);
+
+    output
+}
+
+pub fn xor_in_place(output: &mut [u8], mask: &[u8]) {
+    for (byte, mask_byte) in output.iter_mut().zip(mask.iter()) {
+        *byte ^= mask_byte;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::mgf1;
+    use crate::hash::HashType;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors generated with Python's hashlib following RFC 8017 appendix B.2.1
+    #[test]
+    fn mgf1_output() {
+        assert_eq!(mgf1(&HashType::Sha256Type, b"seed", 50), hex("336f28a022193939585a1b4edc989f870917f3a5f6ddd16e4fb357084a6bdfc273a649427664d03bbb062e456425488416c5"));
+        assert_eq!(mgf1(&HashType::Sha512Type, b"seed", 100), hex("b76f0d507aafecd10f1a1f9893059f9d691de22082c56b9057c38ea555a506148fda313e51515d18522c4e70066f8adfc773cde314d480b9521773495e3069ad24cb16e3eebfe8444aca93a80cfd96b16a5f0ab3d71fb4c3956089cbb89d9288f2f11ca8"));
+        assert!(mgf1(&HashType::Sha256Type, b"seed", 0).is_empty());
+    }
+}
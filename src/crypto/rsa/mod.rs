@@ -0,0 +1,15 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod mgf1;
+mod prime;
+mod pss;
+mod pkcs1v15;
+mod oaep;
+mod key;
+mod types;
+
+pub use key::{RSA, RSA2048, RSA3072, RSA4096, RsaPublicKey};
+
+pub use types::RsaError;
@@ -0,0 +1,107 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8017#section-7.1
+*/
+
+#![allow(dead_code)]
+
+use super::mgf1::{hash, hash_length, mgf1, xor_in_place};
+use super::RsaError;
+use crate::hash::HashType;
+
+pub fn eme_oaep_encode(hash_type: &HashType, message: &[u8], label: &[u8], k: usize, seed: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let h_len: usize = hash_length(hash_type);
+    if k < 2 * h_len + 2 {
+        return Err(RsaError::ModulusTooShort);
+    }
+    if message.len() > k - 2 * h_len - 2 {
+        return Err(RsaError::MessageTooLong);
+    }
+
+    // DB = lHash || PS || 0x01 || M
+    let mut db: Vec<u8> = hash(hash_type, label);
+    db.resize(k - message.len() - h_len - 2, 0x00);
+    db.push(0x01);
+    db.extend_from_slice(message);
+    xor_in_place(&mut db, &mgf1(hash_type, seed, k - h_len - 1));
+
+    let mut masked_seed: Vec<u8> = seed.to_vec();
+    xor_in_place(&mut masked_seed, &mgf1(hash_type, &db, h_len));
+
+    // EM = 0x00 || maskedSeed || maskedDB
+    let mut output: Vec<u8> = vec![0x00];
+    output.extend_from_slice(&masked_seed);
+    output.extend_from_slice(&db);
+
+    Ok(output)
+}
+
+pub fn eme_oaep_decode(hash_type: &HashType, em: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let h_len: usize = hash_length(hash_type);
+    let k: usize = em.len();
+    if k < 2 * h_len + 2 {
+        return Err(RsaError::DecryptionError);
+    }
+
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+    let mut seed: Vec<u8> = masked_seed.to_vec();
+    xor_in_place(&mut seed, &mgf1(hash_type, masked_db, h_len));
+    let mut db: Vec<u8> = masked_db.to_vec();
+    xor_in_place(&mut db, &mgf1(hash_type, &seed, k - h_len - 1));
+
+    // all checks are collected without early return, so a padding oracle
+    // (Manger's attack) cannot tell which one failed
+    let mut invalid: u8 = em[0];
+    for (byte, expected) in db.iter().zip(hash(hash_type, label).iter()) {
+        invalid |= byte ^ expected;
+    }
+
+    // find the 0x01 separator after the zero padding in one pass over DB
+    let mut looking: u8 = 1;
+    let mut index: usize = 0;
+    for (i, byte) in db[h_len..].iter().enumerate() {
+        let is_one: u8 = (*byte == 0x01) as u8;
+        let is_zero: u8 = (*byte == 0x00) as u8;
+        index |= i & (looking & is_one).wrapping_neg() as usize;
+        invalid |= looking & !is_one & !is_zero & 1;
+        looking &= !is_one & 1;
+    }
+    invalid |= looking;
+
+    if invalid != 0 {
+        return Err(RsaError::DecryptionError);
+    }
+
+    Ok(db[h_len + index + 1..].to_vec())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{eme_oaep_encode, eme_oaep_decode};
+    use super::super::RsaError;
+    use crate::hash::HashType;
+
+    #[test]
+    fn encode_and_decode() {
+        let seed: [u8; 32] = [0x33; 32];
+        let em: Vec<u8> = eme_oaep_encode(&HashType::Sha256Type, b"message", b"label", 256, &seed).unwrap();
+
+        assert_eq!(em.len(), 256);
+        assert_eq!(eme_oaep_decode(&HashType::Sha256Type, &em, b"label").unwrap(), b"message");
+        assert_eq!(eme_oaep_decode(&HashType::Sha256Type, &em, b"other"), Err(RsaError::DecryptionError));
+
+        let mut modified: Vec<u8> = em.clone();
+        modified[0] = 0x01;
+        assert_eq!(eme_oaep_decode(&HashType::Sha256Type, &modified, b"label"), Err(RsaError::DecryptionError));
+
+        // the longest and the empty message
+        let longest: [u8; 190] = [0x11; 190];
+        let em: Vec<u8> = eme_oaep_encode(&HashType::Sha256Type, &longest, &[], 256, &seed).unwrap();
+        assert_eq!(eme_oaep_decode(&HashType::Sha256Type, &em, &[]).unwrap(), longest);
+        let em: Vec<u8> = eme_oaep_encode(&HashType::Sha256Type, &[], &[], 256, &seed).unwrap();
+        assert!(eme_oaep_decode(&HashType::Sha256Type, &em, &[]).unwrap().is_empty());
+
+        assert_eq!(eme_oaep_encode(&HashType::Sha256Type, &[0x11; 191], &[], 256, &seed), Err(RsaError::MessageTooLong));
+    }
+}
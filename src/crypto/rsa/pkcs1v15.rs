@@ -0,0 +1,60 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8017#section-9.2
+*/
+
+#![allow(dead_code)]
+
+use super::mgf1::hash;
+use super::RsaError;
+use crate::hash::HashType;
+
+// DER encoded DigestInfo prefixes of RFC 8017 section 9.2, note 1
+const SHA1_DIGEST_INFO: [u8; 15] = [0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14];
+const SHA256_DIGEST_INFO: [u8; 19] = [0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20];
+const SHA512_DIGEST_INFO: [u8; 19] = [0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40];
+
+pub fn emsa_pkcs1_v1_5_encode(hash_type: &HashType, message: &[u8], em_len: usize) -> Result<Vec<u8>, RsaError> {
+    // T = DigestInfo || Hash(M)
+    let mut t: Vec<u8> = match hash_type {
+        HashType::Sha1Type => SHA1_DIGEST_INFO.to_vec(),
+        HashType::Sha256Type => SHA256_DIGEST_INFO.to_vec(),
+        HashType::Sha512Type => SHA512_DIGEST_INFO.to_vec(),
+    };
+    t.extend_from_slice(&hash(hash_type, message));
+
+    // at least 8 bytes of 0xff padding
+    if em_len < t.len() + 11 {
+        return Err(RsaError::ModulusTooShort);
+    }
+
+    // EM = 0x00 || 0x01 || PS || 0x00 || T
+    let mut output: Vec<u8> = vec![0x00, 0x01];
+    output.resize(em_len - t.len() - 1, 0xff);
+    output.push(0x00);
+    output.extend_from_slice(&t);
+
+    Ok(output)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::emsa_pkcs1_v1_5_encode;
+    use super::super::RsaError;
+    use crate::hash::HashType;
+
+    #[test]
+    fn encoding_layout() {
+        let em: Vec<u8> = emsa_pkcs1_v1_5_encode(&HashType::Sha256Type, b"message", 256).unwrap();
+
+        assert_eq!(em.len(), 256);
+        assert_eq!(&em[..2], &[0x00, 0x01]);
+        assert!(em[2..256 - 52].iter().all(|byte| *byte == 0xff));
+        assert_eq!(em[256 - 52], 0x00);
+
+        // 19 bytes DigestInfo + 64 bytes hash + 11 bytes
+        assert_eq!(emsa_pkcs1_v1_5_encode(&HashType::Sha512Type, b"message", 94).unwrap().len(), 94);
+        assert_eq!(emsa_pkcs1_v1_5_encode(&HashType::Sha512Type, b"message", 93), Err(RsaError::ModulusTooShort));
+    }
+}
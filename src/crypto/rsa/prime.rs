@@ -0,0 +1,156 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf (B.3.1 Miller-Rabin & A.1.3 random probable primes)
+*/
+
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use crate::utils::bigint::{BigUint, Montgomery};
+use crate::utils::rng::fill_bytes;
+
+// Error probability of at most 4^-40 for a composite candidate.
+pub const MILLER_RABIN_ROUNDS: usize = 40;
+
+static SMALL_PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+
+fn small_primes() -> &'static Vec<u64> {
+    // sieve of Eratosthenes below 2^10 for trial division
+    SMALL_PRIMES.get_or_init(|| {
+        let mut composite: [bool; 1024] = [false; 1024];
+        let mut primes: Vec<u64> = Vec::new();
+        for i in 2..1024 {
+            if !composite[i] {
+                primes.push(i as u64);
+                for j in (i * i..1024).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+
+        primes
+    })
+}
+
+fn random_below<const N: usize>(bits: usize) -> BigUint<N> {
+    let mut bytes: Vec<u8> = vec![0; 8 * N];
+    fill_bytes(&mut bytes);
+
+    BigUint::from_be_bytes(&bytes).unwrap().shr(64 * N - bits)
+}
+
+pub fn is_probable_prime<const N: usize>(candidate: &BigUint<N>, rounds: usize) -> bool {
+    for prime in small_primes().iter() {
+        if *candidate == BigUint::from_u64(*prime) {
+            return true;
+        }
+        if candidate.div_rem_small(*prime).1 == 0 {
+            return false;
+        }
+    }
+    if *candidate < BigUint::from_u64(1024) {
+        return false;
+    }
+
+    // candidate - 1 = 2^s * d with d odd
+    let minus_one: BigUint<N> = candidate.overflowing_sub(&BigUint::one()).0;
+    let s: usize = (0..minus_one.bits()).find(|i| minus_one.bit(*i)).unwrap();
+    let d: BigUint<N> = minus_one.shr(s);
+
+    let montgomery: Montgomery<N> = Montgomery::new(candidate).unwrap();
+    let one: BigUint<N> = montgomery.one();
+    let minus_one: BigUint<N> = montgomery.to_montgomery(&minus_one);
+    let three: BigUint<N> = BigUint::from_u64(3);
+
+    for _ in 0..rounds {
+        // base in [2, candidate - 2]
+        let base: BigUint<N> = random_below::<N>(candidate.bits())
+            .rem(&candidate.overflowing_sub(&three).0)
+            .overflowing_add(&BigUint::from_u64(2)).0;
+
+        let mut x: BigUint<N> = montgomery.pow(&montgomery.to_montgomery(&base), &d);
+        if x == one || x == minus_one {
+            continue;
+        }
+
+        let mut composite: bool = true;
+        for _ in 1..s {
+            x = montgomery.square(&x);
+            if x == minus_one {
+                composite = false;
+                break;
+            }
+        }
+
+        if composite {
+            return false;
+        }
+    }
+
+    true
+}
+
+pub fn random_prime<const N: usize>(bits: usize, e: &BigUint<N>) -> BigUint<N> {
+    // the two top bits are set, so the product of two such primes has exactly 2 * bits bits,
+    // and p - 1 has to be coprime to e for d to exist
+    loop {
+        let mut candidate: BigUint<N> = random_below::<N>(bits);
+        candidate.set_bit(bits - 1);
+        candidate.set_bit(bits - 2);
+        candidate.set_bit(0);
+
+        if is_probable_prime(&candidate, MILLER_RABIN_ROUNDS)
+            && e.gcd(&candidate.overflowing_sub(&BigUint::one()).0) == BigUint::one() {
+            return candidate;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{is_probable_prime, random_prime, MILLER_RABIN_ROUNDS};
+    use crate::utils::bigint::{U256, U2048};
+
+    #[test]
+    fn miller_rabin() {
+        // p of Curve25519 and 2^127 - 1 are prime, the Carmichael number 561 and
+        // 2^128 + 1 = 59649589127497217 * 5704689200685129054721 are not
+        let p: U256 = U256::from_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed").unwrap();
+        let mersenne: U256 = U256::from_hex("7fffffffffffffffffffffffffffffff").unwrap();
+        let fermat: U256 = U256::from_hex("100000000000000000000000000000001").unwrap();
+
+        assert!(is_probable_prime(&p, MILLER_RABIN_ROUNDS));
+        assert!(is_probable_prime(&mersenne, MILLER_RABIN_ROUNDS));
+        assert!(is_probable_prime(&U256::from_u64(1021), MILLER_RABIN_ROUNDS));
+        assert!(!is_probable_prime(&U256::from_u64(561), MILLER_RABIN_ROUNDS));
+        assert!(!is_probable_prime(&fermat, MILLER_RABIN_ROUNDS));
+        assert!(!is_probable_prime(&mersenne.checked_mul(&mersenne).unwrap(), MILLER_RABIN_ROUNDS));
+        assert!(!is_probable_prime(&U256::one(), MILLER_RABIN_ROUNDS));
+        assert!(!is_probable_prime(&U256::zero(), MILLER_RABIN_ROUNDS));
+
+        // 2048-bit MODP prime of RFC 3526, section 3
+        let modp: U2048 = U2048::from_hex(concat!(
+            "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+            "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+            "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+            "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+            "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+            "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+            "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+            "3995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff",
+        )).unwrap();
+        assert!(is_probable_prime(&modp, 4));
+    }
+
+    #[test]
+    fn random_primes() {
+        let e: U256 = U256::from_u64(65537);
+        let prime: U256 = random_prime(128, &e);
+
+        assert_eq!(prime.bits(), 128);
+        assert!(prime.bit(126));
+        assert!(is_probable_prime(&prime, MILLER_RABIN_ROUNDS));
+    }
+}
@@ -0,0 +1,98 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8017#section-9.1
+*/
+
+#![allow(dead_code)]
+
+use super::mgf1::{hash, mgf1, xor_in_place};
+use super::RsaError;
+use crate::hash::HashType;
+
+pub fn emsa_pss_encode(hash_type: &HashType, message_hash: &[u8], em_bits: usize, salt: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let h_len: usize = message_hash.len();
+    let em_len: usize = em_bits.div_ceil(8);
+    if em_len < h_len + salt.len() + 2 {
+        return Err(RsaError::ModulusTooShort);
+    }
+
+    // H = Hash(0x00 * 8 || mHash || salt)
+    let mut m_prime: Vec<u8> = vec![0; 8];
+    m_prime.extend_from_slice(message_hash);
+    m_prime.extend_from_slice(salt);
+    let h: Vec<u8> = hash(hash_type, &m_prime);
+
+    // DB = PS || 0x01 || salt, masked with MGF1(H)
+    let mut db: Vec<u8> = vec![0; em_len - salt.len() - h_len - 2];
+    db.push(0x01);
+    db.extend_from_slice(salt);
+    xor_in_place(&mut db, &mgf1(hash_type, &h, em_len - h_len - 1));
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    // EM = maskedDB || H || 0xbc
+    let mut output: Vec<u8> = db;
+    output.extend_from_slice(&h);
+    output.push(0xbc);
+
+    Ok(output)
+}
+
+pub fn emsa_pss_verify(hash_type: &HashType, message_hash: &[u8], em: &[u8], em_bits: usize, salt_length: usize) -> bool {
+    let h_len: usize = message_hash.len();
+    let em_len: usize = em_bits.div_ceil(8);
+    if em.len() != em_len || em_len < h_len + salt_length + 2 || em[em_len - 1] != 0xbc {
+        return false;
+    }
+
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let top_bits: u8 = 0xff >> (8 * em_len - em_bits);
+    if masked_db[0] & !top_bits != 0 {
+        return false;
+    }
+
+    let mut db: Vec<u8> = masked_db.to_vec();
+    xor_in_place(&mut db, &mgf1(hash_type, h, em_len - h_len - 1));
+    db[0] &= top_bits;
+
+    // DB has to be zero bytes, 0x01 and the salt
+    let ps_length: usize = em_len - h_len - salt_length - 2;
+    if db[..ps_length].iter().any(|byte| *byte != 0) || db[ps_length] != 0x01 {
+        return false;
+    }
+
+    let mut m_prime: Vec<u8> = vec![0; 8];
+    m_prime.extend_from_slice(message_hash);
+    m_prime.extend_from_slice(&db[ps_length + 1..]);
+
+    hash(hash_type, &m_prime) == h
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{emsa_pss_encode, emsa_pss_verify};
+    use super::super::mgf1::hash;
+    use super::super::RsaError;
+    use crate::hash::HashType;
+
+    #[test]
+    fn encode_and_verify() {
+        let message_hash: Vec<u8> = hash(&HashType::Sha256Type, b"message");
+        let salt: [u8; 32] = [0x5a; 32];
+
+        for em_bits in [2047, 2048, 1025] {
+            let em: Vec<u8> = emsa_pss_encode(&HashType::Sha256Type, &message_hash, em_bits, &salt).unwrap();
+            assert_eq!(em.len(), em_bits.div_ceil(8));
+            assert!(emsa_pss_verify(&HashType::Sha256Type, &message_hash, &em, em_bits, 32));
+            assert!(!emsa_pss_verify(&HashType::Sha256Type, &message_hash, &em, em_bits, 31));
+            assert!(!emsa_pss_verify(&HashType::Sha512Type, &message_hash, &em, em_bits, 32));
+
+            let mut modified: Vec<u8> = em.clone();
+            modified[10] ^= 0x01;
+            assert!(!emsa_pss_verify(&HashType::Sha256Type, &message_hash, &modified, em_bits, 32));
+        }
+
+        // 2 + 32 + 32 bytes do not fit into 65 bytes
+        assert_eq!(emsa_pss_encode(&HashType::Sha256Type, &message_hash, 520, &salt), Err(RsaError::ModulusTooShort));
+    }
+}
@@ -0,0 +1,17 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum RsaError {
+    // Message longer than the padding allows for the modulus (RFC 8017 sections 7.1.1 and 7.2.1).
+    MessageTooLong,
+    // Modulus too short for the hash and the padding (RFC 8017 sections 9.1.1 and 9.2).
+    ModulusTooShort,
+    // Signature that does not verify for the given public key and message.
+    InvalidSignature,
+    // Ciphertext that does not decrypt, on purpose without the reason (RFC 8017 section 7.1.2, note).
+    DecryptionError,
+    // Key components that are inconsistent or do not fit into the fixed width.
+    InvalidKey,
+}
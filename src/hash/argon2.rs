@@ -0,0 +1,422 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc9106
+*/
+
+#![allow(dead_code)]
+
+use super::{Blake2b, PasswordHashError};
+use crate::utils::{base64, rng};
+
+const VERSION: u32 = 0x13;
+const ARGON2ID: u32 = 2;
+
+const SYNC_POINTS: usize = 4;
+const BLOCK_WORDS: usize = 128;
+
+const SALT_LENGTH: usize = 16;
+const TAG_LENGTH: usize = 32;
+
+type Block = [u64; BLOCK_WORDS];
+
+fn h_prime(input: &[u8], output_length: usize) -> Vec<u8> {
+    // variable-length hash H', chained 64-byte BLAKE2b outputs of which the first halves are kept
+    let mut message: Vec<u8> = (output_length as u32).to_le_bytes().to_vec();
+    message.extend_from_slice(input);
+
+    if output_length <= 64 {
+        return Blake2b::new().digest(&message, output_length);
+    }
+
+    let r: usize = output_length.div_ceil(32) - 2;
+    let mut v: Vec<u8> = Blake2b::new().digest(&message, 64);
+    let mut output: Vec<u8> = Vec::with_capacity(output_length);
+    for _ in 1..r {
+        output.extend_from_slice(&v[..32]);
+        v = Blake2b::new().digest(&v, 64);
+    }
+    output.extend_from_slice(&v[..32]);
+    output.extend_from_slice(&Blake2b::new().digest(&v, output_length - 32 * r));
+
+    output
+}
+
+fn g_b(v: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    // BLAKE2b round function with the additional 2 * lo(a) * lo(b) term
+    fn f(x: u64, y: u64) -> u64 {
+        x.wrapping_add(y).wrapping_add(2u64.wrapping_mul(x & 0xffffffff).wrapping_mul(y & 0xffffffff))
+    }
+
+    v[a] = f(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = f(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = f(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = f(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn permutation(v: &mut Block, index: [usize; 16]) {
+    // P on eight 16-byte registers, given as the positions of their 16 words in the block
+    let i: [usize; 16] = index;
+    g_b(v, i[0], i[4], i[8], i[12]);
+    g_b(v, i[1], i[5], i[9], i[13]);
+    g_b(v, i[2], i[6], i[10], i[14]);
+    g_b(v, i[3], i[7], i[11], i[15]);
+    g_b(v, i[0], i[5], i[10], i[15]);
+    g_b(v, i[1], i[6], i[11], i[12]);
+    g_b(v, i[2], i[7], i[8], i[13]);
+    g_b(v, i[3], i[4], i[9], i[14]);
+}
+
+fn compress(x: &Block, y: &Block) -> Block {
+    // G(X, Y) = P(columns of P(rows of R)) xor R with R = X xor Y
+    let mut r: Block = [0; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        r[i] = x[i] ^ y[i];
+    }
+
+    let mut z: Block = r;
+    for row in 0..8 {
+        permutation(&mut z, core::array::from_fn(|i| 16 * row + i));
+    }
+    for column in 0..8 {
+        permutation(&mut z, core::array::from_fn(|i| 2 * column + 16 * (i / 2) + i % 2));
+    }
+
+    for i in 0..BLOCK_WORDS {
+        z[i] ^= r[i];
+    }
+
+    z
+}
+
+fn block_from_bytes(input: &[u8]) -> Block {
+    core::array::from_fn(|i| u64::from_le_bytes(input[8 * i..8 * i + 8].try_into().unwrap()))
+}
+
+// Argon2id with memory in KiB, i.e. the number of 1024-byte blocks.
+pub struct Argon2id {
+    memory: u32,
+    iterations: u32,
+    parallelism: u32,
+    secret: Vec<u8>,
+    associated_data: Vec<u8>,
+}
+
+impl Argon2id {
+    pub fn new(memory: u32, iterations: u32, parallelism: u32) -> Result<Self, PasswordHashError> {
+        if parallelism == 0 || parallelism > 0xffffff || iterations == 0 || memory < 8 * parallelism {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+
+        Ok(Self { memory, iterations, parallelism, secret: Vec::new(), associated_data: Vec::new() })
+    }
+
+    pub fn set_secret(&mut self, secret: &[u8]) {
+        self.secret = secret.to_vec();
+    }
+
+    pub fn set_associated_data(&mut self, associated_data: &[u8]) {
+        self.associated_data = associated_data.to_vec();
+    }
+
+    pub fn hash(&self, password: &[u8], salt: &[u8], output_length: usize) -> Result<Vec<u8>, PasswordHashError> {
+        if salt.len() < 8 {
+            return Err(PasswordHashError::SaltTooShort);
+        }
+        if output_length < 4 || output_length > u32::MAX as usize {
+            return Err(PasswordHashError::InvalidOutputLength);
+        }
+
+        let h_0: Vec<u8> = self.initial_hash(password, salt, output_length);
+
+        // m' = 4 * p * floor(m / 4p) blocks in p lanes of q columns and four slices
+        let lanes: usize = self.parallelism as usize;
+        let columns: usize = self.memory as usize / (SYNC_POINTS * lanes) * SYNC_POINTS;
+        let segment_length: usize = columns / SYNC_POINTS;
+        let mut memory: Vec<Block> = vec![[0; BLOCK_WORDS]; lanes * columns];
+
+        for lane in 0..lanes {
+            for column in 0..2 {
+                let mut input: Vec<u8> = h_0.clone();
+                input.extend_from_slice(&(column as u32).to_le_bytes());
+                input.extend_from_slice(&(lane as u32).to_le_bytes());
+                memory[lane * columns + column] = block_from_bytes(&h_prime(&input, 1024));
+            }
+        }
+
+        // the lanes of a slice are independent, they are computed one after another here
+        for pass in 0..self.iterations as usize {
+            for slice in 0..SYNC_POINTS {
+                for lane in 0..lanes {
+                    self.fill_segment(&mut memory, pass, slice, lane, columns, segment_length);
+                }
+            }
+        }
+
+        // C = xor of the last column, tag = H'^T(C)
+        let mut c: Block = memory[columns - 1];
+        for lane in 1..lanes {
+            for (x, y) in c.iter_mut().zip(memory[lane * columns + columns - 1].iter()) {
+                *x ^= y;
+            }
+        }
+        let c_bytes: Vec<u8> = c.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+        Ok(h_prime(&c_bytes, output_length))
+    }
+
+    pub fn hash_encoded(&self, password: &[u8]) -> Result<String, PasswordHashError> {
+        let mut salt: [u8; SALT_LENGTH] = [0; SALT_LENGTH];
+        rng::fill_bytes(&mut salt);
+
+        self.hash_encoded_with_salt(password, &salt)
+    }
+
+    pub fn verify_encoded(&self, encoded: &str, password: &[u8]) -> Result<bool, PasswordHashError> {
+        // parameters come from the string, secret and associated data from self,
+        // a stored string must not ask for more memory, passes or lanes than self
+        let (mut argon2, salt, expected) = decode_phc(encoded)?;
+        if argon2.memory > self.memory || argon2.iterations > self.iterations || argon2.parallelism > self.parallelism {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+        argon2.set_secret(&self.secret);
+        argon2.set_associated_data(&self.associated_data);
+        let tag: Vec<u8> = argon2.hash(password, &salt, expected.len())?;
+
+        let difference: u8 = tag.iter().zip(expected.iter()).fold(0, |acc, (x, y)| acc | (x ^ y));
+
+        Ok(difference == 0)
+    }
+
+    fn hash_encoded_with_salt(&self, password: &[u8], salt: &[u8]) -> Result<String, PasswordHashError> {
+        // $argon2id$v=19$m=<memory>,t=<iterations>,p=<parallelism>$<salt>$<tag>, base64 without padding
+        let tag: Vec<u8> = self.hash(password, salt, TAG_LENGTH)?;
+
+        Ok(format!(
+            "$argon2id$v={}$m={},t={},p={}${}${}",
+            VERSION, self.memory, self.iterations, self.parallelism, base64::encode(salt, false), base64::encode(&tag, false),
+        ))
+    }
+
+    fn initial_hash(&self, password: &[u8], salt: &[u8], output_length: usize) -> Vec<u8> {
+        // H0 over the parameters and the length prefixed inputs
+        let mut input: Vec<u8> = Vec::new();
+        for value in [self.parallelism, output_length as u32, self.memory, self.iterations, VERSION, ARGON2ID] {
+            input.extend_from_slice(&value.to_le_bytes());
+        }
+        for data in [password, salt, &self.secret, &self.associated_data] {
+            input.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            input.extend_from_slice(data);
+        }
+
+        Blake2b::new().digest(&input, 64)
+    }
+
+    fn fill_segment(&self, memory: &mut [Block], pass: usize, slice: usize, lane: usize, columns: usize, segment_length: usize) {
+        // Argon2id uses data-independent addresses in the first half of the first pass
+        let data_independent: bool = pass == 0 && slice < SYNC_POINTS / 2;
+        let mut input_block: Block = [0; BLOCK_WORDS];
+        let mut address_block: Block = [0; BLOCK_WORDS];
+        if data_independent {
+            input_block[..6].copy_from_slice(&[
+                pass as u64,
+                lane as u64,
+                slice as u64,
+                memory.len() as u64,
+                self.iterations as u64,
+                ARGON2ID as u64,
+            ]);
+        }
+
+        // the first two blocks of every lane are already set
+        let start: usize = if pass == 0 && slice == 0 { 2 } else { 0 };
+        if data_independent && start != 0 {
+            address_block = next_addresses(&mut input_block);
+        }
+
+        for index in start..segment_length {
+            let column: usize = slice * segment_length + index;
+            let previous: usize = lane * columns + if column == 0 { columns - 1 } else { column - 1 };
+
+            let pseudo_random: u64 = if data_independent {
+                if index % BLOCK_WORDS == 0 {
+                    address_block = next_addresses(&mut input_block);
+                }
+                address_block[index % BLOCK_WORDS]
+            } else {
+                memory[previous][0]
+            };
+
+            let reference: usize = self.reference_block(pseudo_random, pass, slice, lane, index, columns, segment_length);
+            let new_block: Block = compress(&memory[previous], &memory[reference]);
+
+            // later passes xor into the block of the previous pass
+            let current: &mut Block = &mut memory[lane * columns + column];
+            if pass == 0 {
+                *current = new_block;
+            } else {
+                for (x, y) in current.iter_mut().zip(new_block.iter()) {
+                    *x ^= y;
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn reference_block(&self, pseudo_random: u64, pass: usize, slice: usize, lane: usize, index: usize, columns: usize, segment_length: usize) -> usize {
+        let j_1: u64 = pseudo_random & 0xffffffff;
+        let j_2: u64 = pseudo_random >> 32;
+
+        // the first slice of the first pass can only reference its own lane
+        let reference_lane: usize = if pass == 0 && slice == 0 { lane } else { (j_2 % self.parallelism as u64) as usize };
+        let same_lane: bool = reference_lane == lane;
+
+        // finished segments, plus the finished blocks of the current segment in the same lane,
+        // without the block right before the current one
+        let finished: usize = if pass == 0 { slice * segment_length } else { columns - segment_length };
+        let area: usize = if same_lane {
+            finished + index - 1
+        } else if index == 0 {
+            finished - 1
+        } else {
+            finished
+        };
+
+        // non-uniform mapping that prefers recent blocks
+        let x: u64 = (j_1 * j_1) >> 32;
+        let y: u64 = (area as u64 * x) >> 32;
+        let relative: usize = area - 1 - y as usize;
+
+        let start: usize = if pass == 0 || slice == SYNC_POINTS - 1 { 0 } else { (slice + 1) * segment_length };
+
+        reference_lane * columns + (start + relative) % columns
+    }
+}
+
+impl Default for Argon2id {
+    fn default() -> Self {
+        // second recommended option of RFC 9106 section 4: t = 3, p = 4 and 64 MiB
+        Self::new(1 << 16, 3, 4).unwrap()
+    }
+}
+
+fn next_addresses(input_block: &mut Block) -> Block {
+    // the counter in word 6 starts at 1, the addresses are G(0, G(0, input))
+    input_block[6] += 1;
+    let zero: Block = [0; BLOCK_WORDS];
+
+    compress(&zero, &compress(&zero, input_block))
+}
+
+fn decode_phc(encoded: &str) -> Result<(Argon2id, Vec<u8>, Vec<u8>), PasswordHashError> {
+    let fields: Vec<&str> = encoded.split('$').collect();
+    if fields.len() != 6 || !fields[0].is_empty() || fields[1] != "argon2id" || fields[2] != format!("v={}", VERSION) {
+        return Err(PasswordHashError::InvalidEncoding);
+    }
+
+    let parameters: Vec<&str> = fields[3].split(',').collect();
+    if parameters.len() != 3 {
+        return Err(PasswordHashError::InvalidEncoding);
+    }
+    let mut values: [u32; 3] = [0; 3];
+    for (value, (parameter, name)) in values.iter_mut().zip(parameters.iter().zip(["m=", "t=", "p="])) {
+        *value = parameter
+            .strip_prefix(name)
+            .and_then(|x| x.parse::<u32>().ok())
+            .ok_or(PasswordHashError::InvalidEncoding)?;
+    }
+
+    let salt: Vec<u8> = base64::decode(fields[4]).ok_or(PasswordHashError::InvalidEncoding)?;
+    let tag: Vec<u8> = base64::decode(fields[5]).ok_or(PasswordHashError::InvalidEncoding)?;
+
+    Ok((Argon2id::new(values[0], values[1], values[2])?, salt, tag))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Argon2id;
+    use super::super::PasswordHashError;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc9106#section-5.3
+    #[test]
+    fn argon2id_rfc() {
+        let mut argon2: Argon2id = Argon2id::new(32, 3, 4).unwrap();
+        argon2.set_secret(&[0x03; 8]);
+        argon2.set_associated_data(&[0x04; 12]);
+
+        assert_eq!(argon2.hash(&[0x01; 32], &[0x02; 16], 32).unwrap(), hex("0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"));
+    }
+
+    // Test-Vectors generated with pyca/cryptography
+    #[test]
+    fn argon2id_parameters() {
+        let argon2: Argon2id = Argon2id::new(64, 2, 1).unwrap();
+        assert_eq!(argon2.hash(b"password", b"somesalt", 32).unwrap(), hex("16a1a498734609dd01456da406de9f3d9da93e6c86c300a12fc1465214ce4922"));
+
+        // memory is rounded down to a multiple of 4p blocks
+        let argon2: Argon2id = Argon2id::new(24, 4, 3).unwrap();
+        assert_eq!(argon2.hash(b"hunter2", b"diffsalt", 16).unwrap(), hex("b0f100c86280247f3abb6c07cbf26d95"));
+
+        // tags longer than 64 bytes use the chained form of H'
+        let argon2: Argon2id = Argon2id::new(16, 1, 2).unwrap();
+        let tag: Vec<u8> = hex("c57076186ed7c6aafea077bc6be968d080cd7ea84b4f9f4eb7a3bc771bdb9e56ae0b431622a2d69dc6182972946b41da41f3c00034902edc5cfd0cfa6e26f7bb1994876e17a12c5830ae782bebd118e793f63abc5984a33b6d046ff9b0dc564e31dc16ce");
+        assert_eq!(argon2.hash(&[], b"saltsalt", 100).unwrap(), tag);
+    }
+
+    // Test-Vectors generated with pyca/cryptography
+    #[test]
+    fn phc_string_format() {
+        let argon2: Argon2id = Argon2id::new(32, 3, 4).unwrap();
+        let encoded: &str = "$argon2id$v=19$m=32,t=3,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA";
+
+        assert_eq!(argon2.hash_encoded_with_salt(b"correct horse battery staple", &[0x02; 16]).unwrap(), encoded);
+        assert_eq!(argon2.verify_encoded(encoded, b"correct horse battery staple"), Ok(true));
+        assert_eq!(argon2.verify_encoded(encoded, b"wrong password"), Ok(false));
+
+        // parameters are taken from the string, not from the verifying instance
+        let stored: String = Argon2id::new(8, 1, 1).unwrap().hash_encoded(b"passphrase").unwrap();
+        assert!(stored.starts_with("$argon2id$v=19$m=8,t=1,p=1$"));
+        assert_eq!(argon2.verify_encoded(&stored, b"passphrase"), Ok(true));
+
+        // a secret that was used for hashing is needed for verification
+        let mut keyed: Argon2id = Argon2id::new(32, 3, 4).unwrap();
+        keyed.set_secret(b"pepper");
+        assert_eq!(keyed.verify_encoded(encoded, b"correct horse battery staple"), Ok(false));
+    }
+
+    #[test]
+    fn reject_invalid_inputs() {
+        assert!(matches!(Argon2id::new(31, 3, 4), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Argon2id::new(32, 0, 4), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Argon2id::new(32, 3, 0), Err(PasswordHashError::InvalidParameters)));
+
+        let argon2: Argon2id = Argon2id::new(32, 3, 4).unwrap();
+        assert_eq!(argon2.hash(b"password", b"short", 32), Err(PasswordHashError::SaltTooShort));
+        assert_eq!(argon2.hash(b"password", b"somesalt", 3), Err(PasswordHashError::InvalidOutputLength));
+
+        for encoded in [
+            "$argon2i$v=19$m=32,t=3,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+            "$argon2id$v=16$m=32,t=3,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+            "$argon2id$v=19$t=3,m=32,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+            "$argon2id$v=19$m=32,t=3,p=4$AgICAgICAgICAgICAgICAg",
+            "$argon2id$v=19$m=32,t=3,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkin!",
+        ] {
+            assert_eq!(argon2.verify_encoded(encoded, b"correct horse battery staple"), Err(PasswordHashError::InvalidEncoding));
+        }
+        assert_eq!(argon2.verify_encoded("$argon2id$v=19$m=32,t=3,p=4$AgIC$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA", b""), Err(PasswordHashError::SaltTooShort));
+
+        // parameters above the ones of the verifying instance are rejected before hashing
+        for encoded in [
+            "$argon2id$v=19$m=4294967295,t=3,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+            "$argon2id$v=19$m=32,t=4294967295,p=4$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+            "$argon2id$v=19$m=64,t=3,p=8$AgICAgICAgICAgICAgICAg$QU5S4jfBJYhMkrRo8/2iSC/0ufEPp0Fo7kT3djjkinA",
+        ] {
+            assert_eq!(argon2.verify_encoded(encoded, b"correct horse battery staple"), Err(PasswordHashError::InvalidParameters));
+        }
+    }
+}
@@ -0,0 +1,156 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc7693
+*/
+
+const BLOCK_LENGTH: usize = 128;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn compress(h: &mut [u64; 8], block: &[u8], counter: u128, last: bool) {
+    let mut m: [u64; 16] = [0; 16];
+    for (i, word) in block.chunks_exact(8).enumerate() {
+        m[i] = u64::from_le_bytes(word.try_into().unwrap());
+    }
+
+    let mut v: [u64; 16] = [0; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= counter as u64;
+    v[13] ^= (counter >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in SIGMA.iter() {
+        mix(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        mix(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        mix(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        mix(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        mix(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        mix(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        mix(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        mix(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+pub struct Blake2b {
+    output: Vec<u8>,
+}
+
+impl Blake2b {
+    pub fn new() -> Self {
+        Self {
+            output: Default::default(),
+        }
+    }
+
+    pub fn digest(&mut self, message: &[u8], output_length: usize) -> Vec<u8> {
+        if output_length == 0 || output_length > 64 {
+            panic!("Output-length wrong!");
+        }
+
+        // parameter block without key, fanout and depth 1
+        let mut h: [u64; 8] = IV;
+        h[0] ^= 0x01010000 ^ output_length as u64;
+
+        // the last block is always compressed with the final flag, even if it is full or empty
+        let mut counter: u128 = 0;
+        let full_blocks: usize = message.len().saturating_sub(1) / BLOCK_LENGTH;
+        for block in message.chunks_exact(BLOCK_LENGTH).take(full_blocks) {
+            counter += BLOCK_LENGTH as u128;
+            compress(&mut h, block, counter, false);
+        }
+
+        let remainder: &[u8] = &message[full_blocks * BLOCK_LENGTH..];
+        let mut block: [u8; BLOCK_LENGTH] = [0; BLOCK_LENGTH];
+        block[..remainder.len()].copy_from_slice(remainder);
+        compress(&mut h, &block, counter + remainder.len() as u128, true);
+
+        let mut output: Vec<u8> = h.iter().flat_map(|word| word.to_le_bytes()).collect();
+        output.truncate(output_length);
+
+        self.output = output.clone();
+        output
+    }
+
+    pub fn string(&self) -> String {
+        self.output.iter().map(|x: &u8| format!("{:02x}", x)).collect::<String>()
+    }
+}
+
+impl Default for Blake2b {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Blake2b;
+
+    #[test]
+    fn blake2b_test() {
+        // Test values from https://datatracker.ietf.org/doc/html/rfc7693#appendix-A
+        let mut blake: Blake2b = Blake2b::new();
+        blake.digest("abc".as_bytes(), 64);
+
+        assert_eq!(
+            blake.string(),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+        );
+    }
+
+    #[test]
+    fn block_boundaries_test() {
+        // Test values generated with Python's hashlib.blake2b
+        let mut blake: Blake2b = Blake2b::new();
+
+        blake.digest(&[], 32);
+        assert_eq!(blake.string(), "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+
+        blake.digest(&[0; 128], 64);
+        assert_eq!(
+            blake.string(),
+            "865939e120e6805438478841afb739ae4250cf372653078a065cdcfffca4caf798e6d462b65d658fc165782640eded70963449ae1500fb0f24981d7727e22c41",
+        );
+
+        let message: Vec<u8> = (0..=255).collect();
+        blake.digest(&message, 20);
+        assert_eq!(blake.string(), "2433af65183f411941345962733a8860df650139");
+    }
+}
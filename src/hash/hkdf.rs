@@ -0,0 +1,90 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc5869
+*/
+
+use super::HashType;
+use super::HMAC;
+
+pub struct HKDF {
+    hash_type: HashType,
+    hash_length: usize,
+}
+
+impl HKDF {
+    pub fn new(hash_type: HashType) -> Self {
+        let hash_length: usize = match hash_type {
+            HashType::Sha1Type => 20,
+            HashType::Sha256Type => 32,
+            HashType::Sha512Type => 64,
+        };
+
+        Self { hash_type, hash_length }
+    }
+
+    pub fn extract(&self, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        // an empty salt is the same HMAC key as HashLen zero bytes
+        HMAC::new(self.hash_type).digest(salt, ikm).to_vec()
+    }
+
+    pub fn expand(&self, prk: &[u8], info: &[u8], length: usize) -> Option<Vec<u8>> {
+        if length > 255 * self.hash_length {
+            return None;
+        }
+
+        // T(i) = HMAC(PRK, T(i - 1) || info || i)
+        let mut output: Vec<u8> = Vec::with_capacity(length + self.hash_length);
+        let mut t: Vec<u8> = Vec::new();
+        let mut counter: u8 = 1;
+        while output.len() < length {
+            let mut input: Vec<u8> = t;
+            input.extend_from_slice(info);
+            input.push(counter);
+
+            t = HMAC::new(self.hash_type).digest(prk, &input).to_vec();
+            output.extend_from_slice(&t);
+            counter = counter.wrapping_add(1);
+        }
+        output.truncate(length);
+
+        Some(output)
+    }
+
+    pub fn derive(&self, salt: &[u8], ikm: &[u8], info: &[u8], length: usize) -> Option<Vec<u8>> {
+        self.expand(&self.extract(salt, ikm), info, length)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::HKDF;
+    use crate::hash::HashType;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc5869#appendix-A
+    #[test]
+    fn hkdf_sha256() {
+        let hkdf: HKDF = HKDF::new(HashType::Sha256Type);
+        let ikm: [u8; 22] = [0x0b; 22];
+
+        // A.1
+        let prk: Vec<u8> = hkdf.extract(&hex("000102030405060708090a0b0c"), &ikm);
+        assert_eq!(prk, hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"));
+        assert_eq!(hkdf.expand(&prk, &hex("f0f1f2f3f4f5f6f7f8f9"), 42).unwrap(), hex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"));
+
+        // A.3, empty salt and info
+        assert_eq!(hkdf.derive(&[], &ikm, &[], 42).unwrap(), hex("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"));
+
+        assert!(hkdf.expand(&prk, &[], 255 * 32 + 1).is_none());
+        assert_eq!(hkdf.expand(&prk, &[], 255 * 32).unwrap().len(), 255 * 32);
+    }
+
+    // Test-Vector generated with pyca/cryptography, RFC 5869 has none for SHA-512
+    #[test]
+    fn hkdf_sha512() {
+        let hkdf: HKDF = HKDF::new(HashType::Sha512Type);
+
+        assert_eq!(hkdf.derive(b"salt", b"key", b"info", 100).unwrap(), hex("24156e2c35525baaf3d0fbb92b734c8032a110a3f12e2596e441e1924870d84c3a500652a723738024432451046fd237efad8392fb686c5277a59e01053916536ce56ffa4036f0224573100a07a8dafb96aba25a717b1ef32723bd136a8770834b8b6198"));
+    }
+}
@@ -5,6 +5,7 @@ Implementation according to https://datatracker.ietf.org/doc/html/rfc6234
 
 
 use super::HashType;
+use super::Sha1;
 use super::Sha256;
 use super::Sha512;
 
@@ -55,6 +56,10 @@ impl HMAC {
 
     fn hash(&self, str: &[u8]) -> Vec<u8> {
         match self.hash_type {
+            HashType::Sha1Type => {
+                let mut sha: Sha1 = Sha1::new();
+                sha.digest(&str).to_vec()
+            }
             HashType::Sha256Type => {
                 let mut sha: Sha256 = Sha256::new();
                 sha.digest(&str).to_vec()
@@ -68,6 +73,10 @@ impl HMAC {
 
     fn b_and_l_vars(&mut self) {
         match self.hash_type {
+            HashType::Sha1Type => {
+                self.b = 64;
+                self.l = 20;
+            }
             HashType::Sha256Type => {
                 self.b = 64;
                 self.l = 32;
@@ -164,4 +173,13 @@ mod tests {
             "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
         );
     }
+
+    #[test]
+    fn hmac_sha1_test() {
+        // Test values from https://datatracker.ietf.org/doc/html/rfc2202#section-3
+        let mut hmac_sha1 = HMAC::new(HashType::Sha1Type);
+        hmac_sha1.digest([0x0b; 20].as_slice(), "Hi There".as_bytes());
+
+        assert_eq!(hmac_sha1.string(), "b617318655057264e28bc0b6fb378c8ef146be00");
+    }
 }
\ No newline at end of file
@@ -2,14 +2,28 @@
 Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
 */
 
+mod sha1;
 mod sha256;
 mod sha512;
+mod shake256;
+mod blake2b;
 mod hmac;
+mod hkdf;
+mod pbkdf2;
+mod argon2;
+mod scrypt;
 mod types;
 
+pub use sha1::Sha1;
 pub use sha256::Sha256;
 pub use sha512::Sha512;
+pub use shake256::Shake256;
+pub use blake2b::Blake2b;
 
 pub use hmac::HMAC;
+pub use hkdf::HKDF;
+pub use pbkdf2::PBKDF2;
+pub use argon2::Argon2id;
+pub use scrypt::Scrypt;
 
-pub use types::HashType;
\ No newline at end of file
+pub use types::{HashType, PasswordHashError};
\ No newline at end of file
@@ -0,0 +1,77 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc8018#section-5.2
+*/
+
+use super::HashType;
+use super::HMAC;
+
+pub struct PBKDF2 {
+    hash_type: HashType,
+    hash_length: usize,
+}
+
+impl PBKDF2 {
+    pub fn new(hash_type: HashType) -> Self {
+        let hash_length: usize = match hash_type {
+            HashType::Sha1Type => 20,
+            HashType::Sha256Type => 32,
+            HashType::Sha512Type => 64,
+        };
+
+        Self { hash_type, hash_length }
+    }
+
+    pub fn derive(&self, password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Option<Vec<u8>> {
+        if iterations == 0 || length as u64 > u32::MAX as u64 * self.hash_length as u64 {
+            return None;
+        }
+
+        // T_i = U_1 xor ... xor U_c with U_1 = PRF(P, S || INT(i)) and U_j = PRF(P, U_{j-1})
+        let mut output: Vec<u8> = Vec::with_capacity(length + self.hash_length);
+        let mut index: u32 = 1;
+        while output.len() < length {
+            let mut input: Vec<u8> = salt.to_vec();
+            input.extend_from_slice(&index.to_be_bytes());
+
+            let mut u: Vec<u8> = HMAC::new(self.hash_type).digest(password, &input).to_vec();
+            let mut t: Vec<u8> = u.clone();
+            for _ in 1..iterations {
+                u = HMAC::new(self.hash_type).digest(password, &u).to_vec();
+                for (x, y) in t.iter_mut().zip(u.iter()) {
+                    *x ^= y;
+                }
+            }
+
+            output.extend_from_slice(&t);
+            index += 1;
+        }
+        output.truncate(length);
+
+        Some(output)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::PBKDF2;
+    use super::super::HashType;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7914#section-11
+    #[test]
+    fn pbkdf2_hmac_sha256() {
+        let pbkdf2: PBKDF2 = PBKDF2::new(HashType::Sha256Type);
+
+        assert_eq!(
+            pbkdf2.derive(b"passwd", b"salt", 1, 64).unwrap(),
+            hex("55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"),
+        );
+        assert_eq!(
+            pbkdf2.derive(b"Password", b"NaCl", 80000, 64).unwrap(),
+            hex("4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"),
+        );
+        assert_eq!(pbkdf2.derive(b"passwd", b"salt", 0, 64), None);
+    }
+}
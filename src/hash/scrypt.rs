@@ -0,0 +1,202 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc7914
+*/
+
+#![allow(dead_code)]
+
+use super::{HashType, PasswordHashError, PBKDF2};
+
+fn salsa20_8(block: &mut [u32; 16]) {
+    // Salsa20 with 8 rounds, i.e. four double rounds of column and row rounds
+    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+
+    let mut x: [u32; 16] = *block;
+    for _ in 0..4 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 5, 9, 13, 1);
+        quarter_round(&mut x, 10, 14, 2, 6);
+        quarter_round(&mut x, 15, 3, 7, 11);
+        quarter_round(&mut x, 0, 1, 2, 3);
+        quarter_round(&mut x, 5, 6, 7, 4);
+        quarter_round(&mut x, 10, 11, 8, 9);
+        quarter_round(&mut x, 15, 12, 13, 14);
+    }
+
+    for i in 0..16 {
+        block[i] = block[i].wrapping_add(x[i]);
+    }
+}
+
+fn block_mix(input: &[u32], output: &mut [u32], r: usize) {
+    // Y_i = Salsa(X xor B_i), the even Y_i go to the first half, the odd ones to the second
+    let mut x: [u32; 16] = input[(2 * r - 1) * 16..].try_into().unwrap();
+    for i in 0..2 * r {
+        for (a, b) in x.iter_mut().zip(input[16 * i..16 * i + 16].iter()) {
+            *a ^= b;
+        }
+        salsa20_8(&mut x);
+
+        let position: usize = (i / 2 + (i % 2) * r) * 16;
+        output[position..position + 16].copy_from_slice(&x);
+    }
+}
+
+fn ro_mix(block: &mut [u32], n: usize, r: usize) {
+    let words: usize = 32 * r;
+    let mut v: Vec<u32> = vec![0; n * words];
+    let mut x: Vec<u32> = block.to_vec();
+    let mut y: Vec<u32> = vec![0; words];
+
+    for i in 0..n {
+        v[i * words..(i + 1) * words].copy_from_slice(&x);
+        block_mix(&x, &mut y, r);
+        std::mem::swap(&mut x, &mut y);
+    }
+
+    // Integerify(X) mod N, N is a power of two
+    for _ in 0..n {
+        let j: usize = (x[(2 * r - 1) * 16] as u64 | (x[(2 * r - 1) * 16 + 1] as u64) << 32) as usize & (n - 1);
+        for (a, b) in x.iter_mut().zip(v[j * words..(j + 1) * words].iter()) {
+            *a ^= b;
+        }
+        block_mix(&x, &mut y, r);
+        std::mem::swap(&mut x, &mut y);
+    }
+
+    block.copy_from_slice(&x);
+}
+
+// Memory accepted by Scrypt::new, 1 GiB.
+const MAX_MEMORY: usize = 1 << 30;
+
+// Cost parameter N, block size r and parallelization p, memory use is 128 * r * (N + p) bytes.
+pub struct Scrypt {
+    n: usize,
+    r: usize,
+    p: usize,
+}
+
+impl Scrypt {
+    pub fn new(n: usize, r: usize, p: usize) -> Result<Self, PasswordHashError> {
+        Self::with_memory_limit(n, r, p, MAX_MEMORY)
+    }
+
+    pub fn with_memory_limit(n: usize, r: usize, p: usize, memory_limit: usize) -> Result<Self, PasswordHashError> {
+        // N > 1 a power of two below 2^(128 * r / 8), p <= (2^32 - 1) * 32 / (128 * r)
+        if n < 2 || !n.is_power_of_two() || r == 0 || p == 0 {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+        if r < 4 && n.ilog2() as usize >= 16 * r {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+        if p.checked_mul(r).is_none_or(|x| x >= 1 << 30) {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+
+        // V takes 128 * r * N and B 128 * r * p bytes, parameters may come from untrusted files
+        let memory: Option<usize> = 128usize.checked_mul(r).and_then(|x| x.checked_mul(n)?.checked_add(x.checked_mul(p)?));
+        if memory.is_none_or(|x| x > memory_limit) {
+            return Err(PasswordHashError::InvalidParameters);
+        }
+
+        Ok(Self { n, r, p })
+    }
+
+    pub fn derive(&self, password: &[u8], salt: &[u8], length: usize) -> Result<Vec<u8>, PasswordHashError> {
+        if length == 0 || length as u64 > u32::MAX as u64 * 32 {
+            return Err(PasswordHashError::InvalidOutputLength);
+        }
+
+        let pbkdf2: PBKDF2 = PBKDF2::new(HashType::Sha256Type);
+        let block_length: usize = 128 * self.r;
+        let mut b: Vec<u8> = pbkdf2.derive(password, salt, 1, self.p * block_length).unwrap();
+
+        // the p blocks are independent, they are mixed one after another here
+        for chunk in b.chunks_exact_mut(block_length) {
+            let mut words: Vec<u32> = chunk.chunks_exact(4).map(|x| u32::from_le_bytes(x.try_into().unwrap())).collect();
+            ro_mix(&mut words, self.n, self.r);
+            for (bytes, word) in chunk.chunks_exact_mut(4).zip(words.iter()) {
+                bytes.copy_from_slice(&word.to_le_bytes());
+            }
+        }
+
+        Ok(pbkdf2.derive(password, &b, 1, length).unwrap())
+    }
+}
+
+impl Default for Scrypt {
+    fn default() -> Self {
+        // N = 2^17, r = 8 and p = 1, 128 MiB
+        Self::new(1 << 17, 8, 1).unwrap()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{salsa20_8, Scrypt};
+    use super::super::PasswordHashError;
+    use crate::utils::testing::hex;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7914#section-8
+    #[test]
+    fn salsa20_8_core() {
+        let input: Vec<u8> = hex("7e879a214f3ec9867ca940e641718f26baee555b8c61c1b50df846116dcd3b1dee24f319df9b3d8514121e4b5ac5aa3276021d2909c74829edebc68db8b8c25e");
+        let mut block: [u32; 16] = core::array::from_fn(|i| u32::from_le_bytes(input[4 * i..4 * i + 4].try_into().unwrap()));
+        salsa20_8(&mut block);
+
+        let output: Vec<u8> = block.iter().flat_map(|word| word.to_le_bytes()).collect();
+        assert_eq!(output, hex("a41f859c6608cc993b81cacb020cef05044b2181a2fd337dfd7b1c6396682f29b4393168e3c9e6bcfe6bc5b7a06d96bae424cc102c91745c24ad673dc7618f81"));
+    }
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc7914#section-12
+    #[test]
+    fn scrypt() {
+        assert_eq!(
+            Scrypt::new(16, 1, 1).unwrap().derive(b"", b"", 64).unwrap(),
+            hex("77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"),
+        );
+        assert_eq!(
+            Scrypt::new(1024, 8, 16).unwrap().derive(b"password", b"NaCl", 64).unwrap(),
+            hex("fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"),
+        );
+        assert_eq!(
+            Scrypt::new(16384, 8, 1).unwrap().derive(b"pleaseletmein", b"SodiumChloride", 64).unwrap(),
+            hex("7023bdcb3afd7348461c06cd81fd38ebfda8fbba904f8e3ea9b543f6545da1f2d5432955613f0fcf62d49705242a9af9e61e85dc0d651e40dfcf017b45575887"),
+        );
+    }
+
+    #[test]
+    fn reject_invalid_parameters() {
+        assert!(matches!(Scrypt::new(0, 8, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1, 8, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1000, 8, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1024, 0, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1024, 8, 0), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1 << 16, 1, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(16, 1 << 15, 1 << 15), Err(PasswordHashError::InvalidParameters)));
+
+        assert_eq!(Scrypt::new(16, 1, 1).unwrap().derive(b"", b"", 0), Err(PasswordHashError::InvalidOutputLength));
+    }
+
+    #[test]
+    fn reject_excessive_memory() {
+        // 1 PiB above the default limit, and 128 * r * N above usize::MAX with any limit
+        assert!(matches!(Scrypt::new(1 << 40, 8, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::new(1 << 60, 1 << 10, 1), Err(PasswordHashError::InvalidParameters)));
+        assert!(matches!(Scrypt::with_memory_limit(1 << 60, 1 << 10, 1, usize::MAX), Err(PasswordHashError::InvalidParameters)));
+
+        // 128 * 8 * (1024 + 16) bytes for the second vector of RFC 7914 section 12
+        assert!(matches!(Scrypt::with_memory_limit(1024, 8, 16, 1 << 20), Err(PasswordHashError::InvalidParameters)));
+        assert_eq!(
+            Scrypt::with_memory_limit(1024, 8, 16, 128 * 8 * (1024 + 16)).unwrap().derive(b"password", b"NaCl", 64).unwrap(),
+            hex("fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"),
+        );
+    }
+}
@@ -0,0 +1,106 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc3174
+*/
+
+const BLOCK_LENGTH: usize = 64;
+
+const IV: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+fn compress(h: &mut [u32; 5], block: &[u8]) {
+    let mut w: [u32; 80] = [0; 80];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for t in 16..80 {
+        w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *h;
+    for (t, word) in w.iter().enumerate() {
+        // f and K change every 20 rounds
+        let (f, k): (u32, u32) = match t / 20 {
+            0 => ((b & c) | (!b & d), 0x5a827999),
+            1 => (b ^ c ^ d, 0x6ed9eba1),
+            2 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+
+        let temp: u32 = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (x, y) in h.iter_mut().zip([a, b, c, d, e]) {
+        *x = x.wrapping_add(y);
+    }
+}
+
+// SHA-1 is broken for collisions, it is here for HMAC and the RFC 8017 defaults of OAEP and MGF1.
+pub struct Sha1 {
+    output: [u8; 20],
+}
+
+impl Sha1 {
+    pub fn new() -> Self {
+        Self { output: [0; 20] }
+    }
+
+    pub fn digest(&mut self, message: &[u8]) -> [u8; 20] {
+        // 0x80, zeros up to 56 mod 64 bytes and the bit length as 64-bit big-endian number
+        let mut input: Vec<u8> = message.to_vec();
+        input.push(0x80);
+        while input.len() % BLOCK_LENGTH != BLOCK_LENGTH - 8 {
+            input.push(0x00);
+        }
+        input.extend_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+
+        let mut h: [u32; 5] = IV;
+        for block in input.chunks_exact(BLOCK_LENGTH) {
+            compress(&mut h, block);
+        }
+
+        for (bytes, word) in self.output.chunks_exact_mut(4).zip(h.iter()) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+
+        self.output
+    }
+
+    pub fn string(&self) -> String {
+        self.output.iter().map(|x: &u8| format!("{:02x}", x)).collect::<String>()
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Sha1;
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc3174#section-7.3
+    #[test]
+    fn sha1_test() {
+        let mut sha: Sha1 = Sha1::new();
+
+        sha.digest(b"abc");
+        assert_eq!(sha.string(), "a9993e364706816aba3e25717850c26c9cd0d89d");
+
+        sha.digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+        assert_eq!(sha.string(), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+
+        sha.digest(&[b'a'; 1_000_000]);
+        assert_eq!(sha.string(), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+
+        sha.digest("0123456701234567012345670123456701234567012345670123456701234567".repeat(10).as_bytes());
+        assert_eq!(sha.string(), "dea356a2cddd90c7a7ecedc5ebb563934f460452");
+    }
+}
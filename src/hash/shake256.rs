@@ -0,0 +1,156 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+*/
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// Rotation offsets r[x + 5y] of the rho step
+const ROTATION_OFFSETS: [u32; 25] = [
+    0, 1, 62, 28, 27,
+    36, 44, 6, 55, 20,
+    3, 10, 43, 25, 39,
+    41, 45, 15, 21, 8,
+    18, 2, 61, 56, 14,
+];
+
+// SHAKE256 absorbs and squeezes 1088-bit blocks (capacity 512 bits).
+const RATE: usize = 136;
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for round_constant in ROUND_CONSTANTS.iter() {
+        // theta
+        let mut c: [u64; 5] = [0; 5];
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d: u64 = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut b: [u64; 25] = [0; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y].rotate_left(ROTATION_OFFSETS[x + 5 * y]);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] = b[x + 5 * y] ^ ((!b[(x + 1) % 5 + 5 * y]) & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+
+        // iota
+        state[0] ^= round_constant;
+    }
+}
+
+pub struct Shake256 {
+    state: [u64; 25],
+    output: Vec<u8>,
+}
+
+impl Shake256 {
+    pub fn new() -> Self {
+        Self {
+            state: [0; 25],
+            output: Default::default(),
+        }
+    }
+
+    pub fn digest(&mut self, message: &[u8], output_length: usize) -> Vec<u8> {
+        self.state = [0; 25];
+
+        // pad10*1 with the SHAKE domain separation bits 1111
+        let mut padded: Vec<u8> = message.to_vec();
+        padded.push(0x1f);
+        while !padded.len().is_multiple_of(RATE) {
+            padded.push(0x00);
+        }
+        let last: usize = padded.len() - 1;
+        padded[last] |= 0x80;
+
+        for block in padded.chunks_exact(RATE) {
+            for (i, lane) in block.chunks_exact(8).enumerate() {
+                self.state[i] ^= u64::from_le_bytes(lane.try_into().unwrap());
+            }
+            keccak_f(&mut self.state);
+        }
+
+        let mut output: Vec<u8> = Vec::with_capacity(output_length + RATE);
+        loop {
+            for lane in self.state.iter().take(RATE / 8) {
+                output.extend_from_slice(&lane.to_le_bytes());
+            }
+            if output.len() >= output_length {
+                break;
+            }
+            keccak_f(&mut self.state);
+        }
+        output.truncate(output_length);
+
+        self.output = output.clone();
+        output
+    }
+
+    pub fn string(&self) -> String {
+        self.output.iter().map(|x: &u8| format!("{:02x}", x)).collect::<String>()
+    }
+}
+
+impl Default for Shake256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Shake256;
+
+    #[test]
+    fn shake256_test() {
+        // Test values from https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+        fn check_shake256(message: &[u8], output_length: usize, correct_value: &str) {
+            let mut shake: Shake256 = Shake256::new();
+            shake.digest(message, output_length);
+
+            assert_eq!(shake.string(), correct_value);
+        }
+
+        check_shake256("".as_bytes(), 32, "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f");
+        check_shake256(
+            "abc".as_bytes(),
+            64,
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+        );
+    }
+
+    #[test]
+    fn multi_block_test() {
+        // Input and output both longer than the rate of 136 bytes
+        let message: Vec<u8> = (0..=255).collect();
+
+        let mut shake: Shake256 = Shake256::new();
+        let short_output: Vec<u8> = shake.digest(&message, 100);
+        let long_output: Vec<u8> = shake.digest(&message, 300);
+
+        assert_eq!(long_output[..100], short_output[..]);
+        assert_eq!(shake.string()[..16], *"336c8aa7f2b08bda");
+        assert_eq!(shake.string()[568..], *"8795b9d1d60d3a5c084d879648faf507");
+    }
+}
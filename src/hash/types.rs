@@ -2,8 +2,21 @@
 Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
 */
 
-#[derive(PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum HashType {
+    Sha1Type,
     Sha256Type,
     Sha512Type
 }
+
+#[derive(Debug, PartialEq)]
+pub enum PasswordHashError {
+    // Memory, iteration or parallelism parameter outside of the allowed range.
+    InvalidParameters,
+    // Salt shorter than 8 bytes.
+    SaltTooShort,
+    // Requested output length outside of the allowed range.
+    InvalidOutputLength,
+    // Stored hash string that is not in the expected PHC format.
+    InvalidEncoding,
+}
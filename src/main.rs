@@ -9,13 +9,18 @@ pub mod hash;
 pub mod crypto;
 pub mod utils;
 
-use hash::{Sha256, Sha512, HMAC, HashType};
-use crypto::{AES_CBC, Blocksize, ECDH, scalarmult};
+use hash::{Sha256, Sha512, Blake2b, HMAC, PBKDF2, Argon2id, Scrypt, HashType};
+use crypto::{AES_CBC, Blocksize, ChaCha20Poly1305, AesGcm, HandshakeState, HandshakePattern, TransportState, HPKE, HpkeContext, AeadType, SealedBox, RSA, RSA2048, ECDH, ECDH448, Ed25519, ECVRF, Ed448, Ristretto255, Pedersen, PedersenCommitment, Secp256k1, Schnorr, P256, Point, hash_to_curve25519, scalarmult, scalarmult448};
 
 fn main() {
     hash_example();
     hmac_example();
+    password_hash_example();
     ecc_example();
+    rsa_example();
+    noise_example();
+    hpke_example();
+    sealed_box_example();
     aes_example();
 }
 
@@ -25,6 +30,22 @@ fn hash_example() {
 
     let mut sha512: Sha512 = Sha512::new();
     let res512: [u8; 64] = sha512.digest("abc".as_bytes());
+
+    let mut blake2b: Blake2b = Blake2b::new();
+    let res_blake2b: Vec<u8> = blake2b.digest("abc".as_bytes(), 64);
+}
+
+fn password_hash_example() {
+    // small parameters for the example, Argon2id::default() uses 64 MiB
+    let argon2: Argon2id = Argon2id::new(1024, 3, 4).unwrap();
+    let stored: String = argon2.hash_encoded("wallet passphrase".as_bytes()).unwrap();
+    let valid: bool = argon2.verify_encoded(&stored, "wallet passphrase".as_bytes()).unwrap();
+
+    let scrypt: Scrypt = Scrypt::new(1024, 8, 1).unwrap();
+    let wallet_key: Vec<u8> = scrypt.derive("wallet passphrase".as_bytes(), "salt".as_bytes(), 32).unwrap();
+
+    let pbkdf2: PBKDF2 = PBKDF2::new(HashType::Sha256Type);
+    let derived: Vec<u8> = pbkdf2.derive("wallet passphrase".as_bytes(), "salt".as_bytes(), 1000, 32).unwrap();
 }
 
 fn hmac_example() {
@@ -51,6 +72,136 @@ fn ecc_example() {
 
     let key1: [u8; 32] = scalarmult(&ecdh.pub_key, &alice_private_key);
     let key2: [u8; 32] = ecdh.symmetric_key(&alice_public_key);
+
+    let mut ecdh448: ECDH448 = ECDH448::new();
+    ecdh448.gen_key_pair();
+
+    let mut bob448: ECDH448 = ECDH448::new();
+    bob448.gen_key_pair();
+
+    let key3: [u8; 56] = scalarmult448(&ecdh448.pub_key, &bob448.sec_key);
+    let key4: [u8; 56] = ecdh448.symmetric_key(&bob448.pub_key);
+
+    let mut ed25519: Ed25519 = Ed25519::new();
+    ed25519.gen_key_pair();
+
+    let signature: [u8; 64] = ed25519.sign("message".as_bytes());
+    let valid: bool = Ed25519::verify(&ed25519.pub_key, "message".as_bytes(), &signature).is_ok();
+    let valid_batch: bool = Ed25519::verify_batch(&[ed25519.pub_key], &["message".as_bytes()], &[signature]).is_ok();
+
+    let mut converted: ECDH = ECDH::new();
+    converted.set_secret_key(&ed25519.to_x25519_secret_key());
+    let key_converted: [u8; 32] = converted.symmetric_key(&Ed25519::to_x25519_public_key(&ed25519.pub_key).unwrap());
+
+    let mut ecvrf: ECVRF = ECVRF::new();
+    ecvrf.gen_key_pair();
+
+    let proof: [u8; 80] = ecvrf.prove("message".as_bytes()).unwrap();
+    let output: [u8; 64] = ECVRF::verify(&ecvrf.pub_key, "message".as_bytes(), &proof).unwrap();
+
+    let mut ed448: Ed448 = Ed448::new();
+    ed448.gen_key_pair();
+
+    let signature: [u8; 114] = ed448.sign("message".as_bytes(), &[]).unwrap();
+    let valid: bool = Ed448::verify(&ed448.pub_key, "message".as_bytes(), &[], &signature).is_ok();
+
+    let hashed: Point = hash_to_curve25519("message".as_bytes(), "lightweight_blockchain-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_RO_".as_bytes()).unwrap();
+
+    let element: Ristretto255 = Ristretto255::hash_to_group("message".as_bytes());
+    let decoded: Ristretto255 = Ristretto255::decode(&element.encode()).unwrap();
+    let sum: Ristretto255 = decoded + Ristretto255::generator().scalar_mult(&[0x01; 32]);
+
+    let pedersen: Pedersen = Pedersen::new();
+    let (amount_1, blinding_1) = pedersen.commit_random(700);
+    let (amount_2, blinding_2) = pedersen.commit_random(300);
+    let total: PedersenCommitment = amount_1 + amount_2;
+    let opened: bool = pedersen.open(&total, 1000, &Pedersen::add_blindings(&blinding_1, &blinding_2));
+
+    let mut secp256k1: Secp256k1 = Secp256k1::new();
+    secp256k1.gen_key_pair();
+
+    let message_hash: [u8; 32] = Sha256::new().digest("message".as_bytes());
+    let (signature, recovery_id) = secp256k1.sign_recoverable(&message_hash);
+    let recovered: [u8; 33] = Secp256k1::recover(&message_hash, &signature, recovery_id).unwrap();
+
+    let mut schnorr: Schnorr = Schnorr::new();
+    schnorr.gen_key_pair();
+
+    let signature: [u8; 64] = schnorr.sign("message".as_bytes()).unwrap();
+    let valid: bool = Schnorr::verify(&schnorr.pub_key, "message".as_bytes(), &signature).is_ok();
+
+    let mut p256: P256 = P256::new();
+    p256.gen_key_pair();
+
+    let mut bob_p256: P256 = P256::new();
+    bob_p256.gen_key_pair();
+
+    let key5: [u8; 32] = p256.symmetric_key(&bob_p256.pub_key).unwrap();
+    let signature: [u8; 64] = p256.sign("message".as_bytes());
+    let valid: bool = P256::verify(&p256.pub_key, "message".as_bytes(), &signature).is_ok();
+}
+
+fn rsa_example() {
+    let mut rsa: RSA2048 = RSA2048::new();
+    rsa.gen_key_pair(2048).unwrap();
+
+    let signature: Vec<u8> = rsa.sign_pss(HashType::Sha256Type, "message".as_bytes()).unwrap();
+    let valid: bool = RSA::verify_pss(&rsa.pub_key, HashType::Sha256Type, "message".as_bytes(), &signature).is_ok();
+
+    let ciphertext: Vec<u8> = RSA::encrypt_oaep(&rsa.pub_key, HashType::Sha256Type, "message".as_bytes(), &[]).unwrap();
+    let plaintext: Vec<u8> = rsa.decrypt_oaep(HashType::Sha256Type, &ciphertext, &[]).unwrap();
+}
+
+fn noise_example() {
+    let mut alice: ECDH = ECDH::new();
+    alice.gen_key_pair();
+
+    let mut bob: ECDH = ECDH::new();
+    bob.gen_key_pair();
+
+    let mut initiator: HandshakeState = HandshakeState::new(HandshakePattern::XX, true, &[], alice, None).unwrap();
+    let mut responder: HandshakeState = HandshakeState::new(HandshakePattern::XX, false, &[], bob, None).unwrap();
+
+    responder.read_message(&initiator.write_message(&[]).unwrap()).unwrap();
+    initiator.read_message(&responder.write_message(&[]).unwrap()).unwrap();
+    responder.read_message(&initiator.write_message(&[]).unwrap()).unwrap();
+
+    let mut initiator: TransportState = initiator.into_transport().unwrap();
+    let mut responder: TransportState = responder.into_transport().unwrap();
+    let received: Vec<u8> = responder.read_message(&initiator.write_message("message".as_bytes()).unwrap()).unwrap();
+
+    let aead: ChaCha20Poly1305 = ChaCha20Poly1305::new(&[0x42; 32]);
+    let sealed: Vec<u8> = aead.encrypt(&[0; 12], &[], "message".as_bytes());
+    let opened: Vec<u8> = aead.decrypt(&[0; 12], &[], &sealed).unwrap();
+}
+
+fn hpke_example() {
+    let mut recipient: ECDH = ECDH::new();
+    recipient.gen_key_pair();
+
+    // single-shot encryption of a memo, enc || ciphertext
+    let hpke: HPKE = HPKE::new(AeadType::ChaCha20Poly1305);
+    let sealed: Vec<u8> = hpke.seal_base(&recipient.pub_key, "memo".as_bytes(), &[], "encrypted memo".as_bytes()).unwrap();
+    let memo: Vec<u8> = hpke.open_base(&recipient, "memo".as_bytes(), &[], &sealed).unwrap();
+
+    let mut sender_key: ECDH = ECDH::new();
+    sender_key.gen_key_pair();
+
+    let hpke: HPKE = HPKE::new(AeadType::Aes128Gcm);
+    let (enc, mut sender) = hpke.setup_auth_s(&recipient.pub_key, &[], &sender_key).unwrap();
+    let mut receiver: HpkeContext = hpke.setup_auth_r(&enc, &recipient, &[], &sender_key.pub_key).unwrap();
+    let plaintext: Vec<u8> = receiver.open(&[], &sender.seal(&[], "message".as_bytes()).unwrap()).unwrap();
+
+    let aead: AesGcm = AesGcm::new(&[0x42; 16]);
+    let opened: Vec<u8> = aead.decrypt(&[0; 12], &[], &aead.encrypt(&[0; 12], &[], "message".as_bytes())).unwrap();
+}
+
+fn sealed_box_example() {
+    let mut recipient: ECDH = ECDH::new();
+    recipient.gen_key_pair();
+
+    let sealed: Vec<u8> = SealedBox::seal(&recipient.pub_key, "wallet-to-wallet message".as_bytes()).unwrap();
+    let message: Vec<u8> = SealedBox::open(&recipient, &sealed).unwrap();
 }
 
 fn aes_example() {
@@ -0,0 +1,100 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://datatracker.ietf.org/doc/html/rfc4648#section-4
+*/
+
+#![allow(dead_code)]
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn value(symbol: u8) -> Option<u32> {
+    ALPHABET.iter().position(|&x| x == symbol).map(|x| x as u32)
+}
+
+pub fn encode(input: &[u8], padding: bool) -> String {
+    let mut output: String = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let mut group: [u8; 3] = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits: u32 = ((group[0] as u32) << 16) | ((group[1] as u32) << 8) | group[2] as u32;
+
+        // n input bytes give n + 1 symbols
+        for i in 0..=chunk.len() {
+            output.push(ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+        if padding {
+            for _ in chunk.len()..3 {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    // padding is optional, but has to be complete if it is there
+    let symbols: &[u8] = input.trim_end_matches('=').as_bytes();
+    if input.len() != symbols.len() && !input.len().is_multiple_of(4) {
+        return None;
+    }
+    if symbols.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output: Vec<u8> = Vec::with_capacity(symbols.len() * 3 / 4);
+    for chunk in symbols.chunks(4) {
+        let mut bits: u32 = 0;
+        for (i, symbol) in chunk.iter().enumerate() {
+            bits |= value(*symbol)? << (18 - 6 * i);
+        }
+
+        // bits below the last full byte have to be zero, otherwise two strings decode the same
+        let length: usize = chunk.len() - 1;
+        if bits & (0xffffff >> (8 * length)) != 0 {
+            return None;
+        }
+        for i in 0..length {
+            output.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    // Test-Vectors from: https://datatracker.ietf.org/doc/html/rfc4648#section-10
+    #[test]
+    fn encoding() {
+        let vectors: [(&str, &str); 7] = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (plain, encoded) in vectors {
+            assert_eq!(encode(plain.as_bytes(), true), encoded);
+            assert_eq!(encode(plain.as_bytes(), false), encoded.trim_end_matches('='));
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+            assert_eq!(decode(encoded.trim_end_matches('=')).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn reject_invalid_encodings() {
+        assert_eq!(decode("Zg="), None);
+        assert_eq!(decode("Z"), None);
+        assert_eq!(decode("Zh=="), None);
+        assert_eq!(decode("Zm9v!"), None);
+        assert_eq!(decode("Zm-v"), None);
+    }
+}
@@ -0,0 +1,12 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod uint;
+mod montgomery;
+mod types;
+
+pub use uint::{BigUint, U256, U512, U1024, U2048, U4096};
+pub use montgomery::Montgomery;
+
+pub use types::BigIntError;
@@ -0,0 +1,227 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://cacr.uwaterloo.ca/hac/about/chap14.pdf (14.36, Montgomery multiplication & 14.82, fixed window exponentiation)
+*/
+
+#![allow(dead_code)]
+
+use super::{BigUint, BigIntError};
+
+// Arithmetic modulo an odd modulus of at most N limbs. Elements handed to add,
+// sub, mul and pow are in Montgomery form (a * 2^(64 N) mod m) and fully reduced.
+#[derive(Copy, Clone, Debug)]
+pub struct Montgomery<const N: usize> {
+    pub modulus: BigUint<N>,
+    m_prime: u64,
+    r2: BigUint<N>,
+}
+
+impl<const N: usize> Montgomery<N> {
+    pub fn new(modulus: &BigUint<N>) -> Result<Self, BigIntError> {
+        if !modulus.is_odd() {
+            return Err(BigIntError::EvenModulus);
+        }
+
+        // m' = -m^-1 mod 2^64 by Newton iteration, every step doubles the correct bits
+        let m_0: u64 = modulus.limbs()[0];
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(m_0.wrapping_mul(inverse)));
+        }
+
+        // R^2 = 2^(128 N) mod m by doubling 1 for 128 N times
+        let mut r2: BigUint<N> = BigUint::one().rem(modulus);
+        for _ in 0..2 * BigUint::<N>::BITS {
+            r2 = r2.add_mod(&r2, modulus);
+        }
+
+        Ok(Self {
+            modulus: *modulus,
+            m_prime: inverse.wrapping_neg(),
+            r2,
+        })
+    }
+
+    pub fn one(&self) -> BigUint<N> {
+        self.to_montgomery(&BigUint::one())
+    }
+
+    pub fn to_montgomery(&self, input: &BigUint<N>) -> BigUint<N> {
+        // also reduces inputs that are not below m
+        self.mul(input, &self.r2)
+    }
+
+    pub fn from_montgomery(&self, input: &BigUint<N>) -> BigUint<N> {
+        self.mul(input, &BigUint::one())
+    }
+
+    pub fn add(&self, x: &BigUint<N>, y: &BigUint<N>) -> BigUint<N> {
+        x.add_mod(y, &self.modulus)
+    }
+
+    pub fn sub(&self, x: &BigUint<N>, y: &BigUint<N>) -> BigUint<N> {
+        x.sub_mod(y, &self.modulus)
+    }
+
+    pub fn mul(&self, x: &BigUint<N>, y: &BigUint<N>) -> BigUint<N> {
+        // CIOS Montgomery multiplication, returns x * y / 2^(64 N) mod m,
+        // the two words above t are kept in t_n and t_n1
+        let x: &[u64; N] = x.limbs();
+        let y: &[u64; N] = y.limbs();
+        let m: &[u64; N] = self.modulus.limbs();
+        let mut t: [u64; N] = [0; N];
+        let mut t_n: u64 = 0;
+
+        for x_i in x.iter() {
+            let mut carry: u128 = 0;
+            for j in 0..N {
+                let value: u128 = t[j] as u128 + (*x_i as u128) * (y[j] as u128) + carry;
+                t[j] = value as u64;
+                carry = value >> 64;
+            }
+            let value: u128 = t_n as u128 + carry;
+            t_n = value as u64;
+            let t_n1: u64 = (value >> 64) as u64;
+
+            let u: u64 = t[0].wrapping_mul(self.m_prime);
+            let mut carry: u128 = (t[0] as u128 + (u as u128) * (m[0] as u128)) >> 64;
+            for j in 1..N {
+                let value: u128 = t[j] as u128 + (u as u128) * (m[j] as u128) + carry;
+                t[j - 1] = value as u64;
+                carry = value >> 64;
+            }
+            let value: u128 = t_n as u128 + carry;
+            t[N - 1] = value as u64;
+            t_n = t_n1 + (value >> 64) as u64;
+        }
+
+        // t + t_n * 2^(64 N) is below 2m, keep t - m unless it borrows from t_n
+        let t: BigUint<N> = BigUint::from_limbs(t);
+        let (difference, borrow) = t.overflowing_sub(&self.modulus);
+        let mask: u64 = ((borrow as u64) & !t_n & 1).wrapping_neg();
+
+        let mut output: [u64; N] = [0; N];
+        for (i, limb) in output.iter_mut().enumerate() {
+            *limb = (t.limbs()[i] & mask) | (difference.limbs()[i] & !mask);
+        }
+
+        BigUint::from_limbs(output)
+    }
+
+    pub fn square(&self, x: &BigUint<N>) -> BigUint<N> {
+        self.mul(x, x)
+    }
+
+    pub fn pow(&self, x: &BigUint<N>, exponent: &BigUint<N>) -> BigUint<N> {
+        // fixed 4-bit window, every window squares four times and multiplies
+        // with a table entry read in constant time; only the bit length of the
+        // exponent decides the running time
+        let mut table: [BigUint<N>; 16] = [self.one(); 16];
+        for i in 1..16 {
+            table[i] = self.mul(&table[i - 1], x);
+        }
+
+        let mut output: BigUint<N> = self.one();
+        for window in (0..exponent.bits().div_ceil(4)).rev() {
+            for _ in 0..4 {
+                output = self.square(&output);
+            }
+
+            let mut digit: usize = 0;
+            for bit in 0..4 {
+                let index: usize = 4 * window + bit;
+                if index < BigUint::<N>::BITS {
+                    digit |= (exponent.bit(index) as usize) << bit;
+                }
+            }
+            output = self.mul(&output, &select(&table, digit));
+        }
+
+        output
+    }
+
+    pub fn inverse(&self, x: &BigUint<N>) -> BigUint<N> {
+        // Fermat: x^(m - 2), m has to be prime
+        let (exponent, _) = self.modulus.overflowing_sub(&BigUint::from_u64(2));
+        self.pow(x, &exponent)
+    }
+}
+
+fn select<const N: usize>(table: &[BigUint<N>; 16], index: usize) -> BigUint<N> {
+    // scans the whole table, the mask is all ones only for the entry at index
+    let mut output: [u64; N] = [0; N];
+    for (i, entry) in table.iter().enumerate() {
+        let mask: u64 = (((i ^ index) as u64).wrapping_sub(1) >> 63).wrapping_neg();
+        for (limb, value) in output.iter_mut().zip(entry.limbs().iter()) {
+            *limb |= value & mask;
+        }
+    }
+
+    BigUint::from_limbs(output)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Montgomery;
+    use super::super::{BigUint, BigIntError, U256, U2048};
+
+    #[test]
+    fn montgomery_roundtrip() {
+        // p = 2^256 - 2^224 + 2^192 + 2^96 - 1 of P-256
+        let p: U256 = U256::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        let field: Montgomery<4> = Montgomery::new(&p).unwrap();
+        let value: U256 = U256::from_hex("7777777777777777111111111111111fedcba98765432100123456789abcdef").unwrap();
+
+        assert_eq!(field.from_montgomery(&field.to_montgomery(&value)), value);
+        assert_eq!(field.from_montgomery(&field.one()), U256::one());
+
+        // p + 1 is reduced to 1
+        let p_plus_1: U256 = p.overflowing_add(&U256::one()).0;
+        assert_eq!(field.from_montgomery(&field.to_montgomery(&p_plus_1)), U256::one());
+
+        assert_eq!(Montgomery::new(&U256::from_u64(10)).unwrap_err(), BigIntError::EvenModulus);
+    }
+
+    #[test]
+    fn field_arithmetic() {
+        let p: U256 = U256::from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff").unwrap();
+        let field: Montgomery<4> = Montgomery::new(&p).unwrap();
+        let minus_3: U256 = p.overflowing_sub(&U256::from_u64(3)).0;
+        let x: U256 = field.to_montgomery(&U256::from_u64(7));
+        let y: U256 = field.to_montgomery(&minus_3);
+
+        // 7 + (-3) = 4, 7 - (-3) = 10, 7 * (-3) = -21
+        assert_eq!(field.from_montgomery(&field.add(&x, &y)), U256::from_u64(4));
+        assert_eq!(field.from_montgomery(&field.sub(&x, &y)), U256::from_u64(10));
+        assert_eq!(field.from_montgomery(&field.mul(&x, &y)), p.overflowing_sub(&U256::from_u64(21)).0);
+        assert_eq!(field.mul(&x, &field.inverse(&x)), field.one());
+
+        // Fermat: y^(p - 1) = 1, y^0 = 1
+        let p_minus_1: U256 = p.overflowing_sub(&U256::one()).0;
+        assert_eq!(field.pow(&y, &p_minus_1), field.one());
+        assert_eq!(field.pow(&y, &U256::zero()), field.one());
+        assert_eq!(field.from_montgomery(&field.pow(&y, &U256::from_u64(3))), field.from_montgomery(&field.mul(&field.square(&y), &y)));
+    }
+
+    #[test]
+    fn fermat_2048() {
+        // 2048-bit MODP prime of RFC 3526, section 3
+        let p: U2048 = U2048::from_hex(concat!(
+            "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+            "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+            "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+            "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+            "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+            "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+            "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+            "3995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff",
+        )).unwrap();
+        let field: Montgomery<32> = Montgomery::new(&p).unwrap();
+        let g: U2048 = field.to_montgomery(&U2048::from_u64(2));
+        let p_minus_1: U2048 = p.overflowing_sub(&U2048::one()).0;
+
+        assert_eq!(field.pow(&g, &p_minus_1), field.one());
+        assert_eq!(BigUint::<32>::from_u64(5).pow_mod(&p_minus_1, &p), U2048::one());
+    }
+}
@@ -0,0 +1,13 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum BigIntError {
+    // A character that is not a digit of the given base, or an empty string.
+    InvalidDigit,
+    // The value does not fit into the fixed number of limbs or the requested byte length.
+    Overflow,
+    // Montgomery arithmetic needs an odd modulus.
+    EvenModulus,
+}
@@ -0,0 +1,548 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://cacr.uwaterloo.ca/hac/about/chap14.pdf (14.2 multiple-precision arithmetic, 14.61 extended Euclid)
+*/
+
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+
+use super::{BigIntError, Montgomery};
+
+// Unsigned integer of N 64-bit limbs, least significant limb first.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BigUint<const N: usize> {
+    limbs: [u64; N],
+}
+
+pub type U256 = BigUint<4>;
+pub type U512 = BigUint<8>;
+pub type U1024 = BigUint<16>;
+pub type U2048 = BigUint<32>;
+pub type U4096 = BigUint<64>;
+
+// 10^19 is the largest power of ten below 2^64
+const DECIMAL_CHUNK: u64 = 10_000_000_000_000_000_000;
+
+impl<const N: usize> BigUint<N> {
+    pub const BITS: usize = 64 * N;
+
+    pub fn zero() -> Self {
+        Self { limbs: [0; N] }
+    }
+
+    pub fn one() -> Self {
+        Self::from_u64(1)
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        let mut limbs: [u64; N] = [0; N];
+        limbs[0] = value;
+        Self { limbs }
+    }
+
+    pub fn from_limbs(limbs: [u64; N]) -> Self {
+        Self { limbs }
+    }
+
+    pub fn limbs(&self) -> &[u64; N] {
+        &self.limbs
+    }
+
+    pub fn from_be_bytes(input: &[u8]) -> Result<Self, BigIntError> {
+        // leading zero bytes beyond the width are accepted
+        let mut output: Self = Self::zero();
+        for (i, byte) in input.iter().rev().enumerate() {
+            if i >= 8 * N {
+                if *byte != 0 {
+                    return Err(BigIntError::Overflow);
+                }
+                continue;
+            }
+            output.limbs[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+
+        Ok(output)
+    }
+
+    pub fn from_le_bytes(input: &[u8]) -> Result<Self, BigIntError> {
+        let reversed: Vec<u8> = input.iter().rev().copied().collect();
+        Self::from_be_bytes(&reversed)
+    }
+
+    pub fn to_be_bytes(&self, length: usize) -> Result<Vec<u8>, BigIntError> {
+        // I2OSP of RFC 8017, section 4.1
+        if self.bits() > 8 * length {
+            return Err(BigIntError::Overflow);
+        }
+
+        let mut output: Vec<u8> = vec![0; length];
+        for i in 0..length.min(8 * N) {
+            output[length - 1 - i] = (self.limbs[i / 8] >> (8 * (i % 8))) as u8;
+        }
+
+        Ok(output)
+    }
+
+    pub fn to_le_bytes(&self, length: usize) -> Result<Vec<u8>, BigIntError> {
+        let mut output: Vec<u8> = self.to_be_bytes(length)?;
+        output.reverse();
+        Ok(output)
+    }
+
+    pub fn from_hex(input: &str) -> Result<Self, BigIntError> {
+        let digits: &str = input.strip_prefix("0x").unwrap_or(input);
+        if digits.is_empty() {
+            return Err(BigIntError::InvalidDigit);
+        }
+
+        let mut output: Self = Self::zero();
+        for c in digits.chars() {
+            let digit: u64 = c.to_digit(16).ok_or(BigIntError::InvalidDigit)? as u64;
+            if output.limbs[N - 1] >> 60 != 0 {
+                return Err(BigIntError::Overflow);
+            }
+            output = output.shl(4);
+            output.limbs[0] |= digit;
+        }
+
+        Ok(output)
+    }
+
+    pub fn from_decimal(input: &str) -> Result<Self, BigIntError> {
+        if input.is_empty() {
+            return Err(BigIntError::InvalidDigit);
+        }
+
+        let mut output: Self = Self::zero();
+        for c in input.chars() {
+            let digit: u64 = c.to_digit(10).ok_or(BigIntError::InvalidDigit)? as u64;
+            let (product, carry) = output.mul_small(10);
+            let (sum, overflow) = product.overflowing_add(&Self::from_u64(digit));
+            if carry != 0 || overflow {
+                return Err(BigIntError::Overflow);
+            }
+            output = sum;
+        }
+
+        Ok(output)
+    }
+
+    pub fn to_hex(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        let mut output: String = String::new();
+        for limb in self.limbs.iter().rev() {
+            output.push_str(&format!("{:016x}", limb));
+        }
+
+        output.trim_start_matches('0').to_string()
+    }
+
+    pub fn to_decimal(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        // split into chunks of 19 decimal digits, least significant first
+        let mut chunks: Vec<u64> = Vec::new();
+        let mut value: Self = *self;
+        while !value.is_zero() {
+            let (quotient, remainder) = value.div_rem_small(DECIMAL_CHUNK);
+            chunks.push(remainder);
+            value = quotient;
+        }
+
+        let mut output: String = chunks[chunks.len() - 1].to_string();
+        for chunk in chunks.iter().rev().skip(1) {
+            output.push_str(&format!("{:019}", chunk));
+        }
+
+        output
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|limb| *limb == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.limbs[0] & 1 == 1
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        (self.limbs[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    pub fn set_bit(&mut self, index: usize) {
+        self.limbs[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn bits(&self) -> usize {
+        for i in (0..N).rev() {
+            if self.limbs[i] != 0 {
+                return 64 * i + 64 - self.limbs[i].leading_zeros() as usize;
+            }
+        }
+
+        0
+    }
+
+    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut output: Self = Self::zero();
+        let mut carry: u64 = 0;
+        for i in 0..N {
+            let value: u128 = self.limbs[i] as u128 + other.limbs[i] as u128 + carry as u128;
+            output.limbs[i] = value as u64;
+            carry = (value >> 64) as u64;
+        }
+
+        (output, carry == 1)
+    }
+
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        let mut output: Self = Self::zero();
+        let mut borrow: bool = false;
+        for i in 0..N {
+            let (difference, borrow_1) = self.limbs[i].overflowing_sub(other.limbs[i]);
+            let (difference, borrow_2) = difference.overflowing_sub(borrow as u64);
+            output.limbs[i] = difference;
+            borrow = borrow_1 | borrow_2;
+        }
+
+        (output, borrow)
+    }
+
+    pub fn mul_small(&self, factor: u64) -> (Self, u64) {
+        let mut output: Self = Self::zero();
+        let mut carry: u128 = 0;
+        for i in 0..N {
+            let value: u128 = (self.limbs[i] as u128) * (factor as u128) + carry;
+            output.limbs[i] = value as u64;
+            carry = value >> 64;
+        }
+
+        (output, carry as u64)
+    }
+
+    pub fn div_rem_small(&self, divisor: u64) -> (Self, u64) {
+        assert!(divisor != 0, "division by zero");
+
+        let mut output: Self = Self::zero();
+        let mut remainder: u128 = 0;
+        for i in (0..N).rev() {
+            let value: u128 = (remainder << 64) | self.limbs[i] as u128;
+            output.limbs[i] = (value / divisor as u128) as u64;
+            remainder = value % divisor as u128;
+        }
+
+        (output, remainder as u64)
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        // schoolbook multiplication, every partial product above the width must vanish
+        let mut output: Self = Self::zero();
+        for i in 0..N {
+            if self.limbs[i] == 0 {
+                continue;
+            }
+
+            let mut carry: u128 = 0;
+            for j in 0..N {
+                let value: u128 = (self.limbs[i] as u128) * (other.limbs[j] as u128) + carry;
+                if i + j < N {
+                    let value: u128 = value + output.limbs[i + j] as u128;
+                    output.limbs[i + j] = value as u64;
+                    carry = value >> 64;
+                } else if value != 0 {
+                    return None;
+                }
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+
+        Some(output)
+    }
+
+    pub fn resize<const M: usize>(&self) -> Option<BigUint<M>> {
+        if self.bits() > 64 * M {
+            return None;
+        }
+
+        let mut output: BigUint<M> = BigUint::zero();
+        for i in 0..N.min(M) {
+            output.limbs[i] = self.limbs[i];
+        }
+
+        Some(output)
+    }
+
+    pub fn shl(&self, shift: usize) -> Self {
+        // bits shifted beyond the width are dropped
+        let mut output: Self = Self::zero();
+        let limbs: usize = shift / 64;
+        let bits: usize = shift % 64;
+        for i in (limbs..N).rev() {
+            output.limbs[i] = self.limbs[i - limbs] << bits;
+            if bits > 0 && i > limbs {
+                output.limbs[i] |= self.limbs[i - limbs - 1] >> (64 - bits);
+            }
+        }
+
+        output
+    }
+
+    pub fn shr(&self, shift: usize) -> Self {
+        let mut output: Self = Self::zero();
+        let limbs: usize = shift / 64;
+        let bits: usize = shift % 64;
+        for i in 0..N.saturating_sub(limbs) {
+            output.limbs[i] = self.limbs[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < N {
+                output.limbs[i] |= self.limbs[i + limbs + 1] << (64 - bits);
+            }
+        }
+
+        output
+    }
+
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by zero");
+
+        // shift and subtract, one quotient bit per step
+        let mut quotient: Self = Self::zero();
+        let mut remainder: Self = Self::zero();
+        for i in (0..self.bits()).rev() {
+            // remainder < divisor, so only a divisor with the top bit set lets 2 * remainder overflow
+            let carry: bool = remainder.bit(Self::BITS - 1);
+            remainder = remainder.shl(1);
+            remainder.limbs[0] |= self.bit(i) as u64;
+
+            if carry || remainder >= *divisor {
+                remainder = remainder.overflowing_sub(divisor).0;
+                quotient.set_bit(i);
+            }
+        }
+
+        (quotient, remainder)
+    }
+
+    pub fn rem(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
+
+    pub fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        // both inputs have to be below the modulus
+        let (sum, carry) = self.overflowing_add(other);
+        let (difference, borrow) = sum.overflowing_sub(modulus);
+
+        if carry || !borrow {
+            difference
+        } else {
+            sum
+        }
+    }
+
+    pub fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        // both inputs have to be below the modulus
+        let (difference, borrow) = self.overflowing_sub(other);
+
+        if borrow {
+            difference.overflowing_add(modulus).0
+        } else {
+            difference
+        }
+    }
+
+    pub fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        // double and add over the bits of other, works for every modulus,
+        // use Montgomery for repeated multiplications with an odd one
+        let x: Self = self.rem(modulus);
+        let mut output: Self = Self::zero();
+        for i in (0..other.bits()).rev() {
+            output = output.add_mod(&output, modulus);
+            if other.bit(i) {
+                output = output.add_mod(&x, modulus);
+            }
+        }
+
+        output
+    }
+
+    pub fn pow_mod(&self, exponent: &Self, modulus: &Self) -> Self {
+        if let Ok(montgomery) = Montgomery::new(modulus) {
+            let base: Self = montgomery.to_montgomery(self);
+            return montgomery.from_montgomery(&montgomery.pow(&base, exponent));
+        }
+
+        // square and multiply for even moduli
+        let mut output: Self = Self::one().rem(modulus);
+        for i in (0..exponent.bits()).rev() {
+            output = output.mul_mod(&output, modulus);
+            if exponent.bit(i) {
+                output = output.mul_mod(self, modulus);
+            }
+        }
+
+        output
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut a: Self = *self;
+        let mut b: Self = *other;
+        while !b.is_zero() {
+            let remainder: Self = a.rem(&b);
+            a = b;
+            b = remainder;
+        }
+
+        a
+    }
+
+    pub fn inverse_mod(&self, modulus: &Self) -> Option<Self> {
+        // extended Euclid, keeping x_0 * self = a and x_1 * self = b modulo the modulus,
+        // so the coefficients stay unsigned and even moduli (e.g. lcm(p - 1, q - 1)) work
+        let mut a: Self = self.rem(modulus);
+        let mut b: Self = *modulus;
+        let mut x_0: Self = Self::one().rem(modulus);
+        let mut x_1: Self = Self::zero();
+
+        while !b.is_zero() {
+            let (quotient, remainder) = a.div_rem(&b);
+            let next: Self = x_0.sub_mod(&x_1.mul_mod(&quotient, modulus), modulus);
+            a = b;
+            b = remainder;
+            x_0 = x_1;
+            x_1 = next;
+        }
+
+        if a == Self::one() {
+            Some(x_0)
+        } else {
+            None
+        }
+    }
+}
+
+impl<const N: usize> Ord for BigUint<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for i in (0..N).rev() {
+            match self.limbs[i].cmp(&other.limbs[i]) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl<const N: usize> PartialOrd for BigUint<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{BigUint, U256, U1024};
+    use super::super::BigIntError;
+
+    #[test]
+    fn parsing_and_formatting() {
+        // p = 2^255 - 19 of Curve25519
+        let p_decimal: U256 = U256::from_decimal("57896044618658097711785492504343953926634992332820282019728792003956564819949").unwrap();
+        let p_hex: U256 = U256::from_hex("0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed").unwrap();
+        assert_eq!(p_decimal, p_hex);
+        assert_eq!(p_hex.to_hex(), "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed");
+        assert_eq!(p_hex.to_decimal(), "57896044618658097711785492504343953926634992332820282019728792003956564819949");
+        assert_eq!(p_hex.bits(), 255);
+
+        let a: U256 = U256::from_hex("d7a3b0f1e69c1d08c3f2a6b91e4f5c2d8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d").unwrap();
+        assert_eq!(a.to_decimal(), "97536479745951380486245955796351946789536796910504351179783811624470088207421");
+        assert_eq!(U256::zero().to_decimal(), "0");
+        assert_eq!(U256::zero().to_hex(), "0");
+
+        assert_eq!(U256::from_hex("12g4"), Err(BigIntError::InvalidDigit));
+        assert_eq!(U256::from_decimal(""), Err(BigIntError::InvalidDigit));
+        // 2^256 does not fit into four limbs
+        assert_eq!(U256::from_hex(&format!("1{}", "0".repeat(64))), Err(BigIntError::Overflow));
+        assert_eq!(U256::from_decimal("115792089237316195423570985008687907853269984665640564039457584007913129639936"), Err(BigIntError::Overflow));
+    }
+
+    #[test]
+    fn byte_conversion() {
+        let value: U256 = U256::from_hex("0102030405060708090a").unwrap();
+        let bytes: Vec<u8> = value.to_be_bytes(12).unwrap();
+        assert_eq!(bytes, [0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(U256::from_be_bytes(&bytes).unwrap(), value);
+        assert_eq!(U256::from_le_bytes(&value.to_le_bytes(10).unwrap()).unwrap(), value);
+
+        assert_eq!(value.to_be_bytes(9), Err(BigIntError::Overflow));
+        assert_eq!(U256::from_be_bytes(&[0; 40]).unwrap(), U256::zero());
+        assert_eq!(U256::from_be_bytes(&[1; 33]), Err(BigIntError::Overflow));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a: U256 = U256::from_hex("d7a3b0f1e69c1d08c3f2a6b91e4f5c2d8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d").unwrap();
+        let b: U256 = U256::from_hex("1f2e3d4c5b6a79881726354453627180").unwrap();
+
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(quotient, U256::from_hex("6ea72d9d9555be8abbd88d1e8c0e085c0").unwrap());
+        assert_eq!(remainder, U256::from_hex("a0ea60c58b88e0a4015f4ab6b8eac3d").unwrap());
+        assert_eq!(quotient.checked_mul(&b).unwrap().overflowing_add(&remainder), (a, false));
+
+        let wide: BigUint<8> = a.resize::<8>().unwrap();
+        assert_eq!(wide.checked_mul(&b.resize::<8>().unwrap()).unwrap().to_hex(), "1a43c5796927636132cc829e3e9df40786516eed187221653e62c74dd671d373e12032fb5e3a7547aa06af8670a70b80");
+        assert!(a.checked_mul(&b).is_none());
+        assert!(wide.shl(200).resize::<4>().is_none());
+
+        assert_eq!(a.shr(100).shl(100).overflowing_add(&a.shl(156).shr(156)), (a, false));
+        assert_eq!(U256::one().shl(255).bits(), 256);
+        assert_eq!(U256::one().shl(256), U256::zero());
+        assert!(a > b);
+
+        assert_eq!(a.gcd(&b), U256::from_u64(3));
+        assert_eq!(b.gcd(&U256::zero()), b);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let a: U256 = U256::from_hex("d7a3b0f1e69c1d08c3f2a6b91e4f5c2d8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d").unwrap();
+        let b: U256 = U256::from_hex("1f2e3d4c5b6a79881726354453627180").unwrap();
+        let p: U256 = U256::from_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed").unwrap();
+        let p_plus_1: U256 = p.overflowing_add(&U256::one()).0;
+
+        // odd moduli go through Montgomery, even ones through square and multiply
+        assert_eq!(a.pow_mod(&b, &p).to_hex(), "1c16ea625ad0a9a30b88e8b04cd6260d823bc5f575a760cdec7497afc8d27aab");
+        assert_eq!(a.pow_mod(&b, &p.overflowing_sub(&U256::one()).0).to_hex(), "795bee28305639ada6f37dd9dd739cc28ba7eef8f35ecf6ef321f3320707d0d1");
+        assert_eq!(a.pow_mod(&U256::zero(), &U256::one()), U256::zero());
+
+        let inverse: U256 = a.inverse_mod(&p).unwrap();
+        assert_eq!(a.mul_mod(&inverse, &p), U256::one());
+        assert_eq!(a.rem(&p).add_mod(&p.overflowing_sub(&a.rem(&p)).0, &p), U256::zero());
+        assert_eq!(U256::from_u64(3).sub_mod(&U256::from_u64(5), &p), p.overflowing_sub(&U256::from_u64(2)).0);
+
+        // inverse of the RSA exponent modulo an even lambda
+        let lambda: U256 = U256::from_hex("8b2f3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f80").unwrap();
+        assert_eq!(U256::from_u64(65537).inverse_mod(&lambda).unwrap().to_hex(), "866deeee43219b266abd56e2267911a5a8f2cd6164ae79998c4a35554805f01");
+        assert!(U256::from_u64(2).inverse_mod(&lambda).is_none());
+        assert!(U256::from_u64(2).inverse_mod(&p_plus_1).is_none());
+    }
+
+    #[test]
+    fn pow_mod_1024() {
+        // m = 2^1024 - 105
+        let modulus: U1024 = U1024::zero().overflowing_sub(&U1024::from_u64(105)).0;
+        let base: U1024 = U1024::from_decimal(&"123456789".repeat(30)).unwrap();
+        let exponent: U1024 = U1024::from_decimal(&"987654321".repeat(28)).unwrap();
+
+        assert_eq!(base.to_hex(), "3bd3102dce325afe6bb6a3a4a127b263da292eb543754543016af9d62e4eea5843786f0f8cf2c6e021ac12c57d8725ed06eeb66239b2cd41ff5588b3622143df9b5e80ef810c080a44f75bf716a12b2b3372783f1856f6247a3f8437a2818f68b7c4ea13a5f57b73c72870b684045f15");
+        assert_eq!(base.pow_mod(&exponent, &modulus).to_hex(), "8a6ee8c19dded924cca0e5a22656eb5e73d7281bfe81361851fd7f229659e49fd296f60537f3f4cdeb4821b7f50d81885cf76d0264cbae83854433bc6ebe8d27c61b6b5906214d0bdb46342051ff6ca66e8974b1022b704ddc5fe71351e6201235ae56635e74013ed2968b208ce99421bf9d541e698faacd17b0f513c7bd60e6");
+    }
+}
@@ -0,0 +1,293 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf (10.2.1)
+*/
+
+#![allow(non_camel_case_types)]
+
+use super::DrbgError;
+
+use crate::crypto::{AES, Blocksize};
+
+// SP 800-90A Table 3: at most 2^48 requests between reseeds
+// and at most 2^19 bits per request.
+const RESEED_INTERVAL: u64 = 1 << 48;
+const MAX_BYTES_PER_REQUEST: usize = (1 << 19) / 8;
+const BLOCKLEN: usize = 16;
+
+pub struct CTR_DRBG {
+    blocksize: Blocksize,
+    cipher: AES,
+    v: [u8; 16],
+    reseed_counter: u64,
+    use_derivation_function: bool,
+    prediction_resistance: bool,
+}
+
+impl CTR_DRBG {
+    pub fn instantiate(
+        blocksize: Blocksize,
+        use_derivation_function: bool,
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization_string: &[u8],
+        prediction_resistance: bool,
+    ) -> Result<Self, DrbgError> {
+        let keylen: usize = CTR_DRBG::keylen(blocksize);
+
+        let mut drbg: CTR_DRBG = Self {
+            blocksize,
+            cipher: AES::new(&vec![0; keylen], blocksize),
+            v: [0; 16],
+            reseed_counter: 1,
+            use_derivation_function,
+            prediction_resistance,
+        };
+
+        let seed_material: Vec<u8> = if use_derivation_function {
+            if entropy_input.len() < keylen {
+                return Err(DrbgError::InsufficientEntropy);
+            }
+            drbg.block_cipher_df(&[entropy_input, nonce, personalization_string].concat())
+        } else {
+            // Without a derivation function the entropy input is the full seed
+            // and the nonce is not used (SP 800-90A 10.2.1.3.1).
+            if entropy_input.len() != drbg.seedlen() {
+                return Err(DrbgError::InsufficientEntropy);
+            }
+            drbg.xor_padded(entropy_input, personalization_string)?
+        };
+
+        drbg.update(&seed_material);
+
+        Ok(drbg)
+    }
+
+    pub fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        let seed_material: Vec<u8> = if self.use_derivation_function {
+            if entropy_input.len() < CTR_DRBG::keylen(self.blocksize) {
+                return Err(DrbgError::InsufficientEntropy);
+            }
+            self.block_cipher_df(&[entropy_input, additional_input].concat())
+        } else {
+            if entropy_input.len() != self.seedlen() {
+                return Err(DrbgError::InsufficientEntropy);
+            }
+            self.xor_padded(entropy_input, additional_input)?
+        };
+
+        self.update(&seed_material);
+        self.reseed_counter = 1;
+
+        Ok(())
+    }
+
+    pub fn generate(&mut self, requested_bytes: usize, additional_input: &[u8]) -> Result<Vec<u8>, DrbgError> {
+        if self.prediction_resistance {
+            return Err(DrbgError::PredictionResistanceRequired);
+        }
+
+        self.generate_bytes(requested_bytes, additional_input)
+    }
+
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        entropy_input: &[u8],
+        requested_bytes: usize,
+        additional_input: &[u8],
+    ) -> Result<Vec<u8>, DrbgError> {
+        // SP 800-90A 9.3.1 step 7.1: reseed with the additional input,
+        // which must not be used a second time for the generation.
+        self.reseed(entropy_input, additional_input)?;
+        self.generate_bytes(requested_bytes, &[])
+    }
+
+    fn generate_bytes(&mut self, requested_bytes: usize, additional_input: &[u8]) -> Result<Vec<u8>, DrbgError> {
+        if requested_bytes > MAX_BYTES_PER_REQUEST {
+            return Err(DrbgError::RequestTooLarge);
+        }
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(DrbgError::ReseedRequired);
+        }
+
+        let additional: Vec<u8> = if additional_input.is_empty() {
+            vec![0; self.seedlen()]
+        } else {
+            let additional: Vec<u8> = if self.use_derivation_function {
+                self.block_cipher_df(additional_input)
+            } else {
+                self.xor_padded(&vec![0; self.seedlen()], additional_input)?
+            };
+            self.update(&additional);
+            additional
+        };
+
+        let mut output: Vec<u8> = Vec::with_capacity(requested_bytes + BLOCKLEN);
+        while output.len() < requested_bytes {
+            CTR_DRBG::increment(&mut self.v);
+            output.extend_from_slice(&self.cipher.cipher(self.v));
+        }
+        output.truncate(requested_bytes);
+
+        self.update(&additional);
+        self.reseed_counter += 1;
+
+        Ok(output)
+    }
+
+    fn update(&mut self, provided_data: &[u8]) {
+        let keylen: usize = CTR_DRBG::keylen(self.blocksize);
+        let mut temp: Vec<u8> = Vec::with_capacity(self.seedlen() + BLOCKLEN);
+
+        while temp.len() < self.seedlen() {
+            CTR_DRBG::increment(&mut self.v);
+            temp.extend_from_slice(&self.cipher.cipher(self.v));
+        }
+        temp.truncate(self.seedlen());
+
+        for (t, p) in temp.iter_mut().zip(provided_data.iter()) {
+            *t ^= p;
+        }
+
+        self.cipher = AES::new(&temp[..keylen], self.blocksize);
+        self.v.copy_from_slice(&temp[keylen..]);
+    }
+
+    fn block_cipher_df(&self, input_string: &[u8]) -> Vec<u8> {
+        let keylen: usize = CTR_DRBG::keylen(self.blocksize);
+        let requested: usize = self.seedlen();
+
+        // S = L || N || input_string || 0x80, padded with zeros to a multiple of outlen
+        let mut s: Vec<u8> = Vec::new();
+        s.extend_from_slice(&(input_string.len() as u32).to_be_bytes());
+        s.extend_from_slice(&(requested as u32).to_be_bytes());
+        s.extend_from_slice(input_string);
+        s.push(0x80);
+        while !s.len().is_multiple_of(BLOCKLEN) {
+            s.push(0x00);
+        }
+
+        let key: Vec<u8> = (0..keylen as u8).collect();
+        let mut bcc_cipher: AES = AES::new(&key, self.blocksize);
+
+        let mut temp: Vec<u8> = Vec::with_capacity(keylen + 2 * BLOCKLEN);
+        let mut i: u32 = 0;
+        while temp.len() < keylen + BLOCKLEN {
+            let mut iv: [u8; 16] = [0; 16];
+            iv[..4].copy_from_slice(&i.to_be_bytes());
+
+            temp.extend_from_slice(&CTR_DRBG::bcc(&mut bcc_cipher, &[iv.as_slice(), &s].concat()));
+            i += 1;
+        }
+
+        let mut cipher: AES = AES::new(&temp[..keylen], self.blocksize);
+        let mut x: [u8; 16] = [0; 16];
+        x.copy_from_slice(&temp[keylen..keylen + BLOCKLEN]);
+
+        let mut output: Vec<u8> = Vec::with_capacity(requested + BLOCKLEN);
+        while output.len() < requested {
+            x = cipher.cipher(x);
+            output.extend_from_slice(&x);
+        }
+        output.truncate(requested);
+
+        output
+    }
+
+    fn bcc(cipher: &mut AES, data: &[u8]) -> [u8; 16] {
+        let mut chaining_value: [u8; 16] = [0; 16];
+
+        for block in data.chunks_exact(BLOCKLEN) {
+            for (c, b) in chaining_value.iter_mut().zip(block.iter()) {
+                *c ^= b;
+            }
+            chaining_value = cipher.cipher(chaining_value);
+        }
+
+        chaining_value
+    }
+
+    fn xor_padded(&self, seed: &[u8], input: &[u8]) -> Result<Vec<u8>, DrbgError> {
+        // Personalization string and additional input are padded
+        // with zeros to seedlen and must not be longer.
+        if input.len() > self.seedlen() {
+            return Err(DrbgError::RequestTooLarge);
+        }
+
+        let mut output: Vec<u8> = seed.to_vec();
+        for (o, i) in output.iter_mut().zip(input.iter()) {
+            *o ^= i;
+        }
+
+        Ok(output)
+    }
+
+    fn increment(v: &mut [u8; 16]) {
+        for byte in v.iter_mut().rev() {
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+    }
+
+    fn seedlen(&self) -> usize {
+        CTR_DRBG::keylen(self.blocksize) + BLOCKLEN
+    }
+
+    fn keylen(blocksize: Blocksize) -> usize {
+        match blocksize {
+            Blocksize::B128 => 16,
+            Blocksize::B192 => 24,
+            Blocksize::B256 => 32,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::CTR_DRBG;
+    use crate::crypto::Blocksize;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn aes256_no_df() {
+        // The NIST PQC known answer tests seed their AES-256 CTR_DRBG (no derivation
+        // function, no personalization string) with the bytes 0..48; the first 48
+        // bytes generated are the `seed` of count = 0 in every PQC KAT .rsp file.
+        let entropy: Vec<u8> = (0..48).collect();
+        let mut drbg = CTR_DRBG::instantiate(Blocksize::B256, false, &entropy, &[], &[], false).unwrap();
+
+        assert_eq!(
+            drbg.generate(48, &[]).unwrap(),
+            hex("061550234d158c5ec95595fe04ef7a25767f2e24cc2bc479d09d86dc9abcfde7056a8c266f9ef97ed08541dbd2e1ffa1"),
+        );
+    }
+
+    #[test]
+    fn cavp_aes128_df() {
+        // Test vector from the CAVP CTR_DRBG.rsp, [AES-128 use df] [PredictionResistance = False], COUNT = 0:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/random-number-generators
+        let mut drbg = CTR_DRBG::instantiate(
+            Blocksize::B128,
+            true,
+            &hex("890eb067acf7382eff80b0c73bc872c6"),
+            &hex("aad471ef3ef1d203"),
+            &[],
+            false,
+        ).unwrap();
+        drbg.generate(64, &[]).unwrap();
+
+        assert_eq!(
+            drbg.generate(64, &[]).unwrap(),
+            hex("a5514ed7095f64f3d0d3a5760394ab42062f373a25072a6ea6bcfd8489e94af6cf18659fea22ed1ca0a9e33f718b115ee536b12809c31b72b08ddd8be1910fa3"),
+        );
+    }
+
+    #[test]
+    fn wrong_entropy_length() {
+        assert!(CTR_DRBG::instantiate(Blocksize::B256, false, &[0; 32], &[], &[], false).is_err());
+        assert!(CTR_DRBG::instantiate(Blocksize::B256, true, &[0; 16], &[0; 16], &[], false).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+Implementation according to https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf (10.1.2)
+*/
+
+#![allow(non_camel_case_types)]
+
+use super::DrbgError;
+
+use crate::hash::{HMAC, HashType};
+
+// SP 800-90A Table 2: at most 2^48 requests between reseeds
+// and at most 2^19 bits per request.
+const RESEED_INTERVAL: u64 = 1 << 48;
+const MAX_BYTES_PER_REQUEST: usize = (1 << 19) / 8;
+
+pub struct HMAC_DRBG {
+    hmac: HMAC,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    reseed_counter: u64,
+    security_strength: usize,
+    prediction_resistance: bool,
+}
+
+impl HMAC_DRBG {
+    pub fn instantiate(
+        hash_type: HashType,
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization_string: &[u8],
+        prediction_resistance: bool,
+    ) -> Result<Self, DrbgError> {
+        // Sha256 and Sha512 support the highest security strength of 256 bits, Sha1 128 bits.
+        let (outlen, security_strength): (usize, usize) = match hash_type {
+            HashType::Sha1Type => (20, 16),
+            HashType::Sha256Type => (32, 32),
+            HashType::Sha512Type => (64, 32),
+        };
+
+        let mut drbg: HMAC_DRBG = Self {
+            hmac: HMAC::new(hash_type),
+            k: vec![0x00; outlen],
+            v: vec![0x01; outlen],
+            reseed_counter: 1,
+            security_strength,
+            prediction_resistance,
+        };
+
+        if entropy_input.len() < drbg.security_strength {
+            return Err(DrbgError::InsufficientEntropy);
+        }
+
+        let seed_material: Vec<u8> = [entropy_input, nonce, personalization_string].concat();
+        drbg.update(&seed_material);
+
+        Ok(drbg)
+    }
+
+    pub fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), DrbgError> {
+        if entropy_input.len() < self.security_strength {
+            return Err(DrbgError::InsufficientEntropy);
+        }
+
+        let seed_material: Vec<u8> = [entropy_input, additional_input].concat();
+        self.update(&seed_material);
+        self.reseed_counter = 1;
+
+        Ok(())
+    }
+
+    pub fn generate(&mut self, requested_bytes: usize, additional_input: &[u8]) -> Result<Vec<u8>, DrbgError> {
+        if self.prediction_resistance {
+            return Err(DrbgError::PredictionResistanceRequired);
+        }
+
+        self.generate_bytes(requested_bytes, additional_input)
+    }
+
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        entropy_input: &[u8],
+        requested_bytes: usize,
+        additional_input: &[u8],
+    ) -> Result<Vec<u8>, DrbgError> {
+        // SP 800-90A 9.3.1 step 7.1: reseed with the additional input,
+        // which must not be used a second time for the generation.
+        self.reseed(entropy_input, additional_input)?;
+        self.generate_bytes(requested_bytes, &[])
+    }
+
+    fn generate_bytes(&mut self, requested_bytes: usize, additional_input: &[u8]) -> Result<Vec<u8>, DrbgError> {
+        if requested_bytes > MAX_BYTES_PER_REQUEST {
+            return Err(DrbgError::RequestTooLarge);
+        }
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(DrbgError::ReseedRequired);
+        }
+
+        if !additional_input.is_empty() {
+            self.update(additional_input);
+        }
+
+        let mut output: Vec<u8> = Vec::with_capacity(requested_bytes + self.v.len());
+        while output.len() < requested_bytes {
+            self.v = self.hmac.digest(&self.k, &self.v).clone();
+            output.extend_from_slice(&self.v);
+        }
+        output.truncate(requested_bytes);
+
+        self.update(additional_input);
+        self.reseed_counter += 1;
+
+        Ok(output)
+    }
+
+    fn update(&mut self, provided_data: &[u8]) {
+        self.k = self.hmac.digest(&self.k, &[self.v.as_slice(), &[0x00], provided_data].concat()).clone();
+        self.v = self.hmac.digest(&self.k, &self.v).clone();
+
+        if provided_data.is_empty() {
+            return;
+        }
+
+        self.k = self.hmac.digest(&self.k, &[self.v.as_slice(), &[0x01], provided_data].concat()).clone();
+        self.v = self.hmac.digest(&self.k, &self.v).clone();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::HMAC_DRBG;
+    use crate::hash::HashType;
+    use crate::utils::testing::hex;
+
+    #[test]
+    fn cavp_sha256_vectors() {
+        // Test vectors from the CAVP HMAC_DRBG.rsp, [SHA-256] [PredictionResistance = False]:
+        // https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program/random-number-generators
+        fn check_hmac_drbg(entropy: &str, nonce: &str, additional_input: [&str; 2], expected: &str) {
+            let mut drbg = HMAC_DRBG::instantiate(HashType::Sha256Type, &hex(entropy), &hex(nonce), &[], false).unwrap();
+            drbg.generate(128, &hex(additional_input[0])).unwrap();
+
+            assert_eq!(drbg.generate(128, &hex(additional_input[1])).unwrap(), hex(expected));
+        }
+
+        check_hmac_drbg(
+            "ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488",
+            "659ba96c601dc69fc902940805ec0ca8",
+            ["", ""],
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc107694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8",
+        );
+        check_hmac_drbg(
+            "79737479ba4e7642a221fcfd1b820b134e9e3540a35bb48ffae29c20f5418ea3",
+            "3593259c092bef4129bc2c6c9e19f343",
+            ["", ""],
+            "cf5ad5984f9e43917aa9087380dac46e410ddc8a7731859c84e9d0f31bd43655b924159413e2293b17610f211e09f770f172b8fb693a35b85d3b9e5e63b1dc252ac0e115002e9bedfb4b5b6fd43f33b8e0eafb2d072e1a6fee1f159df9b51e6c8da737e60d5032dd30544ec51558c6f080bdbdab1de8a939e961e06b5f1aca37",
+        );
+        check_hmac_drbg(
+            "d3cc4d1acf3dde0c4bd2290d262337042dc632948223d3a2eaab87da44295fbd",
+            "0109b0e729f457328aa18569a9224921",
+            ["3c311848183c9a212a26f27f8c6647e40375e466a0857cc39c4e47575d53f1f6", "fcb9abd19ccfbccef88c9c39bfb3dd7b1c12266c9808992e305bc3cff566e4e4"],
+            "9c7b758b212cd0fcecd5daa489821712e3cdea4467b560ef5ddc24ab47749a1f1ffdbbb118f4e62fcfca3371b8fbfc5b0646b83e06bfbbab5fac30ea09ea2bc76f1ea568c9be0444b2cc90517b20ca825f2d0eccd88e7175538b85d90ab390183ca6395535d34473af6b5a5b88f5a59ee7561573337ea819da0dcc3573a22974",
+        );
+        check_hmac_drbg(
+            "f97a3cfd91faa046b9e61b9493d436c4931f604b22f1081521b3419151e8ff06",
+            "11f3a7d43595357d58120bd1e2dd8aed",
+            ["517289afe444a0fe5ed1a41dbbb5eb17150079bdd31e29cf2ff30034d8268e3b", "88028d29ef80b4e6f0fe12f91d7449fe75062682e89c571440c0c9b52c42a6e0"],
+            "c6871cff0824fe55ea7689a52229886730450e5d362da5bf590dcf9acd67fed4cb32107df5d03969a66b1f6494fdf5d63d5b4d0d34ea7399a07d0116126d0d518c7c55ba46e12f62efc8fe28a51c9d428e6d371d7397ab319fc73ded4722e5b4f30004032a6128df5e7497ecf82ca7b0a50e867ef6728a4f509a8c859087039c",
+        );
+    }
+
+    #[test]
+    fn insufficient_entropy() {
+        assert!(HMAC_DRBG::instantiate(HashType::Sha256Type, &[0; 16], &[0; 16], &[], false).is_err());
+    }
+}
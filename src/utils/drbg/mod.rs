@@ -0,0 +1,60 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+mod hmac_drbg;
+mod ctr_drbg;
+mod types;
+
+pub use hmac_drbg::HMAC_DRBG;
+pub use ctr_drbg::CTR_DRBG;
+
+pub use types::DrbgError;
+
+
+#[cfg(test)]
+mod tests {
+    use super::{HMAC_DRBG, CTR_DRBG, DrbgError};
+    use crate::crypto::Blocksize;
+    use crate::hash::HashType;
+
+    type Generate<D> = fn(&mut D, usize, &[u8]) -> Result<Vec<u8>, DrbgError>;
+    type Reseed<D> = fn(&mut D, &[u8], &[u8]) -> Result<(), DrbgError>;
+    type GenerateWithPredictionResistance<D> = fn(&mut D, &[u8], usize, &[u8]) -> Result<Vec<u8>, DrbgError>;
+
+    fn check_prediction_resistance<D>(
+        instantiate: fn(bool) -> D,
+        generate: Generate<D>,
+        reseed: Reseed<D>,
+        generate_with_prediction_resistance: GenerateWithPredictionResistance<D>,
+    ) {
+        let reseed_entropy: [u8; 32] = [0x22; 32];
+        let mut with_pr: D = instantiate(true);
+        let mut without_pr: D = instantiate(false);
+
+        assert_eq!(generate(&mut with_pr, 64, &[]), Err(DrbgError::PredictionResistanceRequired));
+
+        // A prediction resistant request equals an explicit reseed followed by a request.
+        let output: Vec<u8> = generate_with_prediction_resistance(&mut with_pr, &reseed_entropy, 100, b"add").unwrap();
+        reseed(&mut without_pr, &reseed_entropy, b"add").unwrap();
+
+        assert_eq!(output, generate(&mut without_pr, 100, &[]).unwrap());
+        assert_eq!(output.len(), 100);
+    }
+
+    #[test]
+    fn prediction_resistance() {
+        check_prediction_resistance(
+            |pr| HMAC_DRBG::instantiate(HashType::Sha512Type, &[0x11; 32], &[0x33; 16], b"pers", pr).unwrap(),
+            HMAC_DRBG::generate,
+            HMAC_DRBG::reseed,
+            HMAC_DRBG::generate_with_prediction_resistance,
+        );
+        check_prediction_resistance(
+            |pr| CTR_DRBG::instantiate(Blocksize::B256, true, &[0x11; 32], &[0x33; 16], b"pers", pr).unwrap(),
+            CTR_DRBG::generate,
+            CTR_DRBG::reseed,
+            CTR_DRBG::generate_with_prediction_resistance,
+        );
+    }
+}
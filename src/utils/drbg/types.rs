@@ -0,0 +1,15 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum DrbgError {
+    // The reseed counter exceeded the reseed interval (SP 800-90A 9.3.1 step 7).
+    ReseedRequired,
+    // Instantiated with prediction resistance, so every request needs fresh entropy.
+    PredictionResistanceRequired,
+    // Entropy input is shorter than the security strength, or has the wrong length.
+    InsufficientEntropy,
+    // More bytes were requested than allowed per generate call.
+    RequestTooLarge,
+}
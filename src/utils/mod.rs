@@ -2,4 +2,10 @@
 Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
 */
 
-pub mod rng;
\ No newline at end of file
+pub mod rng;
+pub mod drbg;
+pub mod bigint;
+pub mod base64;
+
+#[cfg(test)]
+pub mod testing;
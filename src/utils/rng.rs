@@ -2,12 +2,52 @@
 Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
 */
 
-use rand::Rng;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Read;
 
-pub fn fill_array(to_fill: &mut [u8; 32]) {
-    let mut rng: rand::rngs::ThreadRng = rand::thread_rng();
+use super::drbg::{HMAC_DRBG, DrbgError};
+use crate::hash::HashType;
+
+// Bytes requested from the DRBG per generate call, well below its 2^16 byte limit.
+const CHUNK_LENGTH: usize = 4096;
+
+thread_local! {
+    static DRBG: RefCell<Option<HMAC_DRBG>> = const { RefCell::new(None) };
+}
+
+fn entropy(length: usize) -> Vec<u8> {
+    let mut output: Vec<u8> = vec![0; length];
+
+    File::open("/dev/urandom")
+        .and_then(|mut source| source.read_exact(&mut output))
+        .expect("Entropy source unavailable!");
 
-    for i in 0..32 {
-        to_fill[i] = rng.gen();
-    }
-}
\ No newline at end of file
+    output
+}
+
+pub fn fill_bytes(to_fill: &mut [u8]) {
+    DRBG.with(|cell| {
+        let mut state = cell.borrow_mut();
+        let drbg: &mut HMAC_DRBG = state.get_or_insert_with(|| {
+            HMAC_DRBG::instantiate(HashType::Sha512Type, &entropy(32), &entropy(16), b"lightweight_blockchain rng", false)
+                .expect("Instantiating the DRBG failed!")
+        });
+
+        for chunk in to_fill.chunks_mut(CHUNK_LENGTH) {
+            let random: Vec<u8> = match drbg.generate(chunk.len(), &[]) {
+                Err(DrbgError::ReseedRequired) => {
+                    drbg.reseed(&entropy(32), &[]).expect("Reseeding the DRBG failed!");
+                    drbg.generate(chunk.len(), &[])
+                },
+                other => other,
+            }.expect("Generating random bytes failed!");
+
+            chunk.copy_from_slice(&random);
+        }
+    });
+}
+
+pub fn fill_array(to_fill: &mut [u8; 32]) {
+    fill_bytes(to_fill);
+}
@@ -0,0 +1,8 @@
+/* Author: Stefan Goetz, 2023
+Warranty Disclaimer: the software is provided "as is" without any warranties or conditions.
+*/
+
+// Decodes the hex strings the test vectors are written in.
+pub fn hex(input: &str) -> Vec<u8> {
+    (0..input.len()).step_by(2).map(|i| u8::from_str_radix(&input[i..i + 2], 16).unwrap()).collect()
+}